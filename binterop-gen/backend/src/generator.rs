@@ -1,258 +1,1119 @@
+use std::cell::RefCell;
 use std::collections::VecDeque;
 
 use binterop::{
-    field::Field,
-    schema::Schema,
+    Error,
+    constant::Constant,
+    dependency::check_alias_cycles,
+    field::{Bitfield, Field},
+    schema::{Schema, qualified_name, resolve_name, similar_names},
+    target::TargetLayout,
     types::{
         Type, TypeData,
+        alias::AliasType,
         array::ArrayType,
         data::DataType,
-        r#enum::EnumType,
+        r#enum::{EnumType, EnumVariant},
+        flags::{FlagsBit, FlagsType, is_flags_repr},
         function::{Arg, FunctionType},
+        inline::{InlineStringType, InlineVectorType},
+        map::MapType,
+        optional::OptionalType,
         pointer::PointerType,
-        primitives::PRIMITIVES,
-        union::UnionType,
+        primitives::{INTEGER_PRIMITIVE_NAMES, PRIMITIVES},
+        slice::SliceType,
+        union::{UnionType, UnionVariant},
         vector::VectorType,
     },
+    value::Value,
 };
 
-use crate::tokenizer::{self, Token};
+use case::CaseExt;
 
-fn lookup_type_data(
-    defined_type_name: &str,
-    defined_type: Type,
+use crate::optimization::assign_offsets;
+use crate::tokenizer::{self, BinaryOp, Expr, Literal, StructField, Token};
+
+// Generic structs are kept as templates and instantiated on use, with their parameters bound to
+// the type arguments of the use site. Names are resolved relative to the package of the scope
+#[derive(Copy, Clone)]
+struct Scope<'s, 'a> {
+    templates: &'s [(&'a str, &'s Token<'a>)],
+    bindings: &'s [(&'a str, (Type, usize))],
+    instances: &'s RefCell<Vec<Instance>>,
+    namespace: &'a str,
+    depth: usize,
+}
+
+// Templates can instantiate themselves with ever growing arguments, like a P<T> holding a
+// P<P<T>>*, which would never run out of new instances
+const MAX_INSTANTIATION_DEPTH: usize = 32;
+
+// Instances are identified by their template and type arguments rather than by their generated
+// name, which another type can already use. Names are stable across merged includes, indices aren't
+#[derive(PartialEq)]
+struct Instance {
+    template: String,
+    args: Vec<String>,
+}
+
+fn collect_templates<'s, 'a>(
+    tokens: &'s VecDeque<Token<'a>>,
+    templates: &mut Vec<(&'a str, &'s Token<'a>)>,
+) {
+    let mut namespace = "";
+    for token in tokens {
+        match token {
+            Token::Package(name) => namespace = name,
+            Token::Struct(_, _, _, params, _) if !params.is_empty() => {
+                templates.push((namespace, token));
+            }
+            Token::Include(_, tokens) => collect_templates(tokens, templates),
+            _ => {}
+        }
+    }
+}
+
+// Instances are named after their type arguments, so Pair<f32> becomes PairF32
+fn mangled_name(schema: &Schema, r#type: Type, index: usize) -> String {
+    let inner = |inner_type, inner_type_index| mangled_name(schema, inner_type, inner_type_index);
+
+    match r#type {
+        Type::Array => {
+            let array_type = schema.arrays[index];
+            format!(
+                "Array{}{}",
+                array_type.len,
+                inner(array_type.inner_type, array_type.inner_type_index)
+            )
+        }
+        Type::Vector => {
+            let vector_type = schema.vectors[index];
+            format!(
+                "Vector{}",
+                inner(vector_type.inner_type, vector_type.inner_type_index)
+            )
+        }
+        Type::Pointer => {
+            let pointer_type = schema.pointers[index];
+            format!(
+                "Pointer{}",
+                inner(pointer_type.inner_type, pointer_type.inner_type_index)
+            )
+        }
+        Type::Optional => {
+            let optional_type = schema.optionals[index];
+            format!(
+                "Optional{}",
+                inner(optional_type.inner_type, optional_type.inner_type_index)
+            )
+        }
+        Type::Map => {
+            let map_type = schema.maps[index];
+            format!(
+                "Map{}{}",
+                inner(map_type.key_type, map_type.key_type_index),
+                inner(map_type.value_type, map_type.value_type_index)
+            )
+        }
+        Type::Slice => {
+            let slice_type = schema.slices[index];
+            format!(
+                "Slice{}",
+                inner(slice_type.inner_type, slice_type.inner_type_index)
+            )
+        }
+        Type::InlineString => format!("String{}", schema.inline_strings[index].capacity),
+        Type::InlineVector => {
+            let inline_vector_type = schema.inline_vectors[index];
+            format!(
+                "InlineVector{}{}",
+                inline_vector_type.capacity,
+                inner(
+                    inline_vector_type.inner_type,
+                    inline_vector_type.inner_type_index
+                )
+            )
+        }
+        _ => schema
+            .qualified_type_name(r#type, index)
+            .replace('.', "_")
+            .to_camel(),
+    }
+}
+
+fn instantiate_template(
+    schema: &mut Schema,
+    scope: Scope,
+    name: &str,
+    args: &[(Type, usize)],
+) -> Result<(Type, usize), Error> {
+    let templates = scope
+        .templates
+        .iter()
+        .filter_map(|&(namespace, token)| match token {
+            Token::Struct(_, _, name, _, _) => Some((namespace, *name, (namespace, token))),
+            _ => None,
+        });
+    let Some((namespace, Token::Struct(doc, attributes, name, params, fields))) =
+        resolve_name(templates, name, scope.namespace)?
+    else {
+        let template_names = scope
+            .templates
+            .iter()
+            .filter_map(|&(namespace, token)| match token {
+                Token::Struct(_, _, name, _, _) => Some((namespace, *name)),
+                _ => None,
+            });
+
+        return Err(Error::UnknownType {
+            name: name.to_string(),
+            suggestions: similar_names(template_names, name),
+        });
+    };
+    if params.len() != args.len() {
+        return Err(Error::GenericArgumentCount {
+            name: name.to_string(),
+            expected: params.len(),
+            found: args.len(),
+        });
+    }
+
+    let instance = Instance {
+        template: qualified_name(namespace, name),
+        args: args
+            .iter()
+            .map(|&(r#type, index)| schema.qualified_type_name(r#type, index).to_string())
+            .collect(),
+    };
+    let instance_name = args.iter().fold(name.to_string(), |instance_name, arg| {
+        instance_name + &mangled_name(schema, arg.0, arg.1)
+    });
+    if let Some(index) = schema
+        .types
+        .iter()
+        .position(|data_type| data_type.name == instance_name && data_type.namespace == namespace)
+    {
+        if scope.instances.borrow().contains(&instance) {
+            return Ok((Type::Data, index));
+        }
+
+        return Err(Error::InstanceNameTaken {
+            instance: format!("{}<{}>", instance.template, instance.args.join(", ")),
+            name: qualified_name(namespace, &instance_name),
+        });
+    }
+    let depth = scope.depth + 1;
+    if depth > MAX_INSTANTIATION_DEPTH {
+        return Err(Error::InstantiationTooDeep {
+            name: format!("{}<{}>", instance.template, instance.args.join(", ")),
+            depth,
+        });
+    }
+    scope.instances.borrow_mut().push(instance);
+
+    let bindings = params
+        .iter()
+        .copied()
+        .zip(args.iter().copied())
+        .collect::<Vec<_>>();
+    let scope = Scope {
+        templates: scope.templates,
+        bindings: &bindings,
+        instances: scope.instances,
+        namespace,
+        depth,
+    };
+    let index = declare_data_type(schema, namespace, &instance_name, attributes);
+    build_data_type(schema, scope, index, doc, fields)?;
+
+    Ok((Type::Data, index))
+}
+
+// Data types are reserved before their fields are resolved, so that they can refer to themselves
+// and to each other through pointers, and instances of generic structs created by the fields can't
+// take their index
+fn declare_data_type(
     schema: &mut Schema,
+    namespace: &str,
+    name: &str,
+    attributes: &[(String, String)],
+) -> usize {
+    schema.types.push(DataType {
+        name: name.to_string(),
+        namespace: namespace.to_string(),
+        attributes: attributes.to_vec(),
+        ..Default::default()
+    });
+    schema.types.len() - 1
+}
+
+// Field offsets are assigned once the sizes of every type are known
+fn build_data_type(
+    schema: &mut Schema,
+    scope: Scope,
+    index: usize,
+    doc: &str,
+    fields: &[StructField],
+) -> Result<(), Error> {
+    let mut data_type = DataType {
+        doc: doc.to_string(),
+        ..schema.types[index].clone()
+    };
+    let name = data_type.name.clone();
+    // Consecutive bitfields of the same type share a storage unit while their bits fit
+    let mut bitfield_unit: Option<((Type, usize), u32)> = None;
+
+    for (doc, attributes, field_name, r#type, bit_width, default) in fields {
+        let (field_type, field_type_index) =
+            lookup_type(schema, scope, r#type).map_err(|err| err.in_member(&name, field_name))?;
+        let default = default
+            .as_ref()
+            .map(|(literal, text)| {
+                default_value(
+                    schema,
+                    scope.namespace,
+                    field_type,
+                    field_type_index,
+                    literal,
+                )?
+                .ok_or_else(|| Error::InvalidDefault {
+                    field_type: schema
+                        .qualified_type_name(field_type, field_type_index)
+                        .to_string(),
+                    value: text.trim().to_string(),
+                })
+            })
+            .transpose()
+            .map_err(|err| err.in_member(&name, field_name))?;
+
+        let mut field = Field::new(
+            field_name,
+            field_type,
+            field_type_index,
+            0,
+            0,
+            attributes.as_slice(),
+        )
+        .with_doc(doc);
+        if let Some(default) = default {
+            field = field.with_default(default);
+        }
+
+        let Some(bit_width) = *bit_width else {
+            bitfield_unit = None;
+            data_type.fields.push(field);
+            continue;
+        };
+
+        let field_type_name = schema.type_name(field_type, field_type_index);
+        let is_integer = field_type == Type::Primitive
+            && INTEGER_PRIMITIVE_NAMES.contains(&field_type_name.as_ref());
+        let storage_bits = match is_integer {
+            true => schema.try_type_size(field_type, field_type_index)? as u32 * 8,
+            false => 0,
+        };
+        if !is_integer || bit_width == 0 || bit_width > storage_bits {
+            return Err(Error::InvalidBitfield {
+                field_type: field_type_name.to_string(),
+                bit_width,
+            }
+            .in_member(&name, field_name));
+        }
+        if let Some(value) = field.default.as_ref().and_then(Value::as_integer) {
+            let (min, max) = if field_type_name.starts_with('i') {
+                (-(1 << (bit_width - 1)), (1 << (bit_width - 1)) - 1)
+            } else {
+                (0, (1 << bit_width) - 1)
+            };
+            if value < min || value > max {
+                return Err(Error::InvalidDefault {
+                    field_type: format!("{field_type_name}:{bit_width}"),
+                    value: value.to_string(),
+                }
+                .in_member(&name, field_name));
+            }
+        }
+
+        match &mut bitfield_unit {
+            Some((unit_type, used_bits))
+                if *unit_type == (field_type, field_type_index)
+                    && *used_bits + bit_width <= storage_bits =>
+            {
+                field = field.with_bitfield(Bitfield::new(*used_bits, bit_width));
+                *used_bits += bit_width;
+            }
+            _ => {
+                bitfield_unit = Some(((field_type, field_type_index), bit_width));
+                field = field.with_bitfield(Bitfield::new(0, bit_width));
+            }
+        }
+
+        data_type.fields.push(field);
+    }
+
+    schema.types[index] = data_type;
+    Ok(())
+}
+
+fn evaluate(schema: &Schema, namespace: &str, expr: &Expr) -> Result<i128, Error> {
+    match expr {
+        Expr::Literal(value) => Ok(*value),
+        Expr::Constant(name) => {
+            let candidates = schema.constants.iter().map(|constant| {
+                (
+                    constant.namespace.as_str(),
+                    constant.name.as_str(),
+                    constant.value,
+                )
+            });
+
+            resolve_name(candidates, name, namespace)?.ok_or_else(|| Error::UnknownConstant {
+                name: name.to_string(),
+                available: schema
+                    .constants
+                    .iter()
+                    .map(|constant| qualified_name(&constant.namespace, &constant.name))
+                    .collect(),
+            })
+        }
+        Expr::Binary(lhs, op, rhs) => {
+            let (lhs, rhs) = (
+                evaluate(schema, namespace, lhs)?,
+                evaluate(schema, namespace, rhs)?,
+            );
+            let result = match op {
+                BinaryOp::Add => lhs.checked_add(rhs),
+                BinaryOp::Sub => lhs.checked_sub(rhs),
+                BinaryOp::Mul => lhs.checked_mul(rhs),
+                BinaryOp::Div if rhs == 0 => {
+                    return Err(Error::InvalidConstantExpression {
+                        reason: "division by zero".to_string(),
+                    });
+                }
+                BinaryOp::Div => lhs.checked_div(rhs),
+            };
+
+            result.ok_or_else(|| Error::InvalidConstantExpression {
+                reason: "arithmetic overflow".to_string(),
+            })
+        }
+    }
+}
+
+// Defaults are stored as values of the field type, looking through aliases. Types without a literal
+// in every generated language, like 128 bit integers, can't have defaults
+fn default_value(
+    schema: &Schema,
+    namespace: &str,
+    r#type: Type,
+    index: usize,
+    literal: &Literal,
+) -> Result<Option<Value>, Error> {
+    let value = match (r#type, literal) {
+        (Type::Alias, literal) => {
+            let alias_type = &schema.aliases[index];
+            return default_value(
+                schema,
+                namespace,
+                alias_type.inner_type,
+                alias_type.inner_type_index,
+                literal,
+            );
+        }
+        (Type::Enum, Literal::Variant(enum_name, variant_name)) => {
+            if schema.try_resolve_type(enum_name, namespace)? != Some((Type::Enum, index)) {
+                return Ok(None);
+            }
+
+            schema.enums[index]
+                .variant_by_name(variant_name)
+                .map(|variant| Value::Enum(variant.name.clone()))
+        }
+        (Type::Primitive, literal) => match (PRIMITIVES.name_of(index), literal) {
+            (Some("bool"), Literal::Bool(value)) => Some(Value::Bool(*value)),
+            (Some("f32"), Literal::Float(value)) => Some(Value::F32(*value as f32)),
+            (Some("f64"), Literal::Float(value)) => Some(Value::F64(*value)),
+            (Some("f32"), Literal::Integer(expr)) => {
+                Some(Value::F32(evaluate(schema, namespace, expr)? as f32))
+            }
+            (Some("f64"), Literal::Integer(expr)) => {
+                Some(Value::F64(evaluate(schema, namespace, expr)? as f64))
+            }
+            (Some(name), Literal::Integer(expr)) if !name.ends_with("128") => {
+                Value::from_integer(name, evaluate(schema, namespace, expr)?).ok()
+            }
+            // Qualified constants look like enum variants to the tokenizer
+            (Some(name), Literal::Variant(qualifier, constant_name)) if !name.ends_with("128") => {
+                let constant = format!("{qualifier}.{constant_name}");
+                let value = evaluate(schema, namespace, &Expr::Constant(&constant))?;
+
+                Value::from_integer(name, value).ok()
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+
+    Ok(value)
+}
+
+fn lookup_type(
+    schema: &mut Schema,
+    scope: Scope,
     r#type: &tokenizer::Type,
-) -> Option<TypeData> {
+) -> Result<(Type, usize), Error> {
     match r#type {
         tokenizer::Type::Named(name) => {
-            if *name == defined_type_name {
-                let recursive_type_data = match defined_type {
-                    Type::Data => TypeData::new(schema.types.len(), Type::Data, 0, false),
-                    Type::Union => TypeData::new(schema.unions.len(), Type::Union, 0, false),
-                    Type::Function => {
-                        TypeData::new(schema.functions.len(), Type::Function, 0, false)
-                    }
-                    _ => unreachable!("Cannot define {defined_type:?}!"),
-                };
-
-                return Some(recursive_type_data);
+            if let Some((_, bound)) = scope.bindings.iter().find(|(param, _)| param == name) {
+                return Ok(*bound);
             }
 
             if let Some(index) = PRIMITIVES.index_of(name) {
-                return schema.type_data(index, Type::Primitive).ok();
-            }
-            if let Some(index) = schema.types.iter().position(|r#type| &r#type.name == name) {
-                return schema.type_data(index, Type::Data).ok();
+                return Ok((Type::Primitive, index));
             }
-            if let Some(index) = schema.enums.iter().position(|r#type| &r#type.name == name) {
-                return schema.type_data(index, Type::Enum).ok();
-            }
-            if let Some(index) = schema.unions.iter().position(|r#type| &r#type.name == name) {
-                return schema.type_data(index, Type::Union).ok();
-            }
-            if let Some(index) = schema
-                .functions
-                .iter()
-                .position(|r#type| &r#type.name == name)
-            {
-                return schema.type_data(index, Type::Function).ok();
+            if let Some(resolved) = schema.try_resolve_type(name, scope.namespace)? {
+                return Ok(resolved);
             }
             if name == &"String" {
-                return schema.type_data(0, Type::String).ok();
+                return Ok((Type::String, 0));
             }
+
+            Err(Error::UnknownType {
+                name: name.to_string(),
+                suggestions: schema.similar_type_names(name),
+            })
         }
-        tokenizer::Type::Array(inner_type, size) => {
-            let inner_type_data =
-                lookup_type_data(defined_type_name, defined_type, schema, &inner_type)?;
+        tokenizer::Type::Generic(name, args) => {
+            let args = args
+                .iter()
+                .map(|arg| lookup_type(schema, scope, arg))
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            instantiate_template(schema, scope, name, &args)
+        }
+        tokenizer::Type::Array(inner_type, len) => {
+            let len = evaluate(schema, scope.namespace, len)?;
+            let size = &usize::try_from(len).map_err(|_| Error::InvalidArrayLength { len })?;
+            let (inner_type, inner_type_index) = lookup_type(schema, scope, inner_type)?;
             let index = schema
                 .arrays
                 .iter()
                 .position(|array| {
-                    array.inner_type == inner_type_data.r#type
-                        && array.inner_type_index == inner_type_data.index
+                    array.inner_type == inner_type
+                        && array.inner_type_index == inner_type_index
                         && array.len == *size
                 })
-                .or_else(|| {
+                .unwrap_or_else(|| {
                     let index = schema.arrays.len();
-                    schema.arrays.push(ArrayType::new(
-                        inner_type_data.r#type,
-                        inner_type_data.index,
-                        *size,
-                    ));
-                    Some(index)
-                })?;
+                    schema
+                        .arrays
+                        .push(ArrayType::new(inner_type, inner_type_index, *size));
+                    index
+                });
 
-            return schema.type_data(index, Type::Array).ok();
+            Ok((Type::Array, index))
         }
         tokenizer::Type::Vector(inner_type) => {
-            let inner_type_data =
-                lookup_type_data(defined_type_name, defined_type, schema, &inner_type)?;
+            let (inner_type, inner_type_index) = lookup_type(schema, scope, inner_type)?;
             let index = schema
                 .vectors
                 .iter()
                 .position(|vector| {
-                    vector.inner_type == inner_type_data.r#type
-                        && vector.inner_type_index == inner_type_data.index
+                    vector.inner_type == inner_type && vector.inner_type_index == inner_type_index
                 })
-                .or_else(|| {
+                .unwrap_or_else(|| {
                     let index = schema.vectors.len();
-                    schema.vectors.push(VectorType::new(
-                        inner_type_data.r#type,
-                        inner_type_data.index,
-                    ));
-                    Some(index)
-                })?;
+                    schema
+                        .vectors
+                        .push(VectorType::new(inner_type, inner_type_index));
+                    index
+                });
 
-            return schema.type_data(index, Type::Vector).ok();
+            Ok((Type::Vector, index))
         }
         tokenizer::Type::Pointer(pointee_type) => {
-            let pointee_type_data =
-                lookup_type_data(defined_type_name, defined_type, schema, &pointee_type)?;
+            let (pointee_type, pointee_type_index) = lookup_type(schema, scope, pointee_type)?;
             let index = schema
                 .pointers
                 .iter()
                 .position(|pointer| {
-                    pointer.inner_type == pointee_type_data.r#type
-                        && pointer.inner_type_index == pointee_type_data.index
+                    pointer.inner_type == pointee_type
+                        && pointer.inner_type_index == pointee_type_index
                 })
-                .or_else(|| {
+                .unwrap_or_else(|| {
                     let index = schema.pointers.len();
-                    schema.pointers.push(PointerType::new(
-                        pointee_type_data.r#type,
-                        pointee_type_data.index,
-                    ));
-                    Some(index)
-                })?;
+                    schema
+                        .pointers
+                        .push(PointerType::new(pointee_type, pointee_type_index));
+                    index
+                });
 
-            return schema.type_data(index, Type::Pointer).ok();
+            Ok((Type::Pointer, index))
+        }
+        tokenizer::Type::Optional(inner_type) => {
+            let (inner_type, inner_type_index) = lookup_type(schema, scope, inner_type)?;
+            let index = schema
+                .optionals
+                .iter()
+                .position(|optional| {
+                    optional.inner_type == inner_type
+                        && optional.inner_type_index == inner_type_index
+                })
+                .unwrap_or_else(|| {
+                    let index = schema.optionals.len();
+                    schema
+                        .optionals
+                        .push(OptionalType::new(inner_type, inner_type_index));
+                    index
+                });
+
+            Ok((Type::Optional, index))
+        }
+        tokenizer::Type::Slice(inner_type) => {
+            let (inner_type, inner_type_index) = lookup_type(schema, scope, inner_type)?;
+            let index = schema
+                .slices
+                .iter()
+                .position(|slice| {
+                    slice.inner_type == inner_type && slice.inner_type_index == inner_type_index
+                })
+                .unwrap_or_else(|| {
+                    let index = schema.slices.len();
+                    schema
+                        .slices
+                        .push(SliceType::new(inner_type, inner_type_index));
+                    index
+                });
+
+            Ok((Type::Slice, index))
+        }
+        tokenizer::Type::Map(key_type, value_type) => {
+            let (key_type, key_type_index) = lookup_type(schema, scope, key_type)?;
+            let (value_type, value_type_index) = lookup_type(schema, scope, value_type)?;
+            let map_type = MapType::new(key_type, key_type_index, value_type, value_type_index);
+            let index = schema
+                .maps
+                .iter()
+                .position(|map| map == &map_type)
+                .unwrap_or_else(|| {
+                    schema.maps.push(map_type);
+                    schema.maps.len() - 1
+                });
+
+            Ok((Type::Map, index))
+        }
+        tokenizer::Type::InlineString(capacity) => {
+            let inline_string_type = InlineStringType::new(*capacity);
+            let index = schema
+                .inline_strings
+                .iter()
+                .position(|inline_string| inline_string == &inline_string_type)
+                .unwrap_or_else(|| {
+                    schema.inline_strings.push(inline_string_type);
+                    schema.inline_strings.len() - 1
+                });
+
+            Ok((Type::InlineString, index))
+        }
+        tokenizer::Type::InlineVector(inner_type, capacity) => {
+            let (inner_type, inner_type_index) = lookup_type(schema, scope, inner_type)?;
+            let inline_vector_type = InlineVectorType::new(inner_type, inner_type_index, *capacity);
+            let index = schema
+                .inline_vectors
+                .iter()
+                .position(|inline_vector| inline_vector == &inline_vector_type)
+                .unwrap_or_else(|| {
+                    schema.inline_vectors.push(inline_vector_type);
+                    schema.inline_vectors.len() - 1
+                });
+
+            Ok((Type::InlineVector, index))
         }
     }
+}
+
+// Enums and flags don't refer to other types, so they are built as soon as they are declared
+fn build_enum_type(
+    namespace: &str,
+    doc: &str,
+    attributes: &[(String, String)],
+    name: &str,
+    repr: Option<&str>,
+    variants: &[(String, &str, Option<i128>)],
+) -> Result<EnumType, Error> {
+    let mut enum_type = EnumType::default_with_name(name);
+    enum_type.namespace = namespace.to_string();
+    enum_type.doc = doc.to_string();
+    enum_type.attributes = attributes.to_vec();
+
+    if let Some(repr) = repr {
+        enum_type.repr_index = PRIMITIVES
+            .index_of(repr)
+            .filter(|_| INTEGER_PRIMITIVE_NAMES.contains(&repr))
+            .ok_or_else(|| Error::InvalidEnumRepr {
+                enum_name: name.to_string(),
+                repr: repr.to_string(),
+            })?;
+    }
+
+    // Variants without an explicit value continue counting from the previous one, like in C
+    let mut next_value = 0;
+    for (doc, variant_name, value) in variants {
+        let value = value.unwrap_or(next_value);
+        next_value = value + 1;
 
-    None
+        let mut variant = EnumVariant::new(variant_name, value);
+        variant.doc = doc.clone();
+        enum_type.variants.push(variant);
+    }
+
+    Ok(enum_type)
 }
 
-pub fn generate_schema<'a>(tokens: &VecDeque<Token<'a>>) -> Result<Schema, String> {
-    let mut schema = Schema::default();
+fn build_flags_type(
+    namespace: &str,
+    doc: &str,
+    attributes: &[(String, String)],
+    name: &str,
+    repr: Option<&str>,
+    values: &[(String, &str, Option<u128>)],
+) -> Result<FlagsType, Error> {
+    let mut flags_type = FlagsType::default_with_name(name);
+    flags_type.namespace = namespace.to_string();
+    flags_type.doc = doc.to_string();
+    flags_type.attributes = attributes.to_vec();
 
-    let mut recursive_fields_indices = Vec::new();
+    if let Some(repr) = repr {
+        flags_type.repr_index = PRIMITIVES
+            .index_of(repr)
+            .filter(|_| is_flags_repr(repr))
+            .ok_or_else(|| Error::InvalidFlagsRepr {
+                flags_name: name.to_string(),
+                repr: repr.to_string(),
+            })?;
+    }
 
-    for token in tokens {
-        match token {
-            Token::Struct(attributes, struct_name, fields) => {
-                let mut data_type = DataType {
-                    name: struct_name.to_string(),
-                    attributes: attributes.clone(),
-                    ..Default::default()
-                };
-                let mut current_offset = 0;
-
-                for (attributes, field_name, r#type) in fields {
-                    if let tokenizer::Type::Named(type_name) = r#type
-                        && type_name == struct_name
-                    {
-                        recursive_fields_indices.push(data_type.fields.len());
-                    }
-
-                    let type_data = lookup_type_data(struct_name, Type::Data, &mut schema, &r#type)
-                        .ok_or(format!(
-                            "Failed to lookup type {type:?} for field {field_name}"
-                        ))?;
-
-                    let field = Field::new(
-                        field_name,
-                        type_data.r#type,
-                        type_data.index,
-                        current_offset,
-                        0,
-                        attributes.as_slice(),
-                    );
-                    current_offset += type_data.size;
-
-                    data_type.fields.push(field);
-                }
-                schema.types.push(data_type);
+    // Flags without an explicit value take the bit after the previous one
+    let mut next_bit = 0;
+    for (doc, flag_name, value) in values {
+        let bit = match *value {
+            Some(value) if value.is_power_of_two() => value.trailing_zeros(),
+            Some(value) => {
+                return Err(Error::InvalidFlagValue {
+                    flags_name: name.to_string(),
+                    flag: flag_name.to_string(),
+                    value,
+                });
+            }
+            None => next_bit,
+        };
+        next_bit = bit + 1;
+
+        let mut flags_bit = FlagsBit::new(flag_name, bit);
+        flags_bit.doc = doc.clone();
+        flags_type.bits.push(flags_bit);
+    }
+
+    Ok(flags_type)
+}
+
+fn build_constant(
+    schema: &Schema,
+    namespace: &str,
+    doc: &str,
+    attributes: &[(String, String)],
+    name: &str,
+    type_name: &str,
+    value: &Expr,
+) -> Result<Constant, Error> {
+    if schema
+        .constants
+        .iter()
+        .any(|constant| constant.namespace == namespace && constant.name == name)
+    {
+        return Err(Error::ConflictingConstant {
+            name: qualified_name(namespace, name),
+        });
+    }
 
-                let data_type = schema.types.last().unwrap();
-                let self_size = data_type.size(&schema);
-                let data_type = schema.types.last_mut().unwrap();
+    let type_index = PRIMITIVES
+        .index_of(type_name)
+        .ok_or_else(|| Error::InvalidConstantType {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+        })?;
+    let value = evaluate(schema, namespace, value)?;
+    let mut constant = Constant::new(name, type_index, value, attributes);
+    constant.namespace = namespace.to_string();
+    constant.doc = doc.to_string();
+    constant.check(schema)?;
+
+    Ok(constant)
+}
+
+fn expr_constants<'a>(expr: &Expr<'a>, names: &mut Vec<&'a str>) {
+    match expr {
+        Expr::Literal(_) => {}
+        Expr::Constant(name) => names.push(name),
+        Expr::Binary(lhs, _, rhs) => {
+            expr_constants(lhs, names);
+            expr_constants(rhs, names);
+        }
+    }
+}
+
+// Constants can refer to constants declared after them, so every constant is built after the ones
+// its value refers to
+fn build_constants(schema: &mut Schema, constants: &[(&str, &Token)]) -> Result<(), Error> {
+    let mut built = vec![false; constants.len()];
+    let mut visiting = Vec::new();
+
+    for index in 0..constants.len() {
+        build_constant_after_dependencies(schema, constants, index, &mut built, &mut visiting)?;
+    }
+
+    Ok(())
+}
+
+fn build_constant_after_dependencies(
+    schema: &mut Schema,
+    constants: &[(&str, &Token)],
+    index: usize,
+    built: &mut [bool],
+    visiting: &mut Vec<usize>,
+) -> Result<(), Error> {
+    let constant_name = |index: usize| match constants[index] {
+        (namespace, Token::Const(_, _, name, _, _)) => qualified_name(namespace, name),
+        _ => unreachable!(),
+    };
+    let (namespace, Token::Const(doc, attributes, name, type_name, value)) = constants[index]
+    else {
+        unreachable!()
+    };
+
+    if built[index] {
+        return Ok(());
+    }
+    if let Some(position) = visiting.iter().position(|&visiting| visiting == index) {
+        return Err(Error::RecursiveConstant {
+            name: constant_name(index),
+            path: visiting[position..]
+                .iter()
+                .chain([&index])
+                .map(|&index| constant_name(index))
+                .collect(),
+        });
+    }
+
+    visiting.push(index);
+    let mut references = Vec::new();
+    expr_constants(value, &mut references);
+    for reference in references {
+        let pending = constants
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &(namespace, token))| match token {
+                Token::Const(_, _, name, _, _) => Some((namespace, *name, Some(index))),
+                _ => None,
+            });
+        let existing = schema
+            .constants
+            .iter()
+            .map(|constant| (constant.namespace.as_str(), constant.name.as_str(), None));
+
+        if let Some(Some(dependency)) = resolve_name(pending.chain(existing), reference, namespace)?
+        {
+            build_constant_after_dependencies(schema, constants, dependency, built, visiting)?;
+        }
+    }
+    visiting.pop();
+
+    let constant = build_constant(schema, namespace, doc, attributes, name, type_name, value)?;
+    schema.constants.push(constant);
+    built[index] = true;
+
+    Ok(())
+}
+
+fn build_union_type(
+    schema: &mut Schema,
+    scope: Scope,
+    index: usize,
+    doc: &str,
+    variants: &[(String, &str, Option<tokenizer::Type>)],
+) -> Result<(), Error> {
+    let mut union_type = UnionType {
+        doc: doc.to_string(),
+        ..schema.unions[index].clone()
+    };
 
-                for &field_index in &recursive_fields_indices {
-                    let next_fields = data_type.fields[field_index + 1..].iter_mut();
-                    for field in next_fields {
-                        field.offset += self_size;
-                    }
+    union_type.variants = variants
+        .iter()
+        .map(|(doc, variant_name, r#type)| {
+            // Bare type names used to declare payloads, so don't silently turn them into unit
+            // variants
+            if r#type.is_none()
+                && lookup_type(schema, scope, &tokenizer::Type::Named(variant_name)).is_ok()
+            {
+                return Err(Error::UnitVariantNamesType {
+                    name: variant_name.to_string(),
                 }
-                recursive_fields_indices.clear();
+                .in_member(&union_type.name, variant_name));
             }
-            Token::Enum(attributes, name, variants) => {
-                let enum_type = EnumType {
-                    name: name.to_string(),
-                    variants: variants.iter().map(|variant| variant.to_string()).collect(),
-                    attributes: attributes.to_vec(),
-                };
-
-                schema.enums.push(enum_type);
+
+            let payload = r#type
+                .as_ref()
+                .map(|r#type| {
+                    lookup_type(schema, scope, r#type)
+                        .map_err(|err| err.in_member(&union_type.name, variant_name))
+                })
+                .transpose()?
+                .map(|(payload_type, payload_index)| (payload_index, payload_type));
+
+            let mut variant = UnionVariant::new(variant_name, payload);
+            variant.doc = doc.clone();
+            Ok(variant)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    schema.unions[index] = union_type;
+    Ok(())
+}
+
+// Arguments are stored with their sizes, which are only known once offsets are assigned, so they
+// start out with a size of zero and are updated by update_function_args
+fn build_function_type(
+    schema: &mut Schema,
+    scope: Scope,
+    index: usize,
+    doc: &str,
+    args: &[(&str, tokenizer::Type)],
+    return_type: Option<&tokenizer::Type>,
+) -> Result<(), Error> {
+    let mut function_type = FunctionType {
+        doc: doc.to_string(),
+        ..schema.functions[index].clone()
+    };
+    let name = function_type.name.clone();
+
+    function_type.args = args
+        .iter()
+        .map(|(arg_name, r#type)| {
+            let (arg_type, arg_type_index) =
+                lookup_type(schema, scope, r#type).map_err(|err| err.in_member(&name, arg_name))?;
+            Ok(Arg::new(
+                arg_name.to_string(),
+                Some(TypeData::new(arg_type_index, arg_type, 0, false)),
+            ))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    function_type.return_type = return_type
+        .map(|return_type| {
+            lookup_type(schema, scope, return_type)
+                .map(|(return_type, return_type_index)| {
+                    TypeData::new(return_type_index, return_type, 0, false)
+                })
+                .map_err(|err| err.in_member(&name, "return"))
+        })
+        .transpose()?;
+
+    schema.functions[index] = function_type;
+    Ok(())
+}
+
+fn update_function_args(schema: &mut Schema) -> Result<(), Error> {
+    for index in 0..schema.functions.len() {
+        let function_type = &schema.functions[index];
+        let type_data = |type_data: TypeData| schema.type_data(type_data.index, type_data.r#type);
+
+        let args = function_type
+            .args
+            .iter()
+            .map(|arg| {
+                let arg_type = arg.r#type.map(type_data).transpose();
+                Ok(Arg::new(arg.name.clone(), arg_type?))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let return_type = function_type.return_type.map(type_data).transpose()?;
+
+        schema.functions[index].args = args;
+        schema.functions[index].return_type = return_type;
+    }
+
+    Ok(())
+}
+
+fn declare_type(
+    schema: &mut Schema,
+    namespace: &str,
+    token: &Token,
+) -> Result<Option<(Type, usize)>, Error> {
+    let name = match token {
+        Token::Struct(_, _, name, params, _) if params.is_empty() => name,
+        Token::Enum(_, _, name, _, _)
+        | Token::Union(_, _, name, _)
+        | Token::Flags(_, _, name, _, _)
+        | Token::Alias(_, _, name, _, _)
+        | Token::Function(_, name, _, _) => name,
+        _ => return Ok(None),
+    };
+    if schema
+        .named_types()
+        .any(|(type_namespace, type_name, _)| type_namespace == namespace && &type_name == name)
+    {
+        return Err(Error::DuplicateType {
+            name: qualified_name(namespace, name),
+        });
+    }
+
+    let declared = match token {
+        Token::Struct(_, attributes, name, _, _) => {
+            let index = declare_data_type(schema, namespace, name, attributes);
+            (Type::Data, index)
+        }
+        Token::Enum(doc, attributes, name, repr, variants) => {
+            let enum_type = build_enum_type(namespace, doc, attributes, name, *repr, variants)?;
+            schema.enums.push(enum_type);
+            (Type::Enum, schema.enums.len() - 1)
+        }
+        Token::Union(_, attributes, name, _) => {
+            schema.unions.push(UnionType {
+                name: name.to_string(),
+                namespace: namespace.to_string(),
+                attributes: attributes.clone(),
+                ..Default::default()
+            });
+            (Type::Union, schema.unions.len() - 1)
+        }
+        Token::Flags(doc, attributes, name, repr, values) => {
+            let flags_type = build_flags_type(namespace, doc, attributes, name, *repr, values)?;
+            schema.flags.push(flags_type);
+            (Type::Flags, schema.flags.len() - 1)
+        }
+        Token::Alias(_, attributes, name, _, is_distinct) => {
+            let mut alias_type = AliasType::new(
+                name,
+                Type::Primitive,
+                0,
+                *is_distinct,
+                attributes.as_slice(),
+            );
+            alias_type.namespace = namespace.to_string();
+            schema.aliases.push(alias_type);
+            (Type::Alias, schema.aliases.len() - 1)
+        }
+        _ => {
+            let mut function_type = FunctionType::default_with_name(name);
+            function_type.namespace = namespace.to_string();
+            schema.functions.push(function_type);
+            (Type::Function, schema.functions.len() - 1)
+        }
+    };
+
+    Ok(Some(declared))
+}
+
+// Types are resolved in two passes. Every type of the file is declared first, so that the second
+// pass can resolve references regardless of the order of declarations
+pub fn generate_schema<'a>(
+    tokens: &VecDeque<Token<'a>>,
+    target: &TargetLayout,
+) -> Result<Schema, Error> {
+    generate_schema_with_instances(tokens, target, &RefCell::default())
+}
+
+// Includes share the instances table with the including file, so an instance created by both is
+// merged instead of being reported as taking the name of another type
+fn generate_schema_with_instances<'a>(
+    tokens: &VecDeque<Token<'a>>,
+    target: &TargetLayout,
+    instances: &RefCell<Vec<Instance>>,
+) -> Result<Schema, Error> {
+    let mut schema = Schema {
+        target: target.clone(),
+        ..Default::default()
+    };
+    let mut templates = Vec::new();
+    collect_templates(tokens, &mut templates);
+
+    // Included files are generated on their own and merged before anything in this file is declared
+    for token in tokens {
+        if let Token::Include(path, tokens) = token {
+            generate_schema_with_instances(tokens, target, instances)
+                .and_then(|include_schema| schema.merge(&include_schema))
+                .map_err(|err| Error::InInclude {
+                    path: path.clone(),
+                    error: Box::new(err),
+                })?;
+        }
+    }
+
+    let mut declarations = Vec::new();
+    let mut constants = Vec::new();
+    let mut namespace = "";
+    for token in tokens {
+        match token {
+            Token::Package(name) => namespace = name,
+            Token::Const(..) => constants.push((namespace, token)),
+            token => {
+                if let Some((r#type, index)) = declare_type(&mut schema, namespace, token)? {
+                    declarations.push((namespace, token, r#type, index));
+                }
             }
-            Token::Union(attributes, name, variants) => {
-                let mut union_type = UnionType {
-                    name: name.to_string(),
-                    attributes: attributes.clone(),
-                    ..Default::default()
-                };
-                let possible_types = variants
-                    .into_iter()
-                    .map(|variant| {
-                        let variant_type_data = lookup_type_data(
-                            name,
-                            Type::Union,
-                            &mut schema,
-                            &tokenizer::Type::Named(variant),
-                        )
-                        .ok_or(format!(
-                            "Failed to lookup type named {variant} in union {name}"
-                        ))?;
-
-                        Ok((variant_type_data.index, variant_type_data.r#type))
-                    })
-                    .collect::<Result<Vec<_>, String>>()?;
-
-                union_type.possible_types = possible_types;
-
-                schema.unions.push(union_type);
+        }
+    }
+
+    // Constants only refer to other constants, and array lengths and defaults need their values
+    build_constants(&mut schema, &constants)?;
+
+    // Aliases are defined first, as field defaults look through them to the aliased type
+    let (aliases, declarations): (Vec<_>, Vec<_>) = declarations
+        .into_iter()
+        .partition(|&(_, _, r#type, _)| r#type == Type::Alias);
+    for (namespace, token, _, index) in aliases {
+        let Token::Alias(doc, _, _, r#type, _) = token else {
+            continue;
+        };
+        let scope = Scope {
+            templates: &templates,
+            bindings: &[],
+            instances,
+            namespace,
+            depth: 0,
+        };
+
+        let (inner_type, inner_type_index) = lookup_type(&mut schema, scope, r#type)?;
+        let alias_type = &mut schema.aliases[index];
+        alias_type.inner_type = inner_type;
+        alias_type.inner_type_index = inner_type_index;
+        alias_type.doc = doc.clone();
+    }
+    check_alias_cycles(&schema)?;
+
+    for (namespace, token, _, index) in declarations {
+        let scope = Scope {
+            templates: &templates,
+            bindings: &[],
+            instances,
+            namespace,
+            depth: 0,
+        };
+
+        match token {
+            Token::Struct(doc, _, _, _, fields) => {
+                build_data_type(&mut schema, scope, index, doc, fields)?
             }
-            Token::Include(path, tokens) => {
-                let mut include_schema = generate_schema(tokens)
-                    .map_err(|err| format!("Failed to generate schema for {path:?}! Err: {err}"))?;
-                schema.append(&mut include_schema);
+            Token::Union(doc, _, _, variants) => {
+                build_union_type(&mut schema, scope, index, doc, variants)?
             }
-            Token::Function(name, args, return_type) => {
-                let mut function_type = FunctionType::default_with_name(name);
-
-                function_type.args = args
-                    .into_iter()
-                    .map(|(arg_name, r#type)| {
-                        let type_data = lookup_type_data(name, Type::Function, &mut schema, r#type).ok_or(
-                        "Failed to lookup type {r#type:?} for arg {arg_name} in function {name}!",
-                    )?;
-                        Ok(Arg::new(arg_name.to_string(), Some(type_data)))
-                    })
-                    .collect::<Result<Vec<_>, String>>()?;
-                if let Some(return_type) = return_type {
-                    function_type.return_type = lookup_type_data(
-                        name,
-                        Type::Function,
-                        &mut schema,
-                        return_type,
-                    )
-                    .ok_or("Failed to lookup type {r#type:?} for return type of function {name}!")?
-                    .into();
-                }
-
-                schema.functions.push(function_type);
+            Token::Function(doc, _, args, return_type) => {
+                build_function_type(&mut schema, scope, index, doc, args, return_type.as_ref())?
             }
+            _ => {}
         }
     }
 
+    assign_offsets(&mut schema, false)?;
+    update_function_args(&mut schema)?;
     Ok(schema)
 }
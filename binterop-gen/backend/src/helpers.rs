@@ -6,6 +6,7 @@ use crate::optimization::{SchemaOptimizations, optimize_schema};
 use crate::tokenizer::Tokenizer;
 use crate::{TIME, generator};
 use binterop::schema::Schema;
+use binterop::target::TargetLayout;
 use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -15,6 +16,7 @@ pub fn generate_schema(
     file_path: Option<PathBuf>,
     definition_text: &str,
     optimizations: SchemaOptimizations,
+    target: &TargetLayout,
 ) -> Result<Schema, String> {
     let tokenizer_start = Instant::now();
     let mut tokenizer = Tokenizer::new(file_path.as_ref().map(PathBuf::as_path), definition_text);
@@ -23,11 +25,14 @@ pub fn generate_schema(
     let tokens = tokenizer.tokens().ok_or("Failed to tokenize input")?;
 
     let generator_start = Instant::now();
-    let mut schema = generator::generate_schema(&tokens)?;
+    let mut schema = generator::generate_schema(&tokens, target).map_err(|err| err.to_string())?;
     let generator_end = generator_start.elapsed();
 
     let optimization_start = Instant::now();
-    optimize_schema(&mut schema, optimizations);
+    optimize_schema(&mut schema, optimizations).map_err(|err| err.to_string())?;
+    schema
+        .update_fingerprints()
+        .map_err(|err| err.to_string())?;
     let optimization_end = optimization_start.elapsed();
 
     if *TIME {
@@ -67,8 +72,59 @@ pub fn generate_lang_files(
         .map_err(|err| format!("Failed to generate language files! Error: {err}"))
 }
 
+pub fn load_schema(path: &Path) -> Result<Schema, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read {path:?}! Error: {err:?}"))?;
+
+    if path.extension() == Some(OsStr::new("json")) {
+        serde_json::from_str(&text)
+            .map_err(|err| format!("{path:?}: Failed to deserialize schema! Error: {err:?}"))
+    } else {
+        generate_schema(
+            Some(path.into()),
+            &text,
+            SchemaOptimizations::default(),
+            &TargetLayout::default(),
+        )
+    }
+}
+
+pub fn check_compatibility(old_path: &Path, new_path: &Path) -> Result<bool, String> {
+    let old_schema = load_schema(old_path)?;
+    let new_schema = load_schema(new_path)?;
+
+    let changes = old_schema
+        .compare(&new_schema)
+        .map_err(|err| err.to_string())?;
+    for change in &changes {
+        println!("\t{change}");
+    }
+
+    Ok(changes.iter().any(|change| change.is_breaking()))
+}
+
 pub fn process_text(path: &Path, text: &str, args: &[String]) -> Result<(), String> {
-    let schema = generate_schema(Some(path.into()), text, SchemaOptimizations::default())?;
+    let target = match args.iter().find_map(|arg| arg.strip_prefix("--target=")) {
+        Some(target_name) => TargetLayout::by_name(target_name).map_err(|err| err.to_string())?,
+        None => TargetLayout::default(),
+    };
+
+    let schema = generate_schema(
+        Some(path.into()),
+        text,
+        SchemaOptimizations::default(),
+        &target,
+    )?;
+    schema.validate().map_err(|errors| {
+        let errors_text = errors
+            .iter()
+            .map(|err| format!("\t\t{err}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!("{path:?}: Schema failed validation!\n{errors_text}")
+    })?;
+
     let schema_serialized = serialize_schema(&schema);
 
     match schema_serialized {
@@ -4,8 +4,41 @@ import (
 	"unsafe"
 	"strings"
 	"fmt"
+	"bytes"
+	"sort"
+	"unicode/utf8"
 )
 
+// Go has no 16 byte aligned types, so these only line up when the field offset already is
+type Int128 struct {
+	Lo uint64
+	Hi int64
+}
+
+type Uint128 struct {
+	Lo uint64
+	Hi uint64
+}
+
+type Float16 uint16
+
+type Option[T any] struct {
+	IsSome bool
+	Value  T
+}
+
+func Some[T any](value T) Option[T] {
+	return Option[T]{IsSome: true, Value: value}
+}
+
+func None[T any]() Option[T] {
+	return Option[T]{}
+}
+
+func (o Option[T]) Get() (T, bool) {
+	return o.Value, o.IsSome
+}
+
 type Vector[T any] struct {
 	ptr      uint64
 	length   uint64
@@ -24,6 +57,94 @@ func (v *Vector[T]) String() string {
 	return sb.String()
 }
 
+type Slice[T any] struct {
+	ptr    uint64
+	length uint64
+}
+
+func SliceFromSlice[T any](slice []T) Slice[T] {
+	if len(slice) == 0 {
+		return Slice[T]{}
+	}
+
+	return Slice[T]{
+		ptr:    uint64(uintptr(unsafe.Pointer(&slice[0]))),
+		length: uint64(len(slice)),
+	}
+}
+
+func (s Slice[T]) AsSlice() []T {
+	if s.ptr == 0 || s.length == 0 {
+		return []T{}
+	}
+
+	return unsafe.Slice((*T)(unsafe.Pointer(uintptr(s.ptr))), s.length)
+}
+
+func (s Slice[T]) Length() uint64 {
+	return s.length
+}
+
+// B is the backing [N]byte array, like InlineString[[32]byte]
+type InlineString[B any] struct {
+	Length uint32
+	Bytes  B
+}
+
+func (s *InlineString[B]) Capacity() int {
+	return int(unsafe.Sizeof(s.Bytes))
+}
+
+func (s *InlineString[B]) AsBytes() []byte {
+	length := min(int(s.Length), s.Capacity())
+	return unsafe.Slice((*byte)(unsafe.Pointer(&s.Bytes)), length)
+}
+
+func (s *InlineString[B]) AsString() (string, error) {
+	data := s.AsBytes()
+	if !utf8.Valid(data) {
+		return "", fmt.Errorf("string contains invalid UTF-8")
+	}
+
+	return string(data), nil
+}
+
+func (s *InlineString[B]) SetString(str string) error {
+	if len(str) > s.Capacity() {
+		return fmt.Errorf("string of length %d doesn't fit into %d bytes", len(str), s.Capacity())
+	}
+
+	copy(unsafe.Slice((*byte)(unsafe.Pointer(&s.Bytes)), s.Capacity()), str)
+	s.Length = uint32(len(str))
+	return nil
+}
+
+// B is the backing [N]T array, like InlineVector[uint16, [16]uint16]
+type InlineVector[T any, B any] struct {
+	Length uint32
+	Items  B
+}
+
+func (v *InlineVector[T, B]) Capacity() int {
+	var zero T
+	return int(unsafe.Sizeof(v.Items) / unsafe.Sizeof(zero))
+}
+
+func (v *InlineVector[T, B]) AsSlice() []T {
+	length := min(int(v.Length), v.Capacity())
+	return unsafe.Slice((*T)(unsafe.Pointer(&v.Items)), length)
+}
+
+func (v *InlineVector[T, B]) Push(elem T) bool {
+	if int(v.Length) >= v.Capacity() {
+		return false
+	}
+
+	unsafe.Slice((*T)(unsafe.Pointer(&v.Items)), v.Capacity())[v.Length] = elem
+	v.Length++
+	return true
+}
+
 type String struct {
 	data Vector[byte]
 }
@@ -138,3 +259,38 @@ func (v *Vector[T]) GetVectorData() unsafe.Pointer {
 func (s *String) GetStringData() unsafe.Pointer {
 	return unsafe.Pointer(uintptr(s.data.ptr))
 }
+
+type MapEntry[K any, V any] struct {
+	Key   K
+	Value V
+}
+
+type Map[K any, V any] struct {
+	entries Vector[MapEntry[K, V]]
+}
+
+func (m *Map[K, V]) Entries() []MapEntry[K, V] {
+	return m.entries.AsSlice()
+}
+
+func (m *Map[K, V]) Length() uint64 {
+	return m.entries.Length()
+}
+
+// Entries are sorted by key, so compare has to order keys the same way the writer did
+func (m *Map[K, V]) Get(key K, compare func(a, b K) int) (V, bool) {
+	entries := m.Entries()
+	index, found := sort.Find(len(entries), func(i int) int {
+		return compare(key, entries[i].Key)
+	})
+	if !found {
+		var zero V
+		return zero, false
+	}
+
+	return entries[index].Value, true
+}
+
+func CompareStrings(a, b String) int {
+	return bytes.Compare(a.AsBytes(), b.AsBytes())
+}
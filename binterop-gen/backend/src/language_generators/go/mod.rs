@@ -1,42 +1,73 @@
 use std::{
+    collections::BTreeSet,
     fs,
     io::ErrorKind,
     path::{Path, PathBuf},
 };
 
 use binterop::{
+    constant::Constant,
     schema::Schema,
-    types::{Type, r#enum::EnumType, function::FunctionType, union::UnionType},
+    types::{
+        Type,
+        alias::AliasType,
+        r#enum::{EnumType, EnumVariant},
+        flags::FlagsType,
+        function::FunctionType,
+        union::UnionType,
+    },
+    value::Value,
 };
 use case::CaseExt;
 
-use crate::language_generators::{LanguageGenerator, SourceFile};
+use crate::language_generators::{
+    LanguageGenerator, SourceFile, declared_layout, doc_comment, referenced_namespaces,
+    scalar_literal, write_output_files,
+};
 
 use super::LanguageGeneratorState;
 
 #[derive(Default)]
-pub struct GoLanguageGenerator {}
+pub struct GoLanguageGenerator {
+    imports: BTreeSet<(String, String)>,
+}
 impl GoLanguageGenerator {
-    fn go_type_name(r#type: Type, type_index: usize, schema: &Schema) -> String {
+    fn go_type_name(r#type: Type, type_index: usize, schema: &Schema, namespace: &str) -> String {
         match r#type {
             Type::Primitive => {
-                let type_name = schema.type_name(r#type, type_index);
-                match type_name {
-                    type_name if type_name.contains("i") => type_name.replace("i", "int"),
-                    type_name if type_name.contains("u") => type_name.replace("u", "uint"),
-                    type_name if type_name.contains("f") => type_name.replace("f", "float"),
-                    _ => type_name.to_string(),
-                }
+                let type_name = match schema.type_name(r#type, type_index).as_ref() {
+                    "i8" => "int8",
+                    "u8" => "uint8",
+                    "i16" => "int16",
+                    "u16" => "uint16",
+                    "i32" => "int32",
+                    "u32" => "uint32",
+                    "i64" => "int64",
+                    "u64" => "uint64",
+                    "i128" => "binterop.Int128",
+                    "u128" => "binterop.Uint128",
+                    "isize" => "int",
+                    "usize" => "uintptr",
+                    "f16" => "binterop.Float16",
+                    "f32" => "float32",
+                    "f64" => "float64",
+                    "char32" => "rune",
+                    "bool" => "bool",
+                    type_name => return type_name.to_string(),
+                };
+
+                type_name.to_string()
             }
             Type::Array => {
                 let array_type = schema.arrays[type_index];
                 format!(
                     "[{}]{}",
                     array_type.len,
-                    GoLanguageGenerator::go_type_name(
+                    Self::go_type_name(
                         array_type.inner_type,
                         array_type.inner_type_index,
-                        schema
+                        schema,
+                        namespace
                     ),
                 )
             }
@@ -46,10 +77,40 @@ impl GoLanguageGenerator {
                     vector_type.inner_type,
                     vector_type.inner_type_index,
                     schema,
+                    namespace,
                 );
 
                 format!("binterop.Vector[{inner_type_name}]")
             }
+            Type::Slice => {
+                let slice_type = schema.slices[type_index];
+                let inner_type_name = Self::go_type_name(
+                    slice_type.inner_type,
+                    slice_type.inner_type_index,
+                    schema,
+                    namespace,
+                );
+
+                format!("binterop.Slice[{inner_type_name}]")
+            }
+            Type::InlineString => format!(
+                "binterop.InlineString[[{}]byte]",
+                schema.inline_strings[type_index].capacity
+            ),
+            Type::InlineVector => {
+                let inline_vector_type = schema.inline_vectors[type_index];
+                let inner_type_name = Self::go_type_name(
+                    inline_vector_type.inner_type,
+                    inline_vector_type.inner_type_index,
+                    schema,
+                    namespace,
+                );
+
+                format!(
+                    "binterop.InlineVector[{inner_type_name}, [{}]{inner_type_name}]",
+                    inline_vector_type.capacity
+                )
+            }
             Type::String => "binterop.String".to_string(),
             Type::Pointer => {
                 let pointer_type = schema.pointers[type_index];
@@ -57,16 +118,155 @@ impl GoLanguageGenerator {
                     pointer_type.inner_type,
                     pointer_type.inner_type_index,
                     schema,
+                    namespace,
                 );
 
                 format!("*{}", inner_type_name)
             }
-            _ => schema.type_name(r#type, type_index).to_string(),
+            Type::Optional => {
+                let optional_type = schema.optionals[type_index];
+                let inner_type_name = Self::go_type_name(
+                    optional_type.inner_type,
+                    optional_type.inner_type_index,
+                    schema,
+                    namespace,
+                );
+
+                format!("binterop.Option[{inner_type_name}]")
+            }
+            Type::Map => {
+                let map_type = schema.maps[type_index];
+                let key_type_name = Self::go_type_name(
+                    map_type.key_type,
+                    map_type.key_type_index,
+                    schema,
+                    namespace,
+                );
+                let value_type_name = Self::go_type_name(
+                    map_type.value_type,
+                    map_type.value_type_index,
+                    schema,
+                    namespace,
+                );
+
+                format!("binterop.Map[{key_type_name}, {value_type_name}]")
+            }
+            _ => {
+                let type_name = schema.type_name(r#type, type_index);
+                let type_namespace = schema.type_namespace(r#type, type_index);
+                if type_namespace == namespace {
+                    return type_name.to_string();
+                }
+
+                format!("{}.{type_name}", Self::package_alias(type_namespace))
+            }
+        }
+    }
+
+    // Namespaces are packages in subdirectories of the root package, imported under an alias
+    // derived from their full name
+    fn package_alias(namespace: &str) -> String {
+        if namespace.is_empty() {
+            "root".to_string()
+        } else {
+            namespace.replace('.', "_")
+        }
+    }
+
+    fn package_path(namespace: &str) -> PathBuf {
+        namespace.split('.').collect()
+    }
+
+    fn add_imports(&mut self, schema: &Schema, namespace: &str, r#type: Type, type_index: usize) {
+        let mut namespaces = BTreeSet::new();
+        referenced_namespaces(schema, r#type, type_index, &mut namespaces);
+
+        for imported_namespace in namespaces {
+            if imported_namespace != namespace {
+                self.imports
+                    .insert((namespace.to_string(), imported_namespace));
+            }
+        }
+    }
+
+    fn go_literal(
+        &mut self,
+        schema: &Schema,
+        namespace: &str,
+        value: &Value,
+        r#type: Type,
+        type_index: usize,
+    ) -> Result<String, String> {
+        match (r#type, value) {
+            (Type::Alias, value) => {
+                let alias_type = &schema.aliases[type_index];
+                let inner_text = self.go_literal(
+                    schema,
+                    namespace,
+                    value,
+                    alias_type.inner_type,
+                    alias_type.inner_type_index,
+                )?;
+
+                if alias_type.is_distinct {
+                    let alias_name = Self::go_type_name(r#type, type_index, schema, namespace);
+                    Ok(format!("{alias_name}({inner_text})"))
+                } else {
+                    Ok(inner_text)
+                }
+            }
+            // Enum variants are package level constants, so only the package of the enum is kept
+            (Type::Enum, Value::Enum(variant_name)) => {
+                self.add_imports(schema, namespace, r#type, type_index);
+                let enum_name = Self::go_type_name(r#type, type_index, schema, namespace);
+
+                Ok(match enum_name.rsplit_once('.') {
+                    Some((package, _)) => format!("{package}.{}", variant_name.to_camel()),
+                    None => variant_name.to_camel(),
+                })
+            }
+            (_, value) => {
+                scalar_literal(value).ok_or_else(|| format!("No Go literal for {value:?}"))
+            }
         }
     }
 
-    fn output_file_mut<'a>(state: &'a mut LanguageGeneratorState) -> &'a mut SourceFile {
-        &mut state.output_files[0]
+    // Every package checks the target it was generated for, as sub-packages can be imported
+    // without the root package
+    fn package_header(package_name: &str, pointer_width: usize) -> String {
+        format!(
+            "package {package_name}\n\nimport (\n\t\"binterop/helpers\"\n\t\"unsafe\"\n)\nvar _ = binterop.NewVector[byte]()\n\n// Fails to compile unless pointers are {pointer_width} bytes wide\nvar _ [0]struct{{}} = [unsafe.Sizeof(uintptr(0)) - {pointer_width}]struct{{}}{{}}\n\n"
+        )
+    }
+
+    fn output_file_mut<'a>(
+        state: &'a mut LanguageGeneratorState,
+        namespace: &str,
+    ) -> &'a mut SourceFile {
+        if namespace.is_empty() {
+            return &mut state.output_files[0];
+        }
+
+        let package_name = namespace.rsplit('.').next().unwrap();
+        let mut output_file_name = Self::package_path(namespace).join(package_name);
+        output_file_name.set_extension("go");
+
+        let index = match state
+            .output_files
+            .iter()
+            .position(|output_file| output_file.path == output_file_name)
+        {
+            Some(index) => index,
+            None => {
+                let header = Self::package_header(package_name, state.schema.target.pointer_width);
+                state
+                    .output_files
+                    .push(SourceFile::new(output_file_name).contents(header));
+                state.output_files.len() - 1
+            }
+        };
+
+        &mut state.output_files[index]
     }
 }
 impl LanguageGenerator for GoLanguageGenerator {
@@ -76,9 +276,10 @@ impl LanguageGenerator for GoLanguageGenerator {
 
         let package_name = output_file_name.file_stem().unwrap().to_str().unwrap();
 
-        let output_file =
-            SourceFile::new(&output_file_name).contents(format!("package {package_name}\n\nimport (\n\t\"binterop/helpers\"\n)\nvar _ = binterop.NewVector[byte]()\n\n"));
-        state.output_files.push(output_file);
+        let header = Self::package_header(package_name, state.schema.target.pointer_width);
+        state
+            .output_files
+            .push(SourceFile::new(&output_file_name).contents(header));
 
         let go_mod_file = SourceFile::new("go.mod").contents(format!(
             "module {package_name}\n\ngo 1.24\n\nrequire binterop/helpers v0.0.0\n"
@@ -88,36 +289,189 @@ impl LanguageGenerator for GoLanguageGenerator {
         Ok(())
     }
 
+    fn generate_constant(
+        &mut self,
+        state: &mut LanguageGeneratorState,
+        constant: &Constant,
+    ) -> Result<(), String> {
+        let mut name = constant.name.clone();
+        if let Some(first) = name.get_mut(..1) {
+            first.make_ascii_uppercase();
+        }
+
+        // 128 bit integers are structs in Go, so their constants stay untyped
+        let type_annotation = if constant.type_name().ends_with("128") {
+            String::new()
+        } else {
+            format!(
+                " {}",
+                Self::go_type_name(
+                    Type::Primitive,
+                    constant.type_index,
+                    state.schema,
+                    &constant.namespace
+                )
+            )
+        };
+
+        let output = &mut Self::output_file_mut(state, &constant.namespace).content;
+        output.push_str(&format!(
+            "{}const {name}{type_annotation} = {}\n\n",
+            doc_comment(&constant.doc, "//"),
+            constant.value
+        ));
+
+        Ok(())
+    }
+
     fn generate_data_type(
         &mut self,
         state: &mut LanguageGeneratorState,
         data_type: &binterop::types::data::DataType,
     ) -> Result<(), String> {
         let mut fields_text = String::new();
+        let mut accessors_text = String::new();
+        let mut defaults_text = String::new();
+        let mut default_setters_text = String::new();
+        let mut bitfield_units = 0;
+
+        // Go has no packing or alignment directives, so only layouts that padding and a zero sized
+        // field of a wider integer can reproduce are supported
+        let layout = declared_layout(state.schema, data_type).map_err(|err| err.to_string())?;
+        if layout.is_packed && !layout.is_natural {
+            return Err(format!(
+                "Go can't represent the packed layout of {}",
+                data_type.name
+            ));
+        }
+        match layout.align {
+            Some(align) if align > 8 => {
+                return Err(format!(
+                    "Go can't align {} to {align} bytes",
+                    data_type.name
+                ));
+            }
+            Some(align) => fields_text.push_str(&format!("\t_ [0]uint{}\n", align * 8)),
+            None => {}
+        }
 
-        for field in &data_type.fields {
-            let type_data = state.schema.type_data(field.type_index, field.r#type)?;
+        for (field, &padding) in data_type.fields.iter().zip(&layout.paddings) {
+            if padding != 0 {
+                fields_text.push_str(&format!("\t_ [{padding}]byte\n"));
+            }
+
+            let type_data = state
+                .schema
+                .type_data(field.type_index, field.r#type)
+                .map_err(|err| err.to_string())?;
 
             if !state.is_generated(&type_data) {
                 self.generate_from_type_and_index(state, field.r#type, field.type_index)?;
             }
+            self.add_imports(
+                state.schema,
+                &data_type.namespace,
+                field.r#type,
+                field.type_index,
+            );
+
+            let field_type_name = Self::go_type_name(
+                field.r#type,
+                field.type_index,
+                state.schema,
+                &data_type.namespace,
+            );
+
+            let default_text = field
+                .default
+                .as_ref()
+                .map(|default| {
+                    self.go_literal(
+                        state.schema,
+                        &data_type.namespace,
+                        default,
+                        field.r#type,
+                        field.type_index,
+                    )
+                })
+                .transpose()?;
+            match (&default_text, field.bitfield) {
+                (Some(default_text), None) => defaults_text
+                    .push_str(&format!("\t\t{}: {default_text},\n", field.name.to_camel())),
+                (Some(default_text), Some(_)) => default_setters_text.push_str(&format!(
+                    "\tresult.Set{}({default_text})\n",
+                    field.name.to_camel()
+                )),
+                (None, _) => {}
+            }
 
-            let field_type_name = Self::go_type_name(field.r#type, field.type_index, &state.schema);
+            let field_doc = doc_comment(&field.doc, "\t//");
+            let Some(bitfield) = field.bitfield else {
+                fields_text.push_str(&format!(
+                    "{field_doc}\t{} {}\n",
+                    field.name.to_camel(),
+                    field_type_name
+                ));
+                continue;
+            };
+
+            if !field.shares_storage() {
+                bitfield_units += 1;
+                fields_text.push_str(&format!(
+                    "\tBitfield{} {field_type_name}\n",
+                    bitfield_units - 1
+                ));
+            }
 
-            fields_text.push_str(&format!(
-                "\t{} {}\n",
-                field.name.to_camel(),
-                field_type_name
+            let storage = format!("Bitfield{}", bitfield_units - 1);
+            let storage_bits = state
+                .schema
+                .try_type_size(field.r#type, field.type_index)
+                .map_err(|err| err.to_string())?
+                * 8;
+            let left_shift = storage_bits as u32 - bitfield.bit_offset - bitfield.bit_width;
+            let right_shift = storage_bits as u32 - bitfield.bit_width;
+            let mask = bitfield.mask();
+            let name = field.name.to_camel();
+
+            accessors_text.push_str(&format!(
+                "{}func (s *{}) {name}() {field_type_name} {{\n\treturn (s.{storage} << {left_shift}) >> {right_shift}\n}}\n\n",
+                doc_comment(&field.doc, "//"),
+                data_type.name
+            ));
+            // Masks don't fit into signed constants, so they are applied in the unsigned type
+            let set_text = if field_type_name.starts_with("int") {
+                format!(
+                    "{field_type_name}((u{field_type_name}(s.{storage}) &^ {mask:#x}) | ((u{field_type_name}(value) << {}) & {mask:#x}))",
+                    bitfield.bit_offset
+                )
+            } else {
+                format!(
+                    "(s.{storage} &^ {mask:#x}) | ((value << {}) & {mask:#x})",
+                    bitfield.bit_offset
+                )
+            };
+            accessors_text.push_str(&format!(
+                "func (s *{}) Set{name}(value {field_type_name}) {{\n\ts.{storage} = {set_text}\n}}\n\n",
+                data_type.name
             ));
         }
 
-        let output = &mut Self::output_file_mut(state).content;
+        let output = &mut Self::output_file_mut(state, &data_type.namespace).content;
         output.push_str(&format!(
-            "type {} struct {{\n{fields_text}}}\n\n",
+            "{}type {} struct {{\n{fields_text}}}\n\n{accessors_text}",
+            doc_comment(&data_type.doc, "//"),
             data_type.name
         ));
+        // Fields without a default keep their zero value
+        if data_type.fields.iter().any(|field| field.default.is_some()) {
+            output.push_str(&format!(
+                "func New{0}() {0} {{\n\tresult := {0}{{\n{defaults_text}\t}}\n{default_setters_text}\treturn result\n}}\n\n",
+                data_type.name
+            ));
+        }
 
-        state.mark_generated(&data_type.name);
+        state.mark_generated(&data_type.namespace, &data_type.name);
         Ok(())
     }
 
@@ -126,26 +480,117 @@ impl LanguageGenerator for GoLanguageGenerator {
         state: &mut LanguageGeneratorState,
         enum_type: &EnumType,
     ) -> Result<(), String> {
-        let mut variants_iter = enum_type.variants.iter();
-        let first_variant = variants_iter
-            .next()
-            .ok_or("Enum has no variants")?
-            .to_camel();
-
-        let output = &mut Self::output_file_mut(state).content;
-        output.push_str(&format!("type {} int32\n", enum_type.name));
+        if enum_type.variants.is_empty() {
+            return Err("Enum has no variants".to_string());
+        }
+        let repr_name = Self::go_type_name(
+            Type::Primitive,
+            enum_type.repr_index,
+            state.schema,
+            &enum_type.namespace,
+        );
+
+        let output = &mut Self::output_file_mut(state, &enum_type.namespace).content;
         output.push_str(&format!(
-            "const (\n\t{first_variant} {} = iota\n",
-            &enum_type.name
+            "{}type {} {repr_name}\n",
+            doc_comment(&enum_type.doc, "//"),
+            enum_type.name
         ));
-
-        for variant in variants_iter {
-            output.push_str(&format!("\t{}\n", variant.to_camel()));
+        output.push_str("const (\n");
+
+        for variant in &enum_type.variants {
+            output.push_str(&format!(
+                "{}\t{} {} = {}\n",
+                doc_comment(&variant.doc, "\t//"),
+                variant.name.to_camel(),
+                enum_type.name,
+                variant.value
+            ));
         }
 
         output.push_str(")\n\n");
 
-        state.mark_generated(&enum_type.name);
+        state.mark_generated(&enum_type.namespace, &enum_type.name);
+        Ok(())
+    }
+
+    fn generate_flags_type(
+        &mut self,
+        state: &mut LanguageGeneratorState,
+        flags_type: &FlagsType,
+    ) -> Result<(), String> {
+        let repr_name = Self::go_type_name(
+            Type::Primitive,
+            flags_type.repr_index,
+            state.schema,
+            &flags_type.namespace,
+        );
+        let name = &flags_type.name;
+
+        let output = &mut Self::output_file_mut(state, &flags_type.namespace).content;
+        output.push_str(&format!(
+            "{}type {name} {repr_name}\n",
+            doc_comment(&flags_type.doc, "//")
+        ));
+        if !flags_type.bits.is_empty() {
+            output.push_str("const (\n");
+            for bit in &flags_type.bits {
+                output.push_str(&format!(
+                    "{}\t{name}{} {name} = 1 << {}\n",
+                    doc_comment(&bit.doc, "\t//"),
+                    bit.name.to_camel(),
+                    bit.bit
+                ));
+            }
+            output.push_str(")\n");
+        }
+        output.push_str(&format!(
+            "\nfunc (f {name}) Has(flags {name}) bool {{\n\treturn f&flags == flags\n}}\n\nfunc (f *{name}) Set(flags {name}) {{\n\t*f |= flags\n}}\n\nfunc (f *{name}) Clear(flags {name}) {{\n\t*f &^= flags\n}}\n\nfunc (f *{name}) Toggle(flags {name}) {{\n\t*f ^= flags\n}}\n\n"
+        ));
+
+        state.mark_generated(&flags_type.namespace, name);
+        Ok(())
+    }
+
+    fn generate_alias_type(
+        &mut self,
+        state: &mut LanguageGeneratorState,
+        alias_type: &AliasType,
+    ) -> Result<(), String> {
+        let inner_type_data = state
+            .schema
+            .type_data(alias_type.inner_type_index, alias_type.inner_type)
+            .map_err(|err| err.to_string())?;
+        if !state.is_generated(&inner_type_data) {
+            self.generate_from_type_and_index(
+                state,
+                alias_type.inner_type,
+                alias_type.inner_type_index,
+            )?;
+        }
+        self.add_imports(
+            state.schema,
+            &alias_type.namespace,
+            alias_type.inner_type,
+            alias_type.inner_type_index,
+        );
+
+        let inner_type_name = Self::go_type_name(
+            alias_type.inner_type,
+            alias_type.inner_type_index,
+            state.schema,
+            &alias_type.namespace,
+        );
+
+        let output = &mut Self::output_file_mut(state, &alias_type.namespace).content;
+        output.push_str(&format!(
+            "{}type {} {}{inner_type_name}\n\n",
+            doc_comment(&alias_type.doc, "//"),
+            alias_type.name,
+            if alias_type.is_distinct { "" } else { "= " }
+        ));
+
+        state.mark_generated(&alias_type.namespace, &alias_type.name);
         Ok(())
     }
 
@@ -155,33 +600,44 @@ impl LanguageGenerator for GoLanguageGenerator {
         union_type: &UnionType,
     ) -> Result<(), String> {
         let mut variant_enum = EnumType::new(&format!("{}Variant", union_type.name), &[], &[]);
+        variant_enum.namespace = union_type.namespace.clone();
         variant_enum.variants = union_type
-            .possible_types
+            .variants
             .iter()
-            .map(|&(type_index, r#type)| {
-                format!(
-                    "{}Variant",
-                    state.schema.type_name(r#type, type_index).to_string()
-                )
+            .enumerate()
+            .map(|(tag, variant)| {
+                let mut enum_variant =
+                    EnumVariant::new(&format!("{}Variant", variant.name.to_camel()), tag as i128);
+                enum_variant.doc = variant.doc.clone();
+                enum_variant
             })
             .collect();
 
         self.generate_enum_type(state, &variant_enum)?;
 
-        let variant_size = union_type
-            .possible_types
-            .iter()
-            .map(|&(type_index, r#type)| state.schema.type_size(r#type, type_index).unwrap())
-            .max()
-            .unwrap();
+        let data_offset = union_type
+            .try_data_offset(state.schema)
+            .map_err(|err| err.to_string())?;
+        let data_size = union_type
+            .try_size(state.schema)
+            .map_err(|err| err.to_string())?
+            - data_offset;
+        let variant_padding = data_offset - size_of::<i32>();
 
-        let output = &mut Self::output_file_mut(state).content;
-        output.push_str(&format!("type {} struct {{\n", union_type.name));
+        let output = &mut Self::output_file_mut(state, &union_type.namespace).content;
+        output.push_str(&format!(
+            "{}type {} struct {{\n",
+            doc_comment(&union_type.doc, "//"),
+            union_type.name
+        ));
 
         output.push_str(&format!("  Variant {}Variant\n", union_type.name));
-        output.push_str(&format!("  Data [{variant_size}]byte\n}}\n\n"));
+        if variant_padding > 0 {
+            output.push_str(&format!("  _ [{variant_padding}]byte\n"));
+        }
+        output.push_str(&format!("  Data [{data_size}]byte\n}}\n\n"));
 
-        state.mark_generated(&union_type.name);
+        state.mark_generated(&union_type.namespace, &union_type.name);
         Ok(())
     }
 
@@ -190,12 +646,21 @@ impl LanguageGenerator for GoLanguageGenerator {
         state: &mut LanguageGeneratorState,
         function_type: &FunctionType,
     ) -> Result<(), String> {
-        for arg in &function_type.args {
-            let type_data = arg.r#type.unwrap();
-
+        for type_data in function_type
+            .args
+            .iter()
+            .map(|arg| arg.r#type.unwrap())
+            .chain(function_type.return_type)
+        {
             if !state.is_generated(&type_data) {
                 self.generate_from_type_and_index(state, type_data.r#type, type_data.index)?;
             }
+            self.add_imports(
+                state.schema,
+                &function_type.namespace,
+                type_data.r#type,
+                type_data.index,
+            );
         }
 
         let args_text = function_type
@@ -203,7 +668,12 @@ impl LanguageGenerator for GoLanguageGenerator {
             .iter()
             .map(|arg| {
                 let type_data = arg.r#type.unwrap();
-                let type_name = Self::go_type_name(type_data.r#type, type_data.index, state.schema);
+                let type_name = Self::go_type_name(
+                    type_data.r#type,
+                    type_data.index,
+                    state.schema,
+                    &function_type.namespace,
+                );
 
                 format!("{} {type_name}", arg.name)
             })
@@ -219,18 +689,20 @@ impl LanguageGenerator for GoLanguageGenerator {
                         return_type_data.r#type,
                         return_type_data.index,
                         state.schema,
+                        &function_type.namespace
                     )
                 )
             })
             .unwrap_or_default();
 
-        let output = &mut Self::output_file_mut(state).content;
+        let output = &mut Self::output_file_mut(state, &function_type.namespace).content;
         output.push_str(&format!(
-            "type {} func({args_text}){return_type_text}\n",
+            "{}type {} func({args_text}){return_type_text}\n",
+            doc_comment(&function_type.doc, "//"),
             function_type.name.to_camel(),
         ));
 
-        state.mark_generated(&function_type.name);
+        state.mark_generated(&function_type.namespace, &function_type.name);
         Ok(())
     }
 
@@ -246,6 +718,22 @@ impl LanguageGenerator for GoLanguageGenerator {
             .unwrap();
         let output_dir_path = output_dir_path.join(package_name);
 
+        for (namespace, imported_namespace) in &self.imports {
+            let import_path = if imported_namespace.is_empty() {
+                package_name.to_string()
+            } else {
+                format!("{package_name}/{}", imported_namespace.replace('.', "/"))
+            };
+            let import_text = format!(
+                "\t{} \"{import_path}\"\n",
+                Self::package_alias(imported_namespace)
+            );
+
+            let output = &mut Self::output_file_mut(state, namespace).content;
+            let imports_start = output.find("import (\n").unwrap() + "import (\n".len();
+            output.insert_str(imports_start, &import_text);
+        }
+
         let dir_create_result = fs::create_dir(&output_dir_path);
         if let Err(err) = dir_create_result {
             if err.kind() != ErrorKind::AlreadyExists {
@@ -255,16 +743,6 @@ impl LanguageGenerator for GoLanguageGenerator {
             }
         }
 
-        for output_file in &mut state.output_files {
-            let full_path = output_dir_path.join(&output_file.path);
-
-            fs::write(&full_path, &output_file.content).map_err(|err| {
-                format!(
-                    "Failed to write output file to {}! Err: {err:?}",
-                    full_path.display()
-                )
-            })?;
-        }
-        Ok(())
+        write_output_files(state, &output_dir_path)
     }
 }
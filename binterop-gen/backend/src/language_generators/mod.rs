@@ -1,12 +1,16 @@
 use binterop::{
-    schema::Schema,
+    constant::Constant,
+    error::Error,
+    schema::{qualified_name, Schema},
     types::{
-        data::DataType, function::FunctionType, r#enum::EnumType, union::UnionType, Type, TypeData,
+        alias::AliasType, data::DataType, flags::FlagsType, function::FunctionType, r#enum::EnumType,
+        union::UnionType, Type, TypeData,
     },
+    value::Value,
 };
 use std::{
     borrow::Borrow,
-    collections::HashSet,
+    collections::{BTreeSet, HashSet},
     fs,
     path::{Path, PathBuf},
 };
@@ -53,9 +57,25 @@ impl<'a> LanguageGeneratorState<'a> {
 impl<'a> LanguageGeneratorState<'a> {
     pub fn is_generated(&self, type_data: &TypeData) -> bool {
         match type_data.r#type {
-            Type::Primitive | Type::Array | Type::Vector | Type::Pointer | Type::String => true,
-            Type::Data | Type::Enum | Type::Union | Type::Function => {
-                let type_name = self.schema.type_name(type_data.r#type, type_data.index);
+            Type::Primitive
+            | Type::Array
+            | Type::Vector
+            | Type::Pointer
+            | Type::String
+            | Type::Optional
+            | Type::Map
+            | Type::Slice
+            | Type::InlineString
+            | Type::InlineVector => true,
+            Type::Data
+            | Type::Enum
+            | Type::Union
+            | Type::Flags
+            | Type::Alias
+            | Type::Function => {
+                let type_name = self
+                    .schema
+                    .qualified_type_name(type_data.r#type, type_data.index);
 
                 self.generated_type_names
                     .contains(Borrow::<str>::borrow(&type_name))
@@ -63,16 +83,181 @@ impl<'a> LanguageGeneratorState<'a> {
         }
     }
 
-    pub fn mark_generated(&mut self, name: &str) {
-        self.generated_type_names.insert(name.to_string());
+    pub fn mark_generated(&mut self, namespace: &str, name: &str) {
+        self.generated_type_names
+            .insert(qualified_name(namespace, name));
     }
 }
 
+// Collects the named types a type refers to, looking through wrapper types
+pub fn referenced_types(
+    schema: &Schema,
+    r#type: Type,
+    index: usize,
+    named_types: &mut Vec<(Type, usize)>,
+) {
+    let mut inner = |inner_type, inner_type_index| {
+        referenced_types(schema, inner_type, inner_type_index, named_types)
+    };
+
+    match r#type {
+        Type::Data | Type::Enum | Type::Union | Type::Flags | Type::Alias | Type::Function => {
+            named_types.push((r#type, index));
+        }
+        Type::Array => inner(
+            schema.arrays[index].inner_type,
+            schema.arrays[index].inner_type_index,
+        ),
+        Type::Vector => inner(
+            schema.vectors[index].inner_type,
+            schema.vectors[index].inner_type_index,
+        ),
+        Type::Pointer => inner(
+            schema.pointers[index].inner_type,
+            schema.pointers[index].inner_type_index,
+        ),
+        Type::Optional => inner(
+            schema.optionals[index].inner_type,
+            schema.optionals[index].inner_type_index,
+        ),
+        Type::Slice => inner(
+            schema.slices[index].inner_type,
+            schema.slices[index].inner_type_index,
+        ),
+        Type::InlineVector => inner(
+            schema.inline_vectors[index].inner_type,
+            schema.inline_vectors[index].inner_type_index,
+        ),
+        Type::Map => {
+            let map_type = schema.maps[index];
+            inner(map_type.key_type, map_type.key_type_index);
+            inner(map_type.value_type, map_type.value_type_index);
+        }
+        Type::Primitive | Type::String | Type::InlineString => {}
+    }
+}
+
+pub fn referenced_namespaces(
+    schema: &Schema,
+    r#type: Type,
+    index: usize,
+    namespaces: &mut BTreeSet<String>,
+) {
+    let mut named_types = Vec::new();
+    referenced_types(schema, r#type, index, &mut named_types);
+
+    for (named_type, named_type_index) in named_types {
+        namespaces.insert(schema.type_namespace(named_type, named_type_index).to_string());
+    }
+}
+
+// Renders doc text as one comment line per doc line, like "\t/// text\n" for the prefix "\t///"
+pub fn doc_comment(doc: &str, prefix: &str) -> String {
+    if doc.is_empty() {
+        return String::new();
+    }
+
+    doc.split('\n')
+        .map(|line| match line {
+            "" => format!("{prefix}\n"),
+            line => format!("{prefix} {line}\n"),
+        })
+        .collect()
+}
+
+// Bools, integers and floats are written the same way in every generated language
+pub fn scalar_literal(value: &Value) -> Option<String> {
+    match value {
+        Value::Bool(value) => Some(value.to_string()),
+        Value::F32(value) => Some(format!("{value:?}")),
+        Value::F64(value) => Some(format!("{value:?}")),
+        value => value.as_integer().map(|value| value.to_string()),
+    }
+}
+
+// How a struct has to be declared for the C layout rules of a language to reproduce the schema
+// offsets: packed or not, with an explicit align when the attributes raise it above the natural one,
+// and with padding bytes before fields that were moved past their natural offset
+pub struct DeclaredLayout {
+    pub is_packed: bool,
+    pub align: Option<usize>,
+    pub paddings: Vec<usize>,
+    pub is_natural: bool,
+}
+
+pub fn declared_layout(schema: &Schema, data_type: &DataType) -> Result<DeclaredLayout, Error> {
+    let is_packed = schema.is_packed || data_type.try_is_packed()?;
+
+    let mut natural_align = 1;
+    let mut is_natural = true;
+    let mut fields_end = 0usize;
+    let mut paddings = Vec::with_capacity(data_type.fields.len());
+    for field in &data_type.fields {
+        if field.shares_storage() {
+            paddings.push(0);
+            continue;
+        }
+
+        let type_align = schema.try_type_align(field.r#type, field.type_index)?;
+        natural_align = natural_align.max(type_align);
+        is_natural &= field.offset % type_align == 0;
+
+        let align = if is_packed { 1 } else { type_align };
+        if fields_end.next_multiple_of(align) < field.offset {
+            paddings.push(field.offset - fields_end);
+        } else {
+            paddings.push(0);
+        }
+        fields_end = field.offset + field.try_size(schema)?;
+    }
+
+    let align = data_type.try_align(schema)?;
+    is_natural &= data_type.try_size(schema)? == fields_end.next_multiple_of(natural_align);
+
+    Ok(DeclaredLayout {
+        is_packed,
+        align: (!is_packed && align > natural_align).then_some(align),
+        paddings,
+        is_natural,
+    })
+}
+
+pub fn write_output_files(
+    state: &LanguageGeneratorState,
+    output_dir_path: &Path,
+) -> Result<(), String> {
+    for output_file in &state.output_files {
+        let full_path = output_dir_path.join(&output_file.path);
+
+        if let Some(parent_path) = full_path.parent() {
+            fs::create_dir_all(parent_path).map_err(|err| {
+                format!(
+                    "Failed to create output directory at {}! Err: {err:?}",
+                    parent_path.display()
+                )
+            })?;
+        }
+        fs::write(&full_path, &output_file.content).map_err(|err| {
+            format!(
+                "Failed to write output file to {}! Err: {err:?}",
+                full_path.display()
+            )
+        })?;
+    }
+    Ok(())
+}
+
 pub trait LanguageGenerator {
     fn prepare(&mut self, _state: &mut LanguageGeneratorState) -> Result<(), String> {
         Ok(())
     }
 
+    fn generate_constant(
+        &mut self,
+        state: &mut LanguageGeneratorState,
+        constant: &Constant,
+    ) -> Result<(), String>;
+
     fn generate_from_type_and_index(
         &mut self,
         state: &mut LanguageGeneratorState,
@@ -83,6 +268,8 @@ pub trait LanguageGenerator {
             Type::Data => self.generate_data_type(state, &state.schema.types[type_index]),
             Type::Enum => self.generate_enum_type(state, &state.schema.enums[type_index]),
             Type::Union => self.generate_union_type(state, &state.schema.unions[type_index]),
+            Type::Flags => self.generate_flags_type(state, &state.schema.flags[type_index]),
+            Type::Alias => self.generate_alias_type(state, &state.schema.aliases[type_index]),
             Type::Function => {
                 self.generate_function_type(state, &state.schema.functions[type_index])
             }
@@ -108,6 +295,18 @@ pub trait LanguageGenerator {
         union_type: &UnionType,
     ) -> Result<(), String>;
 
+    fn generate_flags_type(
+        &mut self,
+        state: &mut LanguageGeneratorState,
+        flags_type: &FlagsType,
+    ) -> Result<(), String>;
+
+    fn generate_alias_type(
+        &mut self,
+        state: &mut LanguageGeneratorState,
+        alias_type: &AliasType,
+    ) -> Result<(), String>;
+
     fn generate_function_type(
         &mut self,
         state: &mut LanguageGeneratorState,
@@ -121,29 +320,65 @@ pub trait LanguageGenerator {
     ) -> Result<(), String> {
         self.prepare(state)?;
 
+        for constant in &state.schema.constants {
+            self.generate_constant(state, constant)?;
+        }
+
         for (index, data_type) in state.schema.types.iter().enumerate() {
-            let type_data = state.schema.type_data(index, Type::Data)?;
+            let type_data = state
+                .schema
+                .type_data(index, Type::Data)
+                .map_err(|err| err.to_string())?;
 
             if !state.is_generated(&type_data) {
                 self.generate_data_type(state, data_type)?;
             }
         }
         for (index, enum_type) in state.schema.enums.iter().enumerate() {
-            let type_data = state.schema.type_data(index, Type::Enum)?;
+            let type_data = state
+                .schema
+                .type_data(index, Type::Enum)
+                .map_err(|err| err.to_string())?;
 
             if !state.is_generated(&type_data) {
                 self.generate_enum_type(state, enum_type)?;
             }
         }
         for (index, union_type) in state.schema.unions.iter().enumerate() {
-            let type_data = state.schema.type_data(index, Type::Union)?;
+            let type_data = state
+                .schema
+                .type_data(index, Type::Union)
+                .map_err(|err| err.to_string())?;
 
             if !state.is_generated(&type_data) {
                 self.generate_union_type(state, union_type)?;
             }
         }
+        for (index, flags_type) in state.schema.flags.iter().enumerate() {
+            let type_data = state
+                .schema
+                .type_data(index, Type::Flags)
+                .map_err(|err| err.to_string())?;
+
+            if !state.is_generated(&type_data) {
+                self.generate_flags_type(state, flags_type)?;
+            }
+        }
+        for (index, alias_type) in state.schema.aliases.iter().enumerate() {
+            let type_data = state
+                .schema
+                .type_data(index, Type::Alias)
+                .map_err(|err| err.to_string())?;
+
+            if !state.is_generated(&type_data) {
+                self.generate_alias_type(state, alias_type)?;
+            }
+        }
         for (index, function_type) in state.schema.functions.iter().enumerate() {
-            let type_data = state.schema.type_data(index, Type::Function)?;
+            let type_data = state
+                .schema
+                .type_data(index, Type::Function)
+                .map_err(|err| err.to_string())?;
 
             if !state.is_generated(&type_data) {
                 self.generate_function_type(state, function_type)?;
@@ -160,16 +395,6 @@ pub trait LanguageGenerator {
         state: &mut LanguageGeneratorState,
         output_dir_path: &Path,
     ) -> Result<(), String> {
-        for output_file in &mut state.output_files {
-            let full_path = output_dir_path.join(&output_file.path);
-
-            fs::write(&full_path, &output_file.content).map_err(|err| {
-                format!(
-                    "Failed to write output file to {}! Err: {err:?}",
-                    full_path.display()
-                )
-            })?;
-        }
-        Ok(())
+        write_output_files(state, output_dir_path)
     }
 }
@@ -1,3 +1,5 @@
+import std/unicode
+
 type None = object
 
 type Option*[T] = object
@@ -7,11 +9,43 @@ type Option*[T] = object
   of false:
     none*: None
 
+type Int128* = object
+  lo* {.align(16).}: uint64
+  hi*: int64
+
+type UInt128* = object
+  lo* {.align(16).}: uint64
+  hi*: uint64
+
+type Float16* = distinct uint16
+
+type Char32* = distinct uint32
+
 type Vector*[T] = object
   pointer*: ptr UncheckedArray[T]
   len*: uint64
   capacity*: uint64
 
+# Named SliceView since system already defines Slice
+type SliceView*[T] = object
+  pointer*: ptr UncheckedArray[T]
+  len*: uint64
+
+type InlineString*[N: static int] = object
+  len*: uint32
+  bytes*: array[N, uint8]
+
+type InlineVector*[T; N: static int] = object
+  len*: uint32
+  items*: array[N, T]
+
+type MapEntry*[K, V] = object
+  key*: K
+  value*: V
+
+type Map*[K, V] = object
+  entries*: Vector[MapEntry[K, V]]
+
 proc newVector*[T](capacity: uint64 = 0): Vector[T] =
   Vector[T](
     pointer: cast[ptr UncheckedArray[T]](allocShared(capacity * sizeof(T).uint64)),
@@ -35,6 +69,16 @@ iterator pairs*[T](v: var Vector[T]): (uint64, var T) =
   for i in 0..<v.len:
     yield (i, v.pointer[i])
 
+iterator items*[T](s: SliceView[T]): T =
+  for i in 0..<s.len:
+    yield s.pointer[i]
+
+proc toSliceView*[T](vec: Vector[T]): SliceView[T] =
+  SliceView[T](pointer: vec.pointer, len: vec.len)
+
+template toOpenArray*[T](s: SliceView[T]): openArray[T] =
+  s.pointer.toOpenArray(0, s.len.int - 1)
+
 proc freeVector*[T](vec: sink Vector[T]) =
   vec.pointer.freeShared()
 
@@ -83,3 +127,47 @@ proc toBinteropString*(str: string): String =
 proc toNimString*(str: sink String): string =
   for ch in str.toOpenArray():
     result.add(ch.char)
+
+proc toInlineString*[N: static int](str: string): InlineString[N] =
+  if str.len > N:
+    raise newException(ValueError, "String of length " & $str.len & " doesn't fit into " & $N & " bytes")
+  for i, ch in str:
+    result.bytes[i] = ch.uint8
+  result.len = str.len.uint32
+
+proc toNimString*[N: static int](str: InlineString[N]): string =
+  for i in 0..<min(str.len.int, N):
+    result.add(str.bytes[i].char)
+  if validateUtf8(result) != -1:
+    raise newException(ValueError, "String contains invalid UTF-8")
+
+iterator items*[T; N: static int](v: InlineVector[T, N]): T =
+  for i in 0..<min(v.len.int, N):
+    yield v.items[i]
+
+proc push*[T; N: static int](v: var InlineVector[T, N], value: T): bool =
+  if v.len.int >= N:
+    return false
+  v.items[v.len] = value
+  v.len += 1
+  true
+
+proc cmp*(a, b: String): int =
+  for i in 0..<min(a.len, b.len):
+    if a.pointer[i] != b.pointer[i]:
+      return cmp(a.pointer[i], b.pointer[i])
+  cmp(a.len, b.len)
+
+proc get*[K, V](map: Map[K, V], key: K): Option[V] =
+  var low = 0'u64
+  var high = map.entries.len
+  while low < high:
+    let middle = low + (high - low) div 2
+    let order = cmp(map.entries.pointer[middle].key, key)
+    if order == 0:
+      return Option[V](isSome: true, value: map.entries.pointer[middle].value)
+    elif order < 0:
+      low = middle + 1
+    else:
+      high = middle
+  Option[V](isSome: false)
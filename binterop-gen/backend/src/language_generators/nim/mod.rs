@@ -1,22 +1,46 @@
-use std::path::PathBuf;
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
 
 use binterop::{
-    schema::Schema,
-    types::{Type, data::DataType, r#enum::EnumType, function::FunctionType, union::UnionType},
+    constant::Constant,
+    schema::{Schema, qualified_name},
+    target::Endianness,
+    types::{
+        Type,
+        alias::AliasType,
+        data::DataType,
+        r#enum::{EnumType, EnumVariant},
+        flags::FlagsType,
+        function::FunctionType,
+        union::UnionType,
+    },
+    value::Value,
 };
 use case::CaseExt;
 
-use crate::language_generators::{LanguageGenerator, LanguageGeneratorState, SourceFile};
+use crate::language_generators::{
+    LanguageGenerator, LanguageGeneratorState, SourceFile, declared_layout, doc_comment,
+    referenced_namespaces, referenced_types, scalar_literal, write_output_files,
+};
 
 #[derive(Default)]
-pub struct NimLanguageGenerator {}
+pub struct NimLanguageGenerator {
+    imports: BTreeSet<(String, String)>,
+    declaring: Vec<String>,
+}
 impl NimLanguageGenerator {
-    fn nim_type_name(r#type: Type, type_index: usize, schema: &Schema) -> String {
+    fn nim_type_name(r#type: Type, type_index: usize, schema: &Schema, namespace: &str) -> String {
         match r#type {
             Type::Array => {
                 let array_type = schema.arrays[type_index];
-                let inner_type_name =
-                    Self::nim_type_name(array_type.inner_type, array_type.inner_type_index, schema);
+                let inner_type_name = Self::nim_type_name(
+                    array_type.inner_type,
+                    array_type.inner_type_index,
+                    schema,
+                    namespace,
+                );
                 format!("array[{}, {inner_type_name}]", array_type.len)
             }
             Type::Vector => {
@@ -25,34 +49,256 @@ impl NimLanguageGenerator {
                     heap_array_type.inner_type,
                     heap_array_type.inner_type_index,
                     schema,
+                    namespace,
                 );
                 format!("Vector[{inner_type_name}]")
             }
+            Type::Slice => {
+                let slice_type = schema.slices[type_index];
+                let inner_type_name = Self::nim_type_name(
+                    slice_type.inner_type,
+                    slice_type.inner_type_index,
+                    schema,
+                    namespace,
+                );
+
+                format!("SliceView[{inner_type_name}]")
+            }
+            Type::InlineString => {
+                format!(
+                    "InlineString[{}]",
+                    schema.inline_strings[type_index].capacity
+                )
+            }
+            Type::InlineVector => {
+                let inline_vector_type = schema.inline_vectors[type_index];
+                let inner_type_name = Self::nim_type_name(
+                    inline_vector_type.inner_type,
+                    inline_vector_type.inner_type_index,
+                    schema,
+                    namespace,
+                );
+
+                format!(
+                    "InlineVector[{inner_type_name}, {}]",
+                    inline_vector_type.capacity
+                )
+            }
             Type::Pointer => {
                 let pointer_type = schema.pointers[type_index];
                 let inner_type_name = Self::nim_type_name(
                     pointer_type.inner_type,
                     pointer_type.inner_type_index,
                     schema,
+                    namespace,
                 );
 
                 format!("ptr {inner_type_name}")
             }
+            Type::Optional => {
+                let optional_type = schema.optionals[type_index];
+                let inner_type_name = Self::nim_type_name(
+                    optional_type.inner_type,
+                    optional_type.inner_type_index,
+                    schema,
+                    namespace,
+                );
+
+                format!("Option[{inner_type_name}]")
+            }
+            Type::Map => {
+                let map_type = schema.maps[type_index];
+                let key_type_name = Self::nim_type_name(
+                    map_type.key_type,
+                    map_type.key_type_index,
+                    schema,
+                    namespace,
+                );
+                let value_type_name = Self::nim_type_name(
+                    map_type.value_type,
+                    map_type.value_type_index,
+                    schema,
+                    namespace,
+                );
+
+                format!("Map[{key_type_name}, {value_type_name}]")
+            }
             Type::Primitive => {
+                let type_name = match schema.type_name(r#type, type_index).as_ref() {
+                    "i8" => "int8",
+                    "u8" => "uint8",
+                    "i16" => "int16",
+                    "u16" => "uint16",
+                    "i32" => "int32",
+                    "u32" => "uint32",
+                    "i64" => "int64",
+                    "u64" => "uint64",
+                    "i128" => "Int128",
+                    "u128" => "UInt128",
+                    "isize" => "int",
+                    "usize" => "uint",
+                    "f16" => "Float16",
+                    "f32" => "float32",
+                    "f64" => "float64",
+                    "char32" => "Char32",
+                    "bool" => "bool",
+                    type_name => return type_name.to_string(),
+                };
+
+                type_name.to_string()
+            }
+            _ => {
                 let type_name = schema.type_name(r#type, type_index);
-                match type_name {
-                    type_name if type_name.contains("i") => type_name.replace("i", "int"),
-                    type_name if type_name.contains("u") => type_name.replace("u", "uint"),
-                    type_name if type_name.contains("f") => type_name.replace("f", "float"),
-                    _ => type_name.to_string(),
+                let type_namespace = schema.type_namespace(r#type, type_index);
+                if type_namespace == namespace {
+                    return type_name.to_string();
                 }
+
+                format!("{}.{type_name}", Self::module_alias(type_namespace))
+            }
+        }
+    }
+
+    // Namespaces are modules in subdirectories next to the root module, imported under an alias
+    // derived from their full name
+    fn module_alias(namespace: &str) -> String {
+        if namespace.is_empty() {
+            "root".to_string()
+        } else {
+            namespace.replace('.', "_")
+        }
+    }
+
+    fn root_path(namespace: &str) -> String {
+        "../".repeat(namespace.matches('.').count())
+    }
+
+    fn add_imports(&mut self, schema: &Schema, namespace: &str, r#type: Type, type_index: usize) {
+        let mut namespaces = BTreeSet::new();
+        referenced_namespaces(schema, r#type, type_index, &mut namespaces);
+
+        for imported_namespace in namespaces {
+            if imported_namespace != namespace {
+                self.imports
+                    .insert((namespace.to_string(), imported_namespace));
             }
-            _ => schema.type_name(r#type, type_index).to_string(),
         }
     }
 
-    fn output_file_mut<'a>(state: &'a mut LanguageGeneratorState) -> &'a mut SourceFile {
-        &mut state.output_files[0]
+    // Every type is declared in a type section of its own, and Nim only lets a type refer to types
+    // declared before it or in the same section. So the types a type refers to, also through
+    // pointers and other wrappers, are generated first, which only fails for types referring to
+    // each other
+    fn generate_referenced_types(
+        &mut self,
+        state: &mut LanguageGeneratorState,
+        namespace: &str,
+        name: &str,
+        r#type: Type,
+        type_index: usize,
+    ) -> Result<(), String> {
+        let owner_name = qualified_name(namespace, name);
+        let mut named_types = Vec::new();
+        referenced_types(state.schema, r#type, type_index, &mut named_types);
+
+        for (named_type, named_type_index) in named_types {
+            let type_data = state
+                .schema
+                .type_data(named_type_index, named_type)
+                .map_err(|err| err.to_string())?;
+            let type_name = state
+                .schema
+                .qualified_type_name(named_type, named_type_index)
+                .to_string();
+            if type_name == owner_name || state.is_generated(&type_data) {
+                continue;
+            }
+            if self.declaring.contains(&type_name) {
+                return Err(format!(
+                    "Nim can't declare {type_name} and {owner_name} referring to each other in separate type sections"
+                ));
+            }
+
+            self.declaring.push(owner_name.clone());
+            let result = self.generate_from_type_and_index(state, named_type, named_type_index);
+            self.declaring.pop();
+            result?;
+        }
+
+        self.add_imports(state.schema, namespace, r#type, type_index);
+        Ok(())
+    }
+
+    fn nim_literal(
+        &mut self,
+        schema: &Schema,
+        namespace: &str,
+        value: &Value,
+        r#type: Type,
+        type_index: usize,
+    ) -> Result<String, String> {
+        match (r#type, value) {
+            (Type::Alias, value) => {
+                let alias_type = &schema.aliases[type_index];
+                let inner_text = self.nim_literal(
+                    schema,
+                    namespace,
+                    value,
+                    alias_type.inner_type,
+                    alias_type.inner_type_index,
+                )?;
+
+                if alias_type.is_distinct {
+                    let alias_name = Self::nim_type_name(r#type, type_index, schema, namespace);
+                    Ok(format!("{alias_name}({inner_text})"))
+                } else {
+                    Ok(inner_text)
+                }
+            }
+            (Type::Enum, Value::Enum(variant_name)) => {
+                self.add_imports(schema, namespace, r#type, type_index);
+                Ok(format!(
+                    "{}.{variant_name}",
+                    Self::nim_type_name(r#type, type_index, schema, namespace)
+                ))
+            }
+            // Integer literals are int64 unless they have a suffix
+            (_, Value::U64(value) | Value::Usize(value)) if *value > i64::MAX as u64 => {
+                Ok(format!("{value}'u64"))
+            }
+            (_, value) => {
+                scalar_literal(value).ok_or_else(|| format!("No Nim literal for {value:?}"))
+            }
+        }
+    }
+
+    fn output_file_mut<'a>(
+        state: &'a mut LanguageGeneratorState,
+        namespace: &str,
+    ) -> &'a mut SourceFile {
+        if namespace.is_empty() {
+            return &mut state.output_files[0];
+        }
+
+        let mut output_file_name = namespace.split('.').collect::<PathBuf>();
+        output_file_name.set_extension("nim");
+
+        let index = match state
+            .output_files
+            .iter()
+            .position(|output_file| output_file.path == output_file_name)
+        {
+            Some(index) => index,
+            None => {
+                state.output_files.push(
+                    SourceFile::new(output_file_name)
+                        .contents(format!("import {}binterop\n\n", Self::root_path(namespace))),
+                );
+                state.output_files.len() - 1
+            }
+        };
+
+        &mut state.output_files[index]
     }
 }
 
@@ -61,8 +307,15 @@ impl LanguageGenerator for NimLanguageGenerator {
         let mut output_file_name = PathBuf::from(state.file_name);
         output_file_name.set_extension("nim");
 
-        let output_file =
-            SourceFile::new(output_file_name).contents("import binterop\n\n".to_string());
+        let target = &state.schema.target;
+        let endianness = match target.endianness {
+            Endianness::Little => "littleEndian",
+            Endianness::Big => "bigEndian",
+        };
+        let output_file = SourceFile::new(output_file_name).contents(format!(
+            "import binterop\n\nwhen sizeof(pointer) != {} or cpuEndian != {endianness}:\n  {{.error: \"Generated for {} layout\".}}\n\n",
+            target.pointer_width, target.name
+        ));
         state.output_files.push(output_file);
 
         let std_file =
@@ -72,32 +325,153 @@ impl LanguageGenerator for NimLanguageGenerator {
         Ok(())
     }
 
+    fn generate_constant(
+        &mut self,
+        state: &mut LanguageGeneratorState,
+        constant: &Constant,
+    ) -> Result<(), String> {
+        // 128 bit integers are objects in Nim, so their constants stay untyped
+        let type_annotation = if constant.type_name().ends_with("128") {
+            String::new()
+        } else {
+            format!(
+                ": {}",
+                Self::nim_type_name(
+                    Type::Primitive,
+                    constant.type_index,
+                    state.schema,
+                    &constant.namespace
+                )
+            )
+        };
+
+        let output = &mut Self::output_file_mut(state, &constant.namespace).content;
+        output.push_str(&format!(
+            "const {}*{type_annotation} = {}\n{}\n",
+            constant.name,
+            constant.value,
+            doc_comment(&constant.doc, "  ##")
+        ));
+
+        Ok(())
+    }
+
     fn generate_data_type(
         &mut self,
         state: &mut LanguageGeneratorState,
         data_type: &DataType,
     ) -> Result<(), String> {
         let mut fields_text = String::new();
+        let mut accessors_text = String::new();
+        let mut defaults_text = String::new();
+        let mut bitfield_units = 0;
+        let mut padding_fields = 0;
 
-        for field in &data_type.fields {
-            let type_data = state.schema.type_data(field.type_index, field.r#type)?;
+        let layout = declared_layout(state.schema, data_type).map_err(|err| err.to_string())?;
+        for (index, (field, &padding)) in data_type.fields.iter().zip(&layout.paddings).enumerate()
+        {
+            if padding != 0 {
+                fields_text.push_str(&format!(
+                    "  padding{padding_fields}: array[{padding}, uint8]\n"
+                ));
+                padding_fields += 1;
+            }
+            // Nim can only align fields, aligning the first one aligns the whole object
+            let pragma = match layout.align {
+                Some(align) if index == 0 => format!(" {{.align({align}).}}"),
+                _ => String::new(),
+            };
 
-            if !state.is_generated(&type_data) {
-                self.generate_from_type_and_index(state, field.r#type, field.type_index)?;
+            self.generate_referenced_types(
+                state,
+                &data_type.namespace,
+                &data_type.name,
+                field.r#type,
+                field.type_index,
+            )?;
+
+            let field_type_name = Self::nim_type_name(
+                field.r#type,
+                field.type_index,
+                state.schema,
+                &data_type.namespace,
+            );
+
+            if let Some(default) = &field.default {
+                let default_text = self.nim_literal(
+                    state.schema,
+                    &data_type.namespace,
+                    default,
+                    field.r#type,
+                    field.type_index,
+                )?;
+                defaults_text.push_str(&format!("  result.{} = {default_text}\n", field.name));
             }
 
-            let field_type_name = Self::nim_type_name(field.r#type, field.type_index, state.schema);
+            let field_doc = doc_comment(&field.doc, "    ##");
+            let Some(bitfield) = field.bitfield else {
+                fields_text.push_str(&format!(
+                    "  {}*{pragma}: {field_type_name}\n{field_doc}",
+                    field.name
+                ));
+                continue;
+            };
+
+            if !field.shares_storage() {
+                bitfield_units += 1;
+                fields_text.push_str(&format!(
+                    "  bitfield{}*{pragma}: {field_type_name}\n",
+                    bitfield_units - 1
+                ));
+            }
 
-            fields_text.push_str(&format!("  {}*: {field_type_name}\n", field.name));
+            let storage = format!("bitfield{}", bitfield_units - 1);
+            let storage_bits = state
+                .schema
+                .try_type_size(field.r#type, field.type_index)
+                .map_err(|err| err.to_string())?
+                * 8;
+            let left_shift = storage_bits as u32 - bitfield.bit_offset - bitfield.bit_width;
+            let right_shift = storage_bits as u32 - bitfield.bit_width;
+            let is_signed = field_type_name.starts_with("int");
+            let mask = if is_signed {
+                format!(
+                    "cast[{field_type_name}](u{field_type_name}({:#x}))",
+                    bitfield.mask()
+                )
+            } else {
+                format!("{field_type_name}({:#x})", bitfield.mask())
+            };
+
+            accessors_text.push_str(&format!(
+                "proc {}*(self: {}): {field_type_name} =\n{}  (self.{storage} shl {left_shift}) {} {right_shift}\n\n",
+                field.name,
+                data_type.name,
+                doc_comment(&field.doc, "  ##"),
+                if is_signed { "ashr" } else { "shr" }
+            ));
+            accessors_text.push_str(&format!(
+                "proc `{}=`*(self: var {}, value: {field_type_name}) =\n  self.{storage} = (self.{storage} and not {mask}) or ((value shl {}) and {mask})\n\n",
+                field.name, data_type.name, bitfield.bit_offset
+            ));
         }
 
-        let output = &mut Self::output_file_mut(state).content;
+        let output = &mut Self::output_file_mut(state, &data_type.namespace).content;
         output.push_str(&format!(
-            "type {}* = object\n{fields_text}\n",
-            data_type.name
+            "type {}*{} = object\n{}{fields_text}\n{accessors_text}",
+            data_type.name,
+            if layout.is_packed { " {.packed.}" } else { "" },
+            doc_comment(&data_type.doc, "  ##")
         ));
+        // Fields without a default keep the zero value result starts with
+        if !defaults_text.is_empty() {
+            output.push_str(&format!(
+                "proc init*(_: type {0}): {0} =\n{defaults_text}\n",
+                data_type.name
+            ));
+        }
 
-        state.mark_generated(&data_type.name);
+        state.mark_generated(&data_type.namespace, &data_type.name);
         Ok(())
     }
 
@@ -106,18 +480,105 @@ impl LanguageGenerator for NimLanguageGenerator {
         state: &mut LanguageGeneratorState,
         enum_type: &EnumType,
     ) -> Result<(), String> {
+        // Nim requires enum values to be ascending
+        let mut variants = enum_type.variants.iter().collect::<Vec<_>>();
+        variants.sort_by_key(|variant| variant.value);
+
         let mut variants_text = String::new();
-        for variant in &enum_type.variants {
-            variants_text.push_str(&format!("  {variant}\n"));
+        for variant in variants {
+            variants_text.push_str(&format!(
+                "  {} = {}\n{}",
+                variant.name,
+                variant.value,
+                doc_comment(&variant.doc, "    ##")
+            ));
         }
+        let repr_name = Self::nim_type_name(
+            Type::Primitive,
+            enum_type.repr_index,
+            state.schema,
+            &enum_type.namespace,
+        );
 
-        let output = &mut Self::output_file_mut(state).content;
+        let output = &mut Self::output_file_mut(state, &enum_type.namespace).content;
         output.push_str(&format!(
-            "type {}* {{.size: sizeof(uint32).}} = enum\n{variants_text}\n",
-            enum_type.name
+            "type {}* {{.size: sizeof({repr_name}).}} = enum\n{}{variants_text}\n",
+            enum_type.name,
+            doc_comment(&enum_type.doc, "  ##")
         ));
 
-        state.mark_generated(&enum_type.name);
+        state.mark_generated(&enum_type.namespace, &enum_type.name);
+        Ok(())
+    }
+
+    fn generate_flags_type(
+        &mut self,
+        state: &mut LanguageGeneratorState,
+        flags_type: &FlagsType,
+    ) -> Result<(), String> {
+        let mut bits = flags_type.bits.iter().collect::<Vec<_>>();
+        bits.sort_by_key(|bit| bit.bit);
+
+        let mut bits_text = String::new();
+        for bit in bits {
+            bits_text.push_str(&format!(
+                "  {} = {}\n{}",
+                bit.name,
+                bit.bit,
+                doc_comment(&bit.doc, "    ##")
+            ));
+        }
+        let repr_name = Self::nim_type_name(
+            Type::Primitive,
+            flags_type.repr_index,
+            state.schema,
+            &flags_type.namespace,
+        );
+
+        let output = &mut Self::output_file_mut(state, &flags_type.namespace).content;
+        output.push_str(&format!(
+            "type {0}Flag* = enum\n{bits_text}\ntype {0}* {{.size: sizeof({repr_name}).}} = set[{0}Flag]\n{1}\n",
+            flags_type.name,
+            doc_comment(&flags_type.doc, "  ##")
+        ));
+
+        state.mark_generated(&flags_type.namespace, &flags_type.name);
+        Ok(())
+    }
+
+    fn generate_alias_type(
+        &mut self,
+        state: &mut LanguageGeneratorState,
+        alias_type: &AliasType,
+    ) -> Result<(), String> {
+        self.generate_referenced_types(
+            state,
+            &alias_type.namespace,
+            &alias_type.name,
+            alias_type.inner_type,
+            alias_type.inner_type_index,
+        )?;
+
+        let inner_type_name = Self::nim_type_name(
+            alias_type.inner_type,
+            alias_type.inner_type_index,
+            state.schema,
+            &alias_type.namespace,
+        );
+
+        let output = &mut Self::output_file_mut(state, &alias_type.namespace).content;
+        output.push_str(&format!(
+            "type {}* = {}{inner_type_name}\n{}\n",
+            alias_type.name,
+            if alias_type.is_distinct {
+                "distinct "
+            } else {
+                ""
+            },
+            doc_comment(&alias_type.doc, "  ##")
+        ));
+
+        state.mark_generated(&alias_type.namespace, &alias_type.name);
         Ok(())
     }
 
@@ -127,35 +588,60 @@ impl LanguageGenerator for NimLanguageGenerator {
         union_type: &UnionType,
     ) -> Result<(), String> {
         let mut enum_type = EnumType::new(&format!("{}Variant", union_type.name), &[], &[]);
+        enum_type.namespace = union_type.namespace.clone();
         enum_type.variants = union_type
-            .possible_types
+            .variants
             .iter()
-            .map(|&(type_index, r#type)| {
-                format!(
-                    "{}Variant",
-                    state.schema.type_name(r#type, type_index).to_string()
-                )
+            .enumerate()
+            .map(|(tag, variant)| {
+                let mut enum_variant =
+                    EnumVariant::new(&format!("{}Variant", variant.name.to_camel()), tag as i128);
+                enum_variant.doc = variant.doc.clone();
+                enum_variant
             })
             .collect();
         self.generate_enum_type(state, &enum_type)?;
 
         let mut union_fields_text = String::new();
-        for (type_index, r#type) in union_type.possible_types.iter().copied() {
-            let type_name = Self::nim_type_name(r#type, type_index, state.schema);
-            let field_name = type_name.to_camel();
-            let value_name = type_name.to_camel_lowercase();
+        for variant in &union_type.variants {
+            let variant_name = variant.name.to_camel();
+            let variant_doc = doc_comment(&variant.doc, "    ##");
 
-            union_fields_text.push_str(&format!(
-                "  of {field_name}Variant:\n    {value_name}*: {type_name}\n",
-            ));
+            match variant.payload {
+                Some((type_index, r#type)) => {
+                    self.generate_referenced_types(
+                        state,
+                        &union_type.namespace,
+                        &union_type.name,
+                        r#type,
+                        type_index,
+                    )?;
+                    let type_name = Self::nim_type_name(
+                        r#type,
+                        type_index,
+                        state.schema,
+                        &union_type.namespace,
+                    );
+                    let value_name = variant.name.to_camel_lowercase();
+
+                    union_fields_text.push_str(&format!(
+                        "  of {variant_name}Variant:\n{variant_doc}    {value_name}*: {type_name}\n",
+                    ));
+                }
+                None => union_fields_text.push_str(&format!(
+                    "  of {variant_name}Variant:\n{variant_doc}    discard\n"
+                )),
+            }
         }
 
         let union_name = union_type.name.to_camel();
-        let output = &mut Self::output_file_mut(state).content;
+        let output = &mut Self::output_file_mut(state, &union_type.namespace).content;
         output.push_str(&format!(
-            "type {union_name}* = object\n  case variant: {union_name}Variant\n{union_fields_text}\n\n",
+            "type {union_name}* = object\n{}  case variant: {union_name}Variant\n{union_fields_text}\n\n",
+            doc_comment(&union_type.doc, "  ##")
         ));
 
+        state.mark_generated(&union_type.namespace, &union_type.name);
         Ok(())
     }
 
@@ -164,12 +650,19 @@ impl LanguageGenerator for NimLanguageGenerator {
         state: &mut LanguageGeneratorState,
         function_type: &FunctionType,
     ) -> Result<(), String> {
-        for arg in &function_type.args {
-            let type_data = arg.r#type.unwrap();
-
-            if !state.is_generated(&type_data) {
-                self.generate_from_type_and_index(state, type_data.r#type, type_data.index)?;
-            }
+        for type_data in function_type
+            .args
+            .iter()
+            .map(|arg| arg.r#type.unwrap())
+            .chain(function_type.return_type)
+        {
+            self.generate_referenced_types(
+                state,
+                &function_type.namespace,
+                &function_type.name,
+                type_data.r#type,
+                type_data.index,
+            )?;
         }
 
         let args_text = function_type
@@ -177,8 +670,12 @@ impl LanguageGenerator for NimLanguageGenerator {
             .iter()
             .map(|arg| {
                 let type_data = arg.r#type.unwrap();
-                let type_name =
-                    Self::nim_type_name(type_data.r#type, type_data.index, state.schema);
+                let type_name = Self::nim_type_name(
+                    type_data.r#type,
+                    type_data.index,
+                    state.schema,
+                    &function_type.namespace,
+                );
 
                 format!("{}: {type_name}", arg.name)
             })
@@ -194,18 +691,52 @@ impl LanguageGenerator for NimLanguageGenerator {
                         return_type_data.r#type,
                         return_type_data.index,
                         state.schema,
+                        &function_type.namespace
                     )
                 )
             })
             .unwrap_or_default();
 
-        let output = &mut Self::output_file_mut(state).content;
+        let output = &mut Self::output_file_mut(state, &function_type.namespace).content;
         output.push_str(&format!(
-            "type {}* = proc({args_text}){return_type_text}\n",
+            "type {}* = proc({args_text}){return_type_text}\n{}",
             function_type.name.to_camel_lowercase(),
+            doc_comment(&function_type.doc, "  ##")
         ));
 
-        state.mark_generated(&function_type.name);
+        state.mark_generated(&function_type.namespace, &function_type.name);
         Ok(())
     }
+
+    fn finish(
+        &mut self,
+        state: &mut LanguageGeneratorState,
+        output_dir_path: &Path,
+    ) -> Result<(), String> {
+        let root_module = Path::new(state.file_name)
+            .file_stem()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        for (namespace, imported_namespace) in &self.imports {
+            let import_path = if imported_namespace.is_empty() {
+                root_module.clone()
+            } else {
+                imported_namespace.replace('.', "/")
+            };
+            let import_text = format!(
+                "import {}{import_path} as {}\n",
+                Self::root_path(namespace),
+                Self::module_alias(imported_namespace)
+            );
+
+            let output = &mut Self::output_file_mut(state, namespace).content;
+            let imports_end = output.find('\n').unwrap() + 1;
+            output.insert_str(imports_end, &import_text);
+        }
+
+        write_output_files(state, output_dir_path)
+    }
 }
@@ -1,26 +1,55 @@
-use std::path::PathBuf;
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
 
 use binterop::{
+    constant::Constant,
     schema::Schema,
-    types::{Type, data::DataType, r#enum::EnumType},
+    target::Endianness,
+    types::{
+        Type,
+        alias::AliasType,
+        data::DataType,
+        r#enum::{EnumType, EnumVariant},
+        flags::FlagsType,
+    },
+    value::Value,
 };
 use case::CaseExt;
 
-use crate::language_generators::{LanguageGenerator, SourceFile};
+use crate::language_generators::{
+    LanguageGenerator, SourceFile, declared_layout, doc_comment, scalar_literal, write_output_files,
+};
 
 use super::LanguageGeneratorState;
 
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "union", "unsafe", "use", "where",
+    "while",
+];
+
 #[derive(Default)]
-pub struct RustLanguageGenerator {}
+pub struct RustLanguageGenerator {
+    modules: Vec<(String, String)>,
+}
 impl RustLanguageGenerator {
-    fn rust_type_name(r#type: Type, type_index: usize, schema: &Schema) -> String {
+    fn rust_type_name(r#type: Type, type_index: usize, schema: &Schema, namespace: &str) -> String {
         match r#type {
+            Type::Primitive => match schema.type_name(r#type, type_index).as_ref() {
+                "f16" => "binterop::std::F16".to_string(),
+                "char32" => "char".to_string(),
+                type_name => type_name.to_string(),
+            },
             Type::Array => {
                 let array_type = schema.arrays[type_index];
                 let inner_type_name = Self::rust_type_name(
                     array_type.inner_type,
                     array_type.inner_type_index,
                     schema,
+                    namespace,
                 );
                 format!("[{inner_type_name}; {}]", array_type.len)
             }
@@ -30,26 +59,300 @@ impl RustLanguageGenerator {
                     heap_array_type.inner_type,
                     heap_array_type.inner_type_index,
                     schema,
+                    namespace,
                 );
                 format!("binterop::std::Vector<{inner_type_name}>")
             }
+            Type::Slice => {
+                let slice_type = schema.slices[type_index];
+                let inner_type_name = Self::rust_type_name(
+                    slice_type.inner_type,
+                    slice_type.inner_type_index,
+                    schema,
+                    namespace,
+                );
+
+                format!("binterop::std::Slice<'a, {inner_type_name}>")
+            }
             Type::String => "binterop::std::String".to_string(),
+            Type::InlineString => format!(
+                "binterop::std::InlineString<{}>",
+                schema.inline_strings[type_index].capacity
+            ),
+            Type::InlineVector => {
+                let inline_vector_type = schema.inline_vectors[type_index];
+                let inner_type_name = Self::rust_type_name(
+                    inline_vector_type.inner_type,
+                    inline_vector_type.inner_type_index,
+                    schema,
+                    namespace,
+                );
+
+                format!(
+                    "binterop::std::InlineVector<{inner_type_name}, {}>",
+                    inline_vector_type.capacity
+                )
+            }
             Type::Pointer => {
                 let pointer_type = schema.pointers[type_index];
                 let inner_type_name = Self::rust_type_name(
                     pointer_type.inner_type,
                     pointer_type.inner_type_index,
                     schema,
+                    namespace,
                 );
 
                 format!("*mut {inner_type_name}")
             }
-            _ => schema.type_name(r#type, type_index).to_string(),
+            Type::Optional => {
+                let optional_type = schema.optionals[type_index];
+                let inner_type_name = Self::rust_type_name(
+                    optional_type.inner_type,
+                    optional_type.inner_type_index,
+                    schema,
+                    namespace,
+                );
+
+                format!("binterop::std::Option<{inner_type_name}>")
+            }
+            Type::Map => {
+                let map_type = schema.maps[type_index];
+                let key_type_name = Self::rust_type_name(
+                    map_type.key_type,
+                    map_type.key_type_index,
+                    schema,
+                    namespace,
+                );
+                let value_type_name = Self::rust_type_name(
+                    map_type.value_type,
+                    map_type.value_type_index,
+                    schema,
+                    namespace,
+                );
+
+                format!("binterop::std::Map<{key_type_name}, {value_type_name}>")
+            }
+            _ => {
+                let type_name = schema.type_name(r#type, type_index);
+                let lifetime = Self::lifetime(schema, [(r#type, type_index)]);
+                let type_namespace = schema.type_namespace(r#type, type_index);
+                if type_namespace == namespace {
+                    return format!("{type_name}{lifetime}");
+                }
+
+                // Namespaces are nested modules, so other ones are reached through the root module
+                let mut path = "super::".repeat(
+                    namespace
+                        .split('.')
+                        .filter(|segment| !segment.is_empty())
+                        .count(),
+                );
+                for segment in type_namespace
+                    .split('.')
+                    .filter(|segment| !segment.is_empty())
+                {
+                    path.push_str(segment);
+                    path.push_str("::");
+                }
+
+                format!("{path}{type_name}{lifetime}")
+            }
         }
     }
 
-    fn output_file_mut<'a>(state: &'a mut LanguageGeneratorState) -> &'a mut SourceFile {
-        &mut state.output_files[0]
+    // Types holding a slice anywhere inside borrow its elements, so they are declared with a
+    // lifetime that every use of them has to name
+    fn lifetime(schema: &Schema, members: impl IntoIterator<Item = (Type, usize)>) -> &'static str {
+        let mut visited = Vec::new();
+        if members
+            .into_iter()
+            .any(|(r#type, type_index)| Self::borrows(schema, r#type, type_index, &mut visited))
+        {
+            "<'a>"
+        } else {
+            ""
+        }
+    }
+
+    fn borrows(
+        schema: &Schema,
+        r#type: Type,
+        type_index: usize,
+        visited: &mut Vec<(Type, usize)>,
+    ) -> bool {
+        if visited.contains(&(r#type, type_index)) {
+            return false;
+        }
+        visited.push((r#type, type_index));
+
+        let mut inner = |inner_type, inner_type_index| {
+            Self::borrows(schema, inner_type, inner_type_index, visited)
+        };
+        match r#type {
+            Type::Slice => true,
+            Type::Data => schema.types[type_index]
+                .fields
+                .iter()
+                .any(|field| inner(field.r#type, field.type_index)),
+            Type::Union => schema.unions[type_index]
+                .payloads()
+                .any(|(payload_index, payload_type)| inner(payload_type, payload_index)),
+            Type::Alias => inner(
+                schema.aliases[type_index].inner_type,
+                schema.aliases[type_index].inner_type_index,
+            ),
+            Type::Function => {
+                let function_type = &schema.functions[type_index];
+                function_type
+                    .args
+                    .iter()
+                    .filter_map(|arg| arg.r#type)
+                    .chain(function_type.return_type)
+                    .any(|type_data| inner(type_data.r#type, type_data.index))
+            }
+            Type::Array => inner(
+                schema.arrays[type_index].inner_type,
+                schema.arrays[type_index].inner_type_index,
+            ),
+            Type::Vector => inner(
+                schema.vectors[type_index].inner_type,
+                schema.vectors[type_index].inner_type_index,
+            ),
+            Type::Pointer => inner(
+                schema.pointers[type_index].inner_type,
+                schema.pointers[type_index].inner_type_index,
+            ),
+            Type::Optional => inner(
+                schema.optionals[type_index].inner_type,
+                schema.optionals[type_index].inner_type_index,
+            ),
+            Type::InlineVector => inner(
+                schema.inline_vectors[type_index].inner_type,
+                schema.inline_vectors[type_index].inner_type_index,
+            ),
+            Type::Map => {
+                let map_type = schema.maps[type_index];
+                inner(map_type.key_type, map_type.key_type_index)
+                    || inner(map_type.value_type, map_type.value_type_index)
+            }
+            Type::Primitive | Type::Enum | Type::Flags | Type::String | Type::InlineString => false,
+        }
+    }
+
+    fn render_modules(&self, parent: &str, depth: usize) -> String {
+        let mut namespaces = BTreeSet::new();
+        for (namespace, _) in &self.modules {
+            let mut prefix = String::new();
+            for segment in namespace.split('.') {
+                if !prefix.is_empty() {
+                    prefix.push('.');
+                }
+                prefix.push_str(segment);
+                namespaces.insert(prefix.clone());
+            }
+        }
+
+        let indent = "\t".repeat(depth);
+        let mut output = String::new();
+        for namespace in namespaces {
+            let (namespace_parent, name) = namespace.rsplit_once('.').unwrap_or(("", &namespace));
+            if namespace_parent != parent {
+                continue;
+            }
+
+            output.push_str(&format!("{indent}pub mod {name} {{\n"));
+            if let Some((_, content)) = self
+                .modules
+                .iter()
+                .find(|(module_namespace, _)| *module_namespace == namespace)
+            {
+                for line in content.trim_end().lines() {
+                    if !line.is_empty() {
+                        output.push_str(&indent);
+                        output.push('\t');
+                    }
+                    output.push_str(line);
+                    output.push('\n');
+                }
+                output.push('\n');
+            }
+            let children = self.render_modules(&namespace, depth + 1);
+            if !children.is_empty() {
+                output.push_str(children.trim_end());
+                output.push('\n');
+            }
+            output.truncate(output.trim_end().len());
+            output.push_str(&format!("\n{indent}}}\n\n"));
+        }
+
+        output
+    }
+
+    fn rust_field_name(name: &str) -> String {
+        if RUST_KEYWORDS.contains(&name) {
+            format!("r#{name}")
+        } else {
+            name.to_string()
+        }
+    }
+
+    fn rust_literal(
+        value: &Value,
+        r#type: Type,
+        type_index: usize,
+        schema: &Schema,
+        namespace: &str,
+    ) -> Result<String, String> {
+        match (r#type, value) {
+            (Type::Alias, value) => {
+                let alias_type = &schema.aliases[type_index];
+                let inner_text = Self::rust_literal(
+                    value,
+                    alias_type.inner_type,
+                    alias_type.inner_type_index,
+                    schema,
+                    namespace,
+                )?;
+
+                if alias_type.is_distinct {
+                    let alias_name = Self::rust_type_name(r#type, type_index, schema, namespace);
+                    Ok(format!("{alias_name}({inner_text})"))
+                } else {
+                    Ok(inner_text)
+                }
+            }
+            (Type::Enum, Value::Enum(variant_name)) => Ok(format!(
+                "{}::{variant_name}",
+                Self::rust_type_name(r#type, type_index, schema, namespace)
+            )),
+            (_, value) => {
+                scalar_literal(value).ok_or_else(|| format!("No Rust literal for {value:?}"))
+            }
+        }
+    }
+
+    fn output_mut<'a>(
+        &'a mut self,
+        state: &'a mut LanguageGeneratorState,
+        namespace: &str,
+    ) -> &'a mut String {
+        if namespace.is_empty() {
+            return &mut state.output_files[0].content;
+        }
+
+        let index = match self
+            .modules
+            .iter()
+            .position(|(module_namespace, _)| module_namespace == namespace)
+        {
+            Some(index) => index,
+            None => {
+                self.modules.push((namespace.to_string(), String::new()));
+                self.modules.len() - 1
+            }
+        };
+
+        &mut self.modules[index].1
     }
 }
 
@@ -58,41 +361,205 @@ impl LanguageGenerator for RustLanguageGenerator {
         let mut output_file_name = PathBuf::from(state.file_name);
         output_file_name.set_extension("rs");
 
-        let output_file = SourceFile::new(output_file_name);
+        let target = &state.schema.target;
+        let endianness = match target.endianness {
+            Endianness::Little => "little",
+            Endianness::Big => "big",
+        };
+        let output_file = SourceFile::new(output_file_name).contents(format!(
+            "const _: () = assert!(\n\tcfg!(target_pointer_width = \"{}\") && cfg!(target_endian = \"{endianness}\"),\n\t\"Generated for {} layout\"\n);\n\n",
+            target.pointer_width * 8,
+            target.name
+        ));
         state.output_files.push(output_file);
 
         Ok(())
     }
 
+    fn generate_constant(
+        &mut self,
+        state: &mut LanguageGeneratorState,
+        constant: &Constant,
+    ) -> Result<(), String> {
+        let type_name = Self::rust_type_name(
+            Type::Primitive,
+            constant.type_index,
+            state.schema,
+            &constant.namespace,
+        );
+
+        let output = self.output_mut(state, &constant.namespace);
+        output.push_str(&format!(
+            "{}pub const {}: {type_name} = {};\n\n",
+            doc_comment(&constant.doc, "///"),
+            constant.name,
+            constant.value
+        ));
+
+        Ok(())
+    }
+
     fn generate_data_type(
         &mut self,
         state: &mut LanguageGeneratorState,
         data_type: &DataType,
     ) -> Result<(), String> {
         let mut fields_text = String::new();
+        let mut accessors_text = String::new();
+        let mut defaults_text = String::new();
+        let mut default_setters_text = String::new();
+        let mut bitfield_units = 0;
+        let mut padding_fields = 0;
+
+        let layout = declared_layout(state.schema, data_type).map_err(|err| err.to_string())?;
+        for (field, &padding) in data_type.fields.iter().zip(&layout.paddings) {
+            if padding != 0 {
+                fields_text.push_str(&format!(
+                    "\tpub _padding{padding_fields}: [u8; {padding}],\n"
+                ));
+                defaults_text.push_str(&format!(
+                    "\t\t\t_padding{padding_fields}: [0; {padding}],\n"
+                ));
+                padding_fields += 1;
+            }
 
-        for field in &data_type.fields {
-            let type_data = state.schema.type_data(field.type_index, field.r#type)?;
+            let type_data = state
+                .schema
+                .type_data(field.type_index, field.r#type)
+                .map_err(|err| err.to_string())?;
 
             if !state.is_generated(&type_data) {
                 self.generate_from_type_and_index(state, field.r#type, field.type_index)?;
             }
 
-            let field_type_name =
-                Self::rust_type_name(field.r#type, field.type_index, state.schema);
+            let field_type_name = Self::rust_type_name(
+                field.r#type,
+                field.type_index,
+                state.schema,
+                &data_type.namespace,
+            );
+
+            let default_text = field
+                .default
+                .as_ref()
+                .map(|default| {
+                    Self::rust_literal(
+                        default,
+                        field.r#type,
+                        field.type_index,
+                        state.schema,
+                        &data_type.namespace,
+                    )
+                })
+                .transpose()?;
+
+            let field_doc = doc_comment(&field.doc, "\t///");
+            let Some(bitfield) = field.bitfield else {
+                fields_text.push_str(&format!(
+                    "{field_doc}\tpub {}: {field_type_name},\n",
+                    Self::rust_field_name(&field.name)
+                ));
+                // Fields without a default start zeroed, like they do in the other languages
+                defaults_text.push_str(&format!(
+                    "\t\t\t{}: {},\n",
+                    Self::rust_field_name(&field.name),
+                    default_text
+                        .as_deref()
+                        .unwrap_or("unsafe { std::mem::zeroed() }")
+                ));
+                continue;
+            };
+
+            if !field.shares_storage() {
+                bitfield_units += 1;
+                fields_text.push_str(&format!(
+                    "\tpub bitfield{}: {field_type_name},\n",
+                    bitfield_units - 1
+                ));
+                defaults_text.push_str(&format!("\t\t\tbitfield{}: 0,\n", bitfield_units - 1));
+            }
+            if let Some(default_text) = default_text {
+                default_setters_text
+                    .push_str(&format!("\t\tvalue.set_{}({default_text});\n", field.name));
+            }
 
-            fields_text.push_str(&format!("\tpub {}: {field_type_name},\n", field.name));
+            let storage = format!("bitfield{}", bitfield_units - 1);
+            let storage_bits = state
+                .schema
+                .try_type_size(field.r#type, field.type_index)
+                .map_err(|err| err.to_string())?
+                * 8;
+            let left_shift = storage_bits as u32 - bitfield.bit_offset - bitfield.bit_width;
+            let right_shift = storage_bits as u32 - bitfield.bit_width;
+            let mask = if field_type_name.starts_with('i') {
+                format!(
+                    "({:#x}{} as {field_type_name})",
+                    bitfield.mask(),
+                    field_type_name.replacen('i', "u", 1)
+                )
+            } else {
+                format!("{:#x}", bitfield.mask())
+            };
+
+            accessors_text.push_str(&format!(
+                "{field_doc}\tpub fn {}(&self) -> {field_type_name} {{\n\t\t(self.{storage} << {left_shift}) >> {right_shift}\n\t}}\n\n",
+                Self::rust_field_name(&field.name)
+            ));
+            accessors_text.push_str(&format!(
+                "\tpub fn set_{}(&mut self, value: {field_type_name}) {{\n\t\tself.{storage} = (self.{storage} & !{mask}) | ((value << {}) & {mask});\n\t}}\n\n",
+                field.name, bitfield.bit_offset
+            ));
         }
 
-        let is_copy = data_type.is_copy(state.schema);
-        let output = &mut Self::output_file_mut(state).content;
+        let is_copy = data_type
+            .try_is_copy(state.schema)
+            .map_err(|err| err.to_string())?;
+        let repr = match layout.align {
+            _ if layout.is_packed => "C, packed".to_string(),
+            Some(align) => format!("C, align({align})"),
+            None => "C".to_string(),
+        };
+        // Derives can't take references to the fields of packed structs, so those have to be Copy
+        let derive = match (is_copy, layout.is_packed) {
+            (true, _) => "#[derive(Copy, Clone, Debug)]\n",
+            (false, false) => "#[derive(Clone, Debug)]\n",
+            (false, true) => "",
+        };
+        let lifetime = Self::lifetime(
+            state.schema,
+            data_type
+                .fields
+                .iter()
+                .map(|field| (field.r#type, field.type_index)),
+        );
+        let output = self.output_mut(state, &data_type.namespace);
         output.push_str(&format!(
-            "#[repr(C)]\n#[derive({}Clone, Debug)]\npub struct {} {{\n{fields_text}}}\n\n",
-            if is_copy { "Copy, " } else { "" },
+            "{}#[repr({repr})]\n{derive}pub struct {}{lifetime} {{\n{fields_text}}}\n\n",
+            doc_comment(&data_type.doc, "///"),
             data_type.name
         ));
+        if !accessors_text.is_empty() {
+            accessors_text.truncate(accessors_text.len() - 1);
+            output.push_str(&format!(
+                "impl{lifetime} {}{lifetime} {{\n{accessors_text}}}\n\n",
+                data_type.name
+            ));
+        }
+        if data_type.fields.iter().any(|field| field.default.is_some()) {
+            let body_text = if default_setters_text.is_empty() {
+                format!("\t\tSelf {{\n{defaults_text}\t\t}}\n")
+            } else {
+                format!(
+                    "\t\tlet mut value = Self {{\n{defaults_text}\t\t}};\n{default_setters_text}\t\tvalue\n"
+                )
+            };
+            output.push_str(&format!(
+                "impl{lifetime} Default for {}{lifetime} {{\n\tfn default() -> Self {{\n{body_text}\t}}\n}}\n\n",
+                data_type.name
+            ));
+        }
 
-        state.mark_generated(&data_type.name);
+        state.mark_generated(&data_type.namespace, &data_type.name);
         Ok(())
     }
 
@@ -103,16 +570,114 @@ impl LanguageGenerator for RustLanguageGenerator {
     ) -> Result<(), String> {
         let mut variants_text = "\n".to_string();
         for variant in &enum_type.variants {
-            variants_text.push_str(&format!("\t{variant},\n"));
+            variants_text.push_str(&format!(
+                "{}\t{} = {},\n",
+                doc_comment(&variant.doc, "\t///"),
+                variant.name,
+                variant.value
+            ));
         }
-
-        let output = &mut Self::output_file_mut(state).content;
+        let repr_name = Self::rust_type_name(
+            Type::Primitive,
+            enum_type.repr_index,
+            state.schema,
+            &enum_type.namespace,
+        );
+
+        let output = self.output_mut(state, &enum_type.namespace);
         output.push_str(&format!(
-            "#[repr(C)]\n#[derive(Copy, Clone, Debug)]\npub enum {} {{{variants_text}}}\n\n",
+            "{}#[repr({repr_name})]\n#[derive(Copy, Clone, Debug)]\npub enum {} {{{variants_text}}}\n\n",
+            doc_comment(&enum_type.doc, "///"),
             enum_type.name
         ));
 
-        state.mark_generated(&enum_type.name);
+        state.mark_generated(&enum_type.namespace, &enum_type.name);
+        Ok(())
+    }
+
+    fn generate_flags_type(
+        &mut self,
+        state: &mut LanguageGeneratorState,
+        flags_type: &FlagsType,
+    ) -> Result<(), String> {
+        let repr_name = Self::rust_type_name(
+            Type::Primitive,
+            flags_type.repr_index,
+            state.schema,
+            &flags_type.namespace,
+        );
+        let name = &flags_type.name;
+
+        let mut consts_text = String::new();
+        for bit in &flags_type.bits {
+            consts_text.push_str(&format!(
+                "{}\tpub const {}: Self = Self(1 << {});\n",
+                doc_comment(&bit.doc, "\t///"),
+                bit.name.to_snake().to_uppercase(),
+                bit.bit
+            ));
+        }
+
+        let output = self.output_mut(state, &flags_type.namespace);
+        output.push_str(&format!(
+            "{}#[repr(transparent)]\n#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]\npub struct {name}(pub {repr_name});\n\n",
+            doc_comment(&flags_type.doc, "///")
+        ));
+        output.push_str(&format!(
+            "impl {name} {{\n{consts_text}\n\tpub const fn empty() -> Self {{\n\t\tSelf(0)\n\t}}\n\n\tpub const fn bits(self) -> {repr_name} {{\n\t\tself.0\n\t}}\n\n\tpub const fn contains(self, other: Self) -> bool {{\n\t\tself.0 & other.0 == other.0\n\t}}\n\n\tpub fn insert(&mut self, other: Self) {{\n\t\tself.0 |= other.0;\n\t}}\n\n\tpub fn remove(&mut self, other: Self) {{\n\t\tself.0 &= !other.0;\n\t}}\n\n\tpub fn toggle(&mut self, other: Self) {{\n\t\tself.0 ^= other.0;\n\t}}\n\n\tpub fn set(&mut self, other: Self, value: bool) {{\n\t\tif value {{\n\t\t\tself.insert(other);\n\t\t}} else {{\n\t\t\tself.remove(other);\n\t\t}}\n\t}}\n}}\n\n"
+        ));
+        output.push_str(&format!(
+            "impl std::ops::BitOr for {name} {{\n\ttype Output = Self;\n\n\tfn bitor(self, other: Self) -> Self {{\n\t\tSelf(self.0 | other.0)\n\t}}\n}}\n\nimpl std::ops::BitOrAssign for {name} {{\n\tfn bitor_assign(&mut self, other: Self) {{\n\t\tself.0 |= other.0;\n\t}}\n}}\n\nimpl std::ops::BitAnd for {name} {{\n\ttype Output = Self;\n\n\tfn bitand(self, other: Self) -> Self {{\n\t\tSelf(self.0 & other.0)\n\t}}\n}}\n\n"
+        ));
+
+        state.mark_generated(&flags_type.namespace, name);
+        Ok(())
+    }
+
+    fn generate_alias_type(
+        &mut self,
+        state: &mut LanguageGeneratorState,
+        alias_type: &AliasType,
+    ) -> Result<(), String> {
+        let inner_type_data = state
+            .schema
+            .type_data(alias_type.inner_type_index, alias_type.inner_type)
+            .map_err(|err| err.to_string())?;
+        if !state.is_generated(&inner_type_data) {
+            self.generate_from_type_and_index(
+                state,
+                alias_type.inner_type,
+                alias_type.inner_type_index,
+            )?;
+        }
+
+        let inner_type_name = Self::rust_type_name(
+            alias_type.inner_type,
+            alias_type.inner_type_index,
+            state.schema,
+            &alias_type.namespace,
+        );
+
+        let lifetime = Self::lifetime(
+            state.schema,
+            [(alias_type.inner_type, alias_type.inner_type_index)],
+        );
+        let output = self.output_mut(state, &alias_type.namespace);
+        output.push_str(&doc_comment(&alias_type.doc, "///"));
+        if alias_type.is_distinct {
+            output.push_str(&format!(
+                "#[repr(transparent)]\n#[derive({}Clone, Debug)]\npub struct {}{lifetime}(pub {inner_type_name});\n\n",
+                if inner_type_data.is_copy { "Copy, " } else { "" },
+                alias_type.name
+            ));
+        } else {
+            output.push_str(&format!(
+                "pub type {}{lifetime} = {inner_type_name};\n\n",
+                alias_type.name
+            ));
+        }
+
+        state.mark_generated(&alias_type.namespace, &alias_type.name);
         Ok(())
     }
 
@@ -122,34 +687,57 @@ impl LanguageGenerator for RustLanguageGenerator {
         union_type: &binterop::types::union::UnionType,
     ) -> Result<(), String> {
         let mut enum_type = EnumType::new(&format!("{}Variant", union_type.name), &[], &[]);
+        enum_type.namespace = union_type.namespace.clone();
         enum_type.variants = union_type
-            .possible_types
+            .variants
             .iter()
-            .map(|&(type_index, r#type)| Self::rust_type_name(r#type, type_index, state.schema))
+            .enumerate()
+            .map(|(tag, variant)| {
+                let mut enum_variant = EnumVariant::new(&variant.name.to_camel(), tag as i128);
+                enum_variant.doc = variant.doc.clone();
+                enum_variant
+            })
             .collect();
         self.generate_enum_type(state, &enum_type)?;
 
         let mut union_fields_text = String::new();
-        for (type_index, r#type) in union_type.possible_types.iter().copied() {
-            let type_name = Self::rust_type_name(r#type, type_index, state.schema);
-            let field_name = type_name.to_snake();
+        for variant in &union_type.variants {
+            let Some((type_index, r#type)) = variant.payload else {
+                continue;
+            };
+            let type_name =
+                Self::rust_type_name(r#type, type_index, state.schema, &union_type.namespace);
+            let field_name = Self::rust_field_name(&variant.name.to_snake());
 
             union_fields_text.push_str(&format!(
-                "\tpub {field_name}: std::mem::ManuallyDrop<{type_name}>,\n"
+                "{}\tpub {field_name}: std::mem::ManuallyDrop<{type_name}>,\n",
+                doc_comment(&variant.doc, "\t///")
             ));
         }
+        // Rust doesn't allow unions without fields
+        if union_fields_text.is_empty() {
+            union_fields_text.push_str("\tpub none: (),\n");
+        }
 
         let is_copy = union_type.is_copy(state.schema);
-        let output = &mut Self::output_file_mut(state).content;
+        let lifetime = Self::lifetime(
+            state.schema,
+            union_type
+                .payloads()
+                .map(|(payload_index, payload_type)| (payload_type, payload_index)),
+        );
+        let output = self.output_mut(state, &union_type.namespace);
         let union_type_name = &union_type.name;
         output.push_str(&format!(
-            "#[repr(C)]\npub union {union_type_name}Union {{\n{union_fields_text}}}\n\n",
+            "#[repr(C)]\npub union {union_type_name}Union{lifetime} {{\n{union_fields_text}}}\n\n",
         ));
 
-        output.push_str(&format!("#[repr(C)]\n#[derive({}Clone, Debug)]\npub struct {union_type_name} {{\n\tpub variant: {union_type_name}Variant,\n\tpub data: {union_type_name}Union\n}}\n\n",
+        output.push_str(&format!("{}#[repr(C)]\n#[derive({}Clone, Debug)]\npub struct {union_type_name}{lifetime} {{\n\tpub variant: {union_type_name}Variant,\n\tpub data: {union_type_name}Union{lifetime}\n}}\n\n",
+            doc_comment(&union_type.doc, "///"),
             if is_copy { "Copy, " } else { "" }
         ));
 
+        state.mark_generated(&union_type.namespace, &union_type.name);
         Ok(())
     }
 
@@ -171,8 +759,12 @@ impl LanguageGenerator for RustLanguageGenerator {
             .iter()
             .map(|arg| {
                 let type_data = arg.r#type.unwrap();
-                let type_name =
-                    Self::rust_type_name(type_data.r#type, type_data.index, state.schema);
+                let type_name = Self::rust_type_name(
+                    type_data.r#type,
+                    type_data.index,
+                    state.schema,
+                    &function_type.namespace,
+                );
 
                 format!("{}: {type_name}", arg.name)
             })
@@ -188,18 +780,40 @@ impl LanguageGenerator for RustLanguageGenerator {
                         return_type_data.r#type,
                         return_type_data.index,
                         state.schema,
+                        &function_type.namespace
                     )
                 )
             })
             .unwrap_or_default();
 
-        let output = &mut Self::output_file_mut(state).content;
+        let lifetime = Self::lifetime(
+            state.schema,
+            function_type
+                .args
+                .iter()
+                .filter_map(|arg| arg.r#type)
+                .chain(function_type.return_type)
+                .map(|type_data| (type_data.r#type, type_data.index)),
+        );
+        let output = self.output_mut(state, &function_type.namespace);
         output.push_str(&format!(
-            "type {} = extern \"C\" fn({args_text}){return_type_text};\n",
+            "{}type {}{lifetime} = extern \"C\" fn({args_text}){return_type_text};\n",
+            doc_comment(&function_type.doc, "///"),
             function_type.name.to_snake(),
         ));
 
-        state.mark_generated(&function_type.name);
+        state.mark_generated(&function_type.namespace, &function_type.name);
         Ok(())
     }
+
+    fn finish(
+        &mut self,
+        state: &mut LanguageGeneratorState,
+        output_dir_path: &Path,
+    ) -> Result<(), String> {
+        let modules = self.render_modules("", 0);
+        state.output_files[0].content.push_str(&modules);
+
+        write_output_files(state, output_dir_path)
+    }
 }
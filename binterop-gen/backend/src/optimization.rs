@@ -1,5 +1,8 @@
+use binterop::attributes::invalid_attribute;
+use binterop::dependency::layout_order;
+use binterop::error::Error;
 use binterop::schema::Schema;
-use std::alloc::Layout;
+use binterop::types::Type;
 use std::{env, slice};
 
 #[derive(Copy, Clone)]
@@ -26,52 +29,108 @@ impl SchemaOptimizations {
     }
 }
 
-fn optimize_data_type_layouts(schema: &mut Schema) {
-    let mut field_sizes = schema
+fn optimize_data_type_layouts(schema: &mut Schema) -> Result<(), Error> {
+    let field_sizes = schema
         .types
         .iter()
-        .flat_map(|data_type| data_type.fields.iter().map(|field| field.size(schema)))
-        .collect::<Vec<_>>();
+        .flat_map(|data_type| data_type.fields.iter().map(|field| field.try_size(schema)))
+        .collect::<Result<Vec<_>, _>>()?;
     let mut field_sizes_cursor = 0;
 
     for data_type in &mut schema.types {
         let field_sizes =
-            &mut field_sizes[field_sizes_cursor..field_sizes_cursor + data_type.fields.len()];
+            &field_sizes[field_sizes_cursor..field_sizes_cursor + data_type.fields.len()];
         field_sizes_cursor += data_type.fields.len();
 
-        let mut permutation = permutation::sort_unstable_by(&field_sizes, |f1, f2| f1.cmp(f2));
-        permutation.apply_slice_in_place(&mut data_type.fields);
-        permutation.apply_slice_in_place(field_sizes);
-
-        let mut field_offset = 0;
-        for (field, &size) in data_type.fields.iter_mut().zip(field_sizes.iter()) {
-            field.offset = field_offset;
-            field_offset += size;
+        if data_type.try_keeps_order()? {
+            continue;
         }
+
+        // Bitfields sharing a storage unit have to stay together and in order
+        let mut unit_start = 0;
+        let sort_keys = data_type
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                if !field.shares_storage() {
+                    unit_start = index;
+                }
+                (field_sizes[unit_start], unit_start, index)
+            })
+            .collect::<Vec<_>>();
+
+        let mut permutation = permutation::sort_unstable_by(&sort_keys, |k1, k2| k1.cmp(k2));
+        permutation.apply_slice_in_place(&mut data_type.fields);
     }
+
+    Ok(())
 }
 
-fn add_padding(schema: &mut Schema) {
+// Without padding every field is placed right after the previous one, unless it has an explicit
+// offset. Types are laid out after the types they contain, since their sizes depend on those
+pub fn assign_offsets(schema: &mut Schema, add_padding: bool) -> Result<(), Error> {
+    let order = layout_order(schema)?;
     let types = unsafe { slice::from_raw_parts_mut(schema.types.as_mut_ptr(), schema.types.len()) };
 
-    for data_type in types {
-        let mut layout = Layout::from_size_align(0, 1).unwrap();
+    for (r#type, index) in order {
+        if r#type != Type::Data {
+            continue;
+        }
+        let data_type = &mut types[index];
+        let is_packed = !add_padding || data_type.try_is_packed()?;
+
+        let mut fields_end = 0;
+        let mut unit_offset = 0;
         for field in &mut data_type.fields {
-            let field_layout = field.layout(schema);
-            let (new_layout, offset) = layout.extend(field_layout).unwrap();
-            layout = new_layout;
+            if field.shares_storage() {
+                field.offset = unit_offset;
+                continue;
+            }
+
+            let in_field = |err: Error| err.in_member(&data_type.name, &field.name);
+
+            let size = field.try_size(schema).map_err(in_field)?;
+            let align = match is_packed {
+                true => 1,
+                false => field.try_align(schema).map_err(in_field)?,
+            };
+            let offset = match field.try_offset_attribute().map_err(in_field)? {
+                Some(offset) if offset < fields_end => Err(in_field(invalid_attribute(
+                    "offset",
+                    &offset.to_string(),
+                    "overlaps the previous field",
+                ))),
+                Some(offset) if offset % align != 0 => Err(in_field(invalid_attribute(
+                    "offset",
+                    &offset.to_string(),
+                    &format!("not a multiple of the field align {align}"),
+                ))),
+                Some(offset) => Ok(offset),
+                None => Ok(fields_end.next_multiple_of(align)),
+            }?;
+
             field.offset = offset;
+            unit_offset = offset;
+            fields_end = offset + size;
         }
     }
 
-    schema.is_packed = false;
+    Ok(())
 }
 
-pub fn optimize_schema(schema: &mut Schema, optimizations: SchemaOptimizations) {
-    if optimizations.data_type_layout {
-        optimize_data_type_layouts(schema);
+pub fn optimize_schema(
+    schema: &mut Schema,
+    optimizations: SchemaOptimizations,
+) -> Result<(), Error> {
+    for data_type in &schema.types {
+        data_type.try_check_attributes()?;
     }
-    if optimizations.add_padding {
-        add_padding(schema);
+
+    if optimizations.data_type_layout {
+        optimize_data_type_layouts(schema)?;
     }
+
+    schema.is_packed = !optimizations.add_padding;
+    assign_offsets(schema, optimizations.add_padding)
 }
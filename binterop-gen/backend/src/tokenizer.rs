@@ -11,59 +11,311 @@ use std::{
 type ParserExtra<'a> =
     extra::Full<Rich<'a, char>, extra::SimpleState<ParserState<'a, VecDeque<Token<'a>>>>, ()>;
 
+#[derive(Debug)]
+pub enum Expr<'a> {
+    Literal(i128),
+    Constant(&'a str),
+    Binary(Box<Expr<'a>>, BinaryOp, Box<Expr<'a>>),
+}
+
+#[derive(Debug)]
+pub enum Literal<'a> {
+    Bool(bool),
+    Float(f64),
+    Integer(Expr<'a>),
+    Variant(&'a str, &'a str),
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
 #[derive(Debug)]
 pub enum Type<'a> {
     Named(&'a str),
-    Array(Box<Type<'a>>, usize),
+    Generic(&'a str, Vec<Type<'a>>),
+    Array(Box<Type<'a>>, Expr<'a>),
     Vector(Box<Type<'a>>),
     Pointer(Box<Type<'a>>),
+    Optional(Box<Type<'a>>),
+    Map(Box<Type<'a>>, Box<Type<'a>>),
+    Slice(Box<Type<'a>>),
+    InlineString(usize),
+    InlineVector(Box<Type<'a>>, usize),
 }
 
 #[derive(Debug)]
 pub enum Token<'a> {
     Struct(
+        String,
+        Vec<(String, String)>,
+        &'a str,
+        Vec<&'a str>,
+        Vec<StructField<'a>>,
+    ),
+    Enum(
+        String,
         Vec<(String, String)>,
         &'a str,
-        Vec<(Vec<(String, String)>, &'a str, Type<'a>)>,
+        Option<&'a str>,
+        Vec<(String, &'a str, Option<i128>)>,
     ),
-    Enum(Vec<(String, String)>, &'a str, Vec<&'a str>),
-    Union(Vec<(String, String)>, &'a str, Vec<&'a str>),
+    Union(
+        String,
+        Vec<(String, String)>,
+        &'a str,
+        Vec<(String, &'a str, Option<Type<'a>>)>,
+    ),
+    Flags(
+        String,
+        Vec<(String, String)>,
+        &'a str,
+        Option<&'a str>,
+        Vec<(String, &'a str, Option<u128>)>,
+    ),
+    Const(String, Vec<(String, String)>, &'a str, &'a str, Expr<'a>),
+    Alias(String, Vec<(String, String)>, &'a str, Type<'a>, bool),
+    Package(&'a str),
     Include(PathBuf, VecDeque<Token<'a>>),
-    Function(&'a str, Vec<(&'a str, Type<'a>)>, Option<Type<'a>>),
+    Function(String, &'a str, Vec<(&'a str, Type<'a>)>, Option<Type<'a>>),
+}
+
+pub type StructField<'a> = (
+    String,
+    Vec<(String, String)>,
+    &'a str,
+    Type<'a>,
+    Option<u32>,
+    Option<(Literal<'a>, &'a str)>,
+);
+
+fn doc_marker<'a>() -> impl Parser<'a, &'a str, &'a str, ParserExtra<'a>> + Clone {
+    just("///").then_ignore(just('/').not())
+}
+
+// Comments are skipped like whitespace, except for doc comments which are only allowed right
+// before the declaration or member they document
+fn padding<'a>() -> impl Parser<'a, &'a str, (), ParserExtra<'a>> + Clone {
+    let line_comment = just("//")
+        .and_is(doc_marker().not())
+        .then(any().and_is(text::newline().not()).repeated());
+    let block_comment = just("/*")
+        .then(any().and_is(just("*/").not()).repeated())
+        .then(just("*/"));
+
+    choice((
+        text::whitespace().at_least(1).ignored(),
+        line_comment.ignored(),
+        block_comment.ignored(),
+    ))
+    .repeated()
+}
+
+fn doc_parser<'a>() -> impl Parser<'a, &'a str, String, ParserExtra<'a>> + Clone {
+    doc_marker()
+        .ignore_then(any().and_is(text::newline().not()).repeated().to_slice())
+        .padded_by(padding())
+        .repeated()
+        .collect::<Vec<&str>>()
+        .map(|lines| {
+            lines
+                .iter()
+                .map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+}
+
+fn expr_parser<'a>() -> impl Parser<'a, &'a str, Expr<'a>, ParserExtra<'a>> + Clone {
+    recursive(|expr_parser| {
+        let hex_literal = just("0x")
+            .ignore_then(text::int(16))
+            .try_map(|value: &str, span| {
+                i128::from_str_radix(value, 16).map_err(|e| Rich::custom(span, e))
+            });
+        let decimal_literal = just('-')
+            .or_not()
+            .then(text::int(10))
+            .to_slice()
+            .try_map(|value: &str, span| value.parse().map_err(|e| Rich::custom(span, e)));
+        let literal = hex_literal.or(decimal_literal).map(Expr::Literal);
+
+        let atom = choice((
+            literal,
+            qualified_ident_parser().map(Expr::Constant),
+            expr_parser.delimited_by(
+                just('(').padded_by(padding()),
+                just(')').padded_by(padding()),
+            ),
+        ))
+        .padded_by(padding());
+
+        let binary = |lhs, (op, rhs)| Expr::Binary(Box::new(lhs), op, Box::new(rhs));
+        let product = atom.clone().foldl(
+            choice((
+                just('*').to(BinaryOp::Mul),
+                just('/').then_ignore(one_of("/*").not()).to(BinaryOp::Div),
+            ))
+            .padded_by(padding())
+            .then(atom)
+            .repeated(),
+            binary,
+        );
+
+        product.clone().foldl(
+            choice((just('+').to(BinaryOp::Add), just('-').to(BinaryOp::Sub)))
+                .padded_by(padding())
+                .then(product)
+                .repeated(),
+            binary,
+        )
+    })
+}
+
+// Enum variants are written as Enum.Variant, other identifiers are constants
+fn literal_parser<'a>() -> impl Parser<'a, &'a str, (Literal<'a>, &'a str), ParserExtra<'a>> {
+    let bool_literal = text::keyword("true")
+        .to(true)
+        .or(text::keyword("false").to(false))
+        .map(Literal::Bool);
+    let float_literal = just('-')
+        .or_not()
+        .then(text::int(10))
+        .then(just('.'))
+        .then(text::digits(10))
+        .to_slice()
+        .try_map(|value: &str, span| {
+            value
+                .parse()
+                .map(Literal::Float)
+                .map_err(|e| Rich::custom(span, e))
+        });
+    let variant_literal = qualified_ident_parser().try_map(|path: &str, span| {
+        path.rsplit_once('.')
+            .map(|(enum_name, variant_name)| Literal::Variant(enum_name, variant_name))
+            .ok_or_else(|| Rich::custom(span, "expected an enum variant"))
+    });
+
+    choice((
+        bool_literal,
+        float_literal,
+        variant_literal,
+        expr_parser().map(Literal::Integer),
+    ))
+    .map_with(|literal, extra| (literal, extra.slice()))
+    .padded_by(padding())
+}
+
+fn qualified_ident_parser<'a>() -> impl Parser<'a, &'a str, &'a str, ParserExtra<'a>> + Clone {
+    text::ident().separated_by(just('.')).at_least(1).to_slice()
 }
 
 fn type_parser<'a>() -> impl Parser<'a, &'a str, Type<'a>, ParserExtra<'a>> {
     recursive(|type_parser| {
-        let named_parser = text::ident().map(Type::Named);
+        let named_parser = qualified_ident_parser()
+            .then(
+                type_parser
+                    .clone()
+                    .separated_by(just(','))
+                    .at_least(1)
+                    .collect()
+                    .delimited_by(
+                        just('<').padded_by(padding()),
+                        just('>').padded_by(padding()),
+                    )
+                    .or_not(),
+            )
+            .map(|(name, args)| match args {
+                Some(args) => Type::Generic(name, args),
+                None => Type::Named(name),
+            });
 
         let array_parser = type_parser
             .clone()
-            .padded()
-            .then_ignore(just(':').padded())
-            .then(text::int(10))
-            .delimited_by(just('[').padded(), just(']').padded())
-            .try_map(|(inner_type, size): (Type, &str), span| {
-                let size = size.parse().map_err(|e| Rich::custom(span, e))?;
-                Ok(Type::Array(Box::new(inner_type), size))
-            });
+            .padded_by(padding())
+            .then_ignore(just(':').padded_by(padding()))
+            .then(expr_parser())
+            .delimited_by(
+                just('[').padded_by(padding()),
+                just(']').padded_by(padding()),
+            )
+            .map(|(inner_type, len)| Type::Array(Box::new(inner_type), len));
 
         let vector_parser = type_parser
             .clone()
-            .padded()
-            .delimited_by(just('<').padded(), just('>').padded())
-            .map(|inner_type| Type::Vector(Box::new(inner_type)));
+            .padded_by(padding())
+            .then(
+                just(':')
+                    .padded_by(padding())
+                    .ignore_then(text::int(10))
+                    .or_not(),
+            )
+            .delimited_by(
+                just('<').padded_by(padding()),
+                just('>').padded_by(padding()),
+            )
+            .try_map(
+                |(inner_type, capacity): (Type, Option<&str>), span| match capacity {
+                    Some(capacity) => {
+                        let capacity = capacity.parse().map_err(|e| Rich::custom(span, e))?;
+                        Ok(Type::InlineVector(Box::new(inner_type), capacity))
+                    }
+                    None => Ok(Type::Vector(Box::new(inner_type))),
+                },
+            );
+
+        let inline_string_parser = just("String")
+            .ignore_then(text::int(10).delimited_by(
+                just('<').padded_by(padding()),
+                just('>').padded_by(padding()),
+            ))
+            .try_map(|capacity: &str, span| {
+                let capacity = capacity.parse().map_err(|e| Rich::custom(span, e))?;
+                Ok(Type::InlineString(capacity))
+            });
 
-        let base_type_parser = choice((array_parser, vector_parser, named_parser));
+        let map_parser = type_parser
+            .clone()
+            .padded_by(padding())
+            .then_ignore(just(':').padded_by(padding()))
+            .then(type_parser.clone().padded_by(padding()))
+            .delimited_by(
+                just('{').padded_by(padding()),
+                just('}').padded_by(padding()),
+            )
+            .map(|(key_type, value_type)| Type::Map(Box::new(key_type), Box::new(value_type)));
+
+        let slice_parser = type_parser
+            .clone()
+            .padded_by(padding())
+            .delimited_by(
+                just("&<").padded_by(padding()),
+                just('>').padded_by(padding()),
+            )
+            .map(|inner_type| Type::Slice(Box::new(inner_type)));
+
+        let base_type_parser = choice((
+            array_parser,
+            vector_parser,
+            slice_parser,
+            map_parser,
+            inline_string_parser,
+            named_parser,
+        ));
 
         base_type_parser
-            .then(just('*').padded().repeated().count())
-            .map(|(mut ty, count)| {
-                for _ in 0..count {
-                    ty = Type::Pointer(Box::new(ty));
-                }
-                ty
-            })
-            .padded()
+            .foldl(
+                one_of("*?").padded_by(padding()).repeated(),
+                |ty, suffix| match suffix {
+                    '*' => Type::Pointer(Box::new(ty)),
+                    _ => Type::Optional(Box::new(ty)),
+                },
+            )
+            .padded_by(padding())
     })
 }
 
@@ -75,12 +327,15 @@ fn attributes_parser<'a>() -> impl Parser<'a, &'a str, Vec<(String, String)>, Pa
         .map(ToString::to_string)
         .then_ignore(just('='))
         .then(string_parser)
-        .padded();
+        .padded_by(padding());
     let attributes = attribute
         .separated_by(just(','))
         .collect()
-        .delimited_by(just("@[").padded(), just(']').padded())
-        .padded();
+        .delimited_by(
+            just("@[").padded_by(padding()),
+            just(']').padded_by(padding()),
+        )
+        .padded_by(padding());
 
     attributes
 }
@@ -88,38 +343,71 @@ fn attributes_parser<'a>() -> impl Parser<'a, &'a str, Vec<(String, String)>, Pa
 fn fields_parser<'a>(
     delimiter_start: char,
     delimiter_end: char,
-) -> impl Parser<'a, &'a str, Vec<(Vec<(String, String)>, &'a str, Type<'a>)>, ParserExtra<'a>> {
-    let field = attributes_parser()
-        .or_not()
-        .map(Option::unwrap_or_default)
+) -> impl Parser<'a, &'a str, Vec<StructField<'a>>, ParserExtra<'a>> {
+    let bit_width = just(':')
+        .padded_by(padding())
+        .ignore_then(text::int(10))
+        .try_map(|bit_width: &str, span| bit_width.parse().map_err(|e| Rich::custom(span, e)))
+        .padded_by(padding());
+
+    let field = doc_parser()
+        .then(attributes_parser().or_not().map(Option::unwrap_or_default))
         .then(text::ident())
-        .padded()
+        .padded_by(padding())
         .then_ignore(just(':'))
         .then(type_parser())
-        .map(|((attributes, field_name), ty)| (attributes, field_name, ty));
+        .then(bit_width.or_not())
+        .then(
+            just('=')
+                .padded_by(padding())
+                .ignore_then(literal_parser())
+                .or_not(),
+        )
+        .map(
+            |(((((doc, attributes), field_name), ty), bit_width), default)| {
+                (doc, attributes, field_name, ty, bit_width, default)
+            },
+        );
 
     field
         .separated_by(just(','))
         .allow_trailing()
         .collect()
-        .delimited_by(just(delimiter_start).padded(), just(delimiter_end).padded())
+        .delimited_by(
+            just(delimiter_start).padded_by(padding()),
+            just(delimiter_end).padded_by(padding()),
+        )
 }
 
 fn struct_parser<'a>() -> impl Parser<'a, &'a str, Token<'a>, ParserExtra<'a>> {
-    let struct_decl = attributes_parser()
-        .or_not()
-        .map(Option::unwrap_or_default)
+    let struct_decl = doc_parser()
+        .then(attributes_parser().or_not().map(Option::unwrap_or_default))
         .then_ignore(text::keyword("struct"))
-        .padded()
-        .then(text::ident().padded())
-        .map(|(attributes, name)| Token::Struct(attributes, name, Vec::new()));
+        .padded_by(padding())
+        .then(text::ident().padded_by(padding()))
+        .then(
+            text::ident()
+                .padded_by(padding())
+                .separated_by(just(','))
+                .at_least(1)
+                .collect()
+                .delimited_by(
+                    just('<').padded_by(padding()),
+                    just('>').padded_by(padding()),
+                )
+                .or_not()
+                .map(Option::unwrap_or_default),
+        )
+        .map(|(((doc, attributes), name), params)| {
+            Token::Struct(doc, attributes, name, params, Vec::new())
+        });
 
     let fields = fields_parser('{', '}');
 
     struct_decl
         .then(fields)
         .map(|(mut struct_decl, fields)| {
-            if let Token::Struct(_, _, struct_fields) = &mut struct_decl {
+            if let Token::Struct(_, _, _, _, struct_fields) = &mut struct_decl {
                 *struct_fields = fields;
             } else {
                 unreachable!(
@@ -129,31 +417,67 @@ fn struct_parser<'a>() -> impl Parser<'a, &'a str, Token<'a>, ParserExtra<'a>> {
 
             struct_decl
         })
-        .padded()
+        .padded_by(padding())
 }
 
-fn variants_parser<'a>() -> impl Parser<'a, &'a str, Vec<&'a str>, ParserExtra<'a>> {
-    text::ident()
-        .padded()
+fn union_variants_parser<'a>()
+-> impl Parser<'a, &'a str, Vec<(String, &'a str, Option<Type<'a>>)>, ParserExtra<'a>> {
+    doc_parser()
+        .then(text::ident().padded_by(padding()))
+        .then(just(':').ignore_then(type_parser()).or_not())
+        .map(|((doc, name), payload)| (doc, name, payload))
         .separated_by(just(','))
         .allow_trailing()
         .collect()
-        .delimited_by(just('{').padded(), just('}').padded())
+        .delimited_by(
+            just('{').padded_by(padding()),
+            just('}').padded_by(padding()),
+        )
 }
 
-fn enum_parser<'a>() -> impl Parser<'a, &'a str, Token<'a>, ParserExtra<'a>> {
-    let enum_decl = attributes_parser()
+fn enum_variants_parser<'a>()
+-> impl Parser<'a, &'a str, Vec<(String, &'a str, Option<i128>)>, ParserExtra<'a>> {
+    let value = just('-')
         .or_not()
-        .map(Option::unwrap_or_default)
+        .then(text::int(10))
+        .to_slice()
+        .try_map(|value: &str, span| value.parse().map_err(|e| Rich::custom(span, e)))
+        .padded_by(padding());
+
+    doc_parser()
+        .then(text::ident().padded_by(padding()))
+        .then(just('=').padded_by(padding()).ignore_then(value).or_not())
+        .map(|((doc, name), value)| (doc, name, value))
+        .separated_by(just(','))
+        .allow_trailing()
+        .collect()
+        .delimited_by(
+            just('{').padded_by(padding()),
+            just('}').padded_by(padding()),
+        )
+}
+
+fn enum_parser<'a>() -> impl Parser<'a, &'a str, Token<'a>, ParserExtra<'a>> {
+    let enum_decl = doc_parser()
+        .then(attributes_parser().or_not().map(Option::unwrap_or_default))
         .then_ignore(text::keyword("enum"))
-        .padded()
-        .then(text::ident().padded())
-        .map(|(attributes, name)| Token::Enum(attributes, name, Vec::new()));
+        .padded_by(padding())
+        .then(text::ident().padded_by(padding()))
+        .then(
+            just(':')
+                .padded_by(padding())
+                .ignore_then(text::ident())
+                .padded_by(padding())
+                .or_not(),
+        )
+        .map(|(((doc, attributes), name), repr)| {
+            Token::Enum(doc, attributes, name, repr, Vec::new())
+        });
 
     enum_decl
-        .then(variants_parser())
+        .then(enum_variants_parser())
         .map(|(mut enum_decl, variants)| {
-            if let Token::Enum(_, _, enum_variants) = &mut enum_decl {
+            if let Token::Enum(_, _, _, _, enum_variants) = &mut enum_decl {
                 *enum_variants = variants;
             } else {
                 unreachable!(
@@ -163,22 +487,78 @@ fn enum_parser<'a>() -> impl Parser<'a, &'a str, Token<'a>, ParserExtra<'a>> {
 
             enum_decl
         })
-        .padded()
+        .padded_by(padding())
+}
+
+fn flags_values_parser<'a>()
+-> impl Parser<'a, &'a str, Vec<(String, &'a str, Option<u128>)>, ParserExtra<'a>> {
+    let hex_value = just("0x")
+        .ignore_then(text::int(16))
+        .try_map(|value: &str, span| {
+            u128::from_str_radix(value, 16).map_err(|e| Rich::custom(span, e))
+        });
+    let decimal_value =
+        text::int(10).try_map(|value: &str, span| value.parse().map_err(|e| Rich::custom(span, e)));
+    let value = hex_value.or(decimal_value).padded_by(padding());
+
+    doc_parser()
+        .then(text::ident().padded_by(padding()))
+        .then(just('=').padded_by(padding()).ignore_then(value).or_not())
+        .map(|((doc, name), value)| (doc, name, value))
+        .separated_by(just(','))
+        .allow_trailing()
+        .collect()
+        .delimited_by(
+            just('{').padded_by(padding()),
+            just('}').padded_by(padding()),
+        )
+}
+
+fn flags_parser<'a>() -> impl Parser<'a, &'a str, Token<'a>, ParserExtra<'a>> {
+    let flags_decl = doc_parser()
+        .then(attributes_parser().or_not().map(Option::unwrap_or_default))
+        .then_ignore(text::keyword("flags"))
+        .padded_by(padding())
+        .then(text::ident().padded_by(padding()))
+        .then(
+            just(':')
+                .padded_by(padding())
+                .ignore_then(text::ident())
+                .padded_by(padding())
+                .or_not(),
+        )
+        .map(|(((doc, attributes), name), repr)| {
+            Token::Flags(doc, attributes, name, repr, Vec::new())
+        });
+
+    flags_decl
+        .then(flags_values_parser())
+        .map(|(mut flags_decl, values)| {
+            if let Token::Flags(_, _, _, _, flags_values) = &mut flags_decl {
+                *flags_values = values;
+            } else {
+                unreachable!(
+                    "flags_decl is supposed to only yield Token::Flags, but got {flags_decl:?}"
+                );
+            }
+
+            flags_decl
+        })
+        .padded_by(padding())
 }
 
 fn union_parser<'a>() -> impl Parser<'a, &'a str, Token<'a>, ParserExtra<'a>> {
-    let union_decl = attributes_parser()
-        .or_not()
-        .map(Option::unwrap_or_default)
+    let union_decl = doc_parser()
+        .then(attributes_parser().or_not().map(Option::unwrap_or_default))
         .then_ignore(text::keyword("union"))
-        .padded()
-        .then(text::ident().padded())
-        .map(|(attributes, name)| Token::Union(attributes, name, Vec::new()));
+        .padded_by(padding())
+        .then(text::ident().padded_by(padding()))
+        .map(|((doc, attributes), name)| Token::Union(doc, attributes, name, Vec::new()));
 
     union_decl
-        .then(variants_parser())
+        .then(union_variants_parser())
         .map(|(mut union_decl, variants)| {
-            if let Token::Union(_, _, union_variants) = &mut union_decl {
+            if let Token::Union(_, _, _, union_variants) = &mut union_decl {
                 *union_variants = variants;
             } else {
                 unreachable!(
@@ -188,7 +568,49 @@ fn union_parser<'a>() -> impl Parser<'a, &'a str, Token<'a>, ParserExtra<'a>> {
 
             union_decl
         })
-        .padded()
+        .padded_by(padding())
+}
+
+fn const_parser<'a>() -> impl Parser<'a, &'a str, Token<'a>, ParserExtra<'a>> {
+    doc_parser()
+        .then(attributes_parser().or_not().map(Option::unwrap_or_default))
+        .then_ignore(text::keyword("const"))
+        .padded_by(padding())
+        .then(text::ident().padded_by(padding()))
+        .then_ignore(just(':').padded_by(padding()))
+        .then(text::ident().padded_by(padding()))
+        .then_ignore(just('=').padded_by(padding()))
+        .then(expr_parser())
+        .map(|((((doc, attributes), name), type_name), value)| {
+            Token::Const(doc, attributes, name, type_name, value)
+        })
+        .padded_by(padding())
+}
+
+fn alias_parser<'a>() -> impl Parser<'a, &'a str, Token<'a>, ParserExtra<'a>> {
+    let keyword = text::keyword("alias")
+        .to(false)
+        .or(text::keyword("newtype").to(true));
+
+    doc_parser()
+        .then(attributes_parser().or_not().map(Option::unwrap_or_default))
+        .then(keyword)
+        .padded_by(padding())
+        .then(text::ident().padded_by(padding()))
+        .then_ignore(just('=').padded_by(padding()))
+        .then(type_parser())
+        .map(|((((doc, attributes), is_distinct), name), r#type)| {
+            Token::Alias(doc, attributes, name, r#type, is_distinct)
+        })
+        .padded_by(padding())
+}
+
+fn package_parser<'a>() -> impl Parser<'a, &'a str, Token<'a>, ParserExtra<'a>> {
+    text::keyword("package")
+        .padded_by(padding())
+        .ignore_then(qualified_ident_parser())
+        .map(Token::Package)
+        .padded_by(padding())
 }
 
 fn include_parser<'a>() -> impl Parser<'a, &'a str, Token<'a>, ParserExtra<'a>> {
@@ -211,7 +633,7 @@ fn include_parser<'a>() -> impl Parser<'a, &'a str, Token<'a>, ParserExtra<'a>>
     });
 
     let include_decl = text::keyword("include")
-        .padded()
+        .padded_by(padding())
         .ignore_then(path_parser)
         .try_map_with(|path, extra| {
             let span = extra.span();
@@ -236,26 +658,50 @@ fn include_parser<'a>() -> impl Parser<'a, &'a str, Token<'a>, ParserExtra<'a>>
 }
 
 fn function_parser<'a>() -> impl Parser<'a, &'a str, Token<'a>, ParserExtra<'a>> {
-    let args_parser = fields_parser('(', ')')
-        .map(|field| field.into_iter().map(|(_, name, ty)| (name, ty)).collect());
-    text::keyword("fn")
-        .padded()
-        .ignore_then(text::ident())
+    let args_parser = fields_parser('(', ')').try_map(|fields, span| {
+        fields
+            .into_iter()
+            .map(
+                |(_, _, name, ty, bit_width, default)| match (bit_width, default) {
+                    (Some(_), _) => {
+                        Err(Rich::custom(span, "function arguments can't be bitfields"))
+                    }
+                    (_, Some(_)) => Err(Rich::custom(
+                        span,
+                        "function arguments can't have default values",
+                    )),
+                    (None, None) => Ok((name, ty)),
+                },
+            )
+            .collect()
+    });
+    doc_parser()
+        .then_ignore(text::keyword("fn").padded_by(padding()))
+        .then(text::ident())
         .then(args_parser)
-        .then(just("->").padded().ignore_then(type_parser()).or_not())
-        .map(|((name, args), return_type)| Token::Function(name, args, return_type))
+        .then(
+            just("->")
+                .padded_by(padding())
+                .ignore_then(type_parser())
+                .or_not(),
+        )
+        .map(|(((doc, name), args), return_type)| Token::Function(doc, name, args, return_type))
 }
 
 fn parser<'a, C: Container<Token<'a>>>() -> impl Parser<'a, &'a str, C, ParserExtra<'a>> {
     let parser = choice((
+        package_parser(),
         include_parser(),
         struct_parser(),
         enum_parser(),
         union_parser(),
+        flags_parser(),
+        const_parser(),
+        alias_parser(),
         function_parser(),
     ));
 
-    parser.repeated().collect()
+    parser.repeated().collect().padded_by(padding())
 }
 
 struct Include<'a> {
@@ -0,0 +1,295 @@
+use backend::helpers::generate_schema;
+use backend::optimization::SchemaOptimizations;
+use binterop::schema::Schema;
+use binterop::target::TargetLayout;
+use binterop::types::Type;
+use binterop::value::Value;
+
+fn generate(text: &str) -> Result<Schema, String> {
+    generate_schema(
+        None,
+        text,
+        SchemaOptimizations::new(false, true),
+        &TargetLayout::default(),
+    )
+}
+
+fn field_type(schema: &Schema, type_name: &str, field_name: &str) -> (Type, usize) {
+    let data_type = schema
+        .types
+        .iter()
+        .find(|data_type| data_type.name == type_name)
+        .unwrap();
+    let field = data_type
+        .fields
+        .iter()
+        .find(|field| field.name == field_name)
+        .unwrap();
+
+    (field.r#type, field.type_index)
+}
+
+fn array_len(schema: &Schema, type_name: &str, field_name: &str) -> usize {
+    let (field_type, field_type_index) = field_type(schema, type_name, field_name);
+    assert_eq!(field_type, Type::Array);
+
+    schema.arrays[field_type_index].len
+}
+
+#[test]
+pub fn constants_resolve_per_package() {
+    let schema = generate(
+        "
+package net
+const MAX: u32 = 16
+
+struct Header {
+    data: [u8:MAX],
+}
+
+package game
+const MAX: u32 = 4
+
+struct Lobby {
+    players: [u8:MAX],
+    buffer: [u8:net.MAX + MAX],
+    limit: u32 = net.MAX,
+}
+",
+    )
+    .unwrap();
+
+    let constants = schema
+        .constants
+        .iter()
+        .map(|constant| {
+            (
+                constant.namespace.as_str(),
+                constant.name.as_str(),
+                constant.value,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(constants, [("net", "MAX", 16), ("game", "MAX", 4)]);
+
+    assert_eq!(array_len(&schema, "Header", "data"), 16);
+    assert_eq!(array_len(&schema, "Lobby", "players"), 4);
+    assert_eq!(array_len(&schema, "Lobby", "buffer"), 20);
+
+    let lobby = schema
+        .types
+        .iter()
+        .find(|data_type| data_type.name == "Lobby")
+        .unwrap();
+    assert_eq!(lobby.fields[2].default, Some(Value::U32(16)));
+
+    let err = generate("package a\nconst MAX: u32 = 1\nconst MAX: u32 = 2").unwrap_err();
+    assert!(
+        err.contains("Constant a.MAX is defined more than once"),
+        "{err}"
+    );
+}
+
+const PAIR: &str = "
+struct Pair<T> {
+    a: T,
+    b: T,
+}
+";
+
+#[test]
+pub fn generic_structs_are_instantiated() {
+    let schema = generate(&format!(
+        "{PAIR}
+struct Scene {{
+    size: Pair<f32>,
+    origin: Pair<f32>,
+    bounds: Pair<i32>,
+}}"
+    ))
+    .unwrap();
+
+    let (size_type, size_index) = field_type(&schema, "Scene", "size");
+    assert_eq!(size_type, Type::Data);
+    assert_eq!(schema.types[size_index].name, "PairF32");
+    assert_eq!(schema.type_size(Type::Data, size_index), Some(8));
+    assert_eq!(
+        field_type(&schema, "Scene", "origin"),
+        (Type::Data, size_index)
+    );
+    assert_eq!(
+        schema.types[field_type(&schema, "Scene", "bounds").1].name,
+        "PairI32"
+    );
+    assert!(
+        !schema
+            .types
+            .iter()
+            .any(|data_type| data_type.name == "Pair")
+    );
+}
+
+#[test]
+pub fn nested_generic_structs_are_instantiated() {
+    let schema = generate(&format!(
+        "{PAIR}
+struct Node<K, V> {{
+    key: K,
+    value: V,
+    next: Node<K, V>*,
+}}
+
+struct Scene {{
+    pairs: Pair<Pair<u8>>,
+    nodes: <Node<u32, Pair<u16>>>,
+}}"
+    ))
+    .unwrap();
+
+    let (_, pairs_index) = field_type(&schema, "Scene", "pairs");
+    assert_eq!(schema.types[pairs_index].name, "PairPairU8");
+    let (inner_type, inner_index) = field_type(&schema, "PairPairU8", "a");
+    assert_eq!(inner_type, Type::Data);
+    assert_eq!(schema.types[inner_index].name, "PairU8");
+
+    let node_index = schema
+        .types
+        .iter()
+        .position(|data_type| data_type.name == "NodeU32PairU16")
+        .unwrap();
+    let (next_type, next_index) = field_type(&schema, "NodeU32PairU16", "next");
+    assert_eq!(next_type, Type::Pointer);
+    assert_eq!(schema.pointers[next_index].inner_type_index, node_index);
+}
+
+#[test]
+pub fn generic_argument_count_is_checked() {
+    let err = generate(&format!(
+        "{PAIR}\nstruct Scene {{\n    size: Pair<f32, f64>,\n}}"
+    ))
+    .unwrap_err();
+
+    assert!(
+        err.contains("Generic struct Pair takes 1 type arguments, but 2 were given"),
+        "{err}"
+    );
+}
+
+#[test]
+pub fn instances_do_not_reuse_unrelated_types() {
+    let err = generate(&format!(
+        "{PAIR}
+struct PairF32 {{
+    x: u8,
+}}
+
+struct Scene {{
+    size: Pair<f32>,
+}}"
+    ))
+    .unwrap_err();
+
+    assert!(
+        err.contains("Pair<f32> would be named PairF32, but another type already has that name"),
+        "{err}"
+    );
+}
+
+#[test]
+pub fn declarations_can_refer_forward() {
+    let schema = generate(
+        "
+struct Packet {
+    header: Header,
+    data: [u8:SIZE],
+}
+
+const SIZE: u32 = COUNT * 2
+const COUNT: u32 = 4
+
+struct Header {
+    length: u32,
+}
+",
+    )
+    .unwrap();
+
+    let (header_type, header_index) = field_type(&schema, "Packet", "header");
+    assert_eq!(header_type, Type::Data);
+    assert_eq!(schema.types[header_index].name, "Header");
+    assert_eq!(array_len(&schema, "Packet", "data"), 8);
+
+    let constants = schema
+        .constants
+        .iter()
+        .map(|constant| (constant.name.as_str(), constant.value))
+        .collect::<Vec<_>>();
+    assert_eq!(constants, [("COUNT", 4), ("SIZE", 8)]);
+}
+
+#[test]
+pub fn recursive_constants_are_rejected() {
+    let err = generate("const A: u32 = B + 1\nconst B: u32 = C\nconst C: u32 = A * 2").unwrap_err();
+
+    assert!(
+        err.contains("Constant A depends on itself through A -> B -> C -> A"),
+        "{err}"
+    );
+}
+
+#[test]
+pub fn bare_type_names_are_not_unit_variants() {
+    let err = generate(
+        "struct Team {
+    id: u8,
+}
+
+union AllTypes {
+    Team,
+    Empty,
+}",
+    )
+    .unwrap_err();
+
+    assert!(
+        err.contains("Variant Team names a type but has no payload"),
+        "{err}"
+    );
+
+    let schema = generate(
+        "struct Team {
+    id: u8,
+}
+
+union AllTypes {
+    team: Team,
+    Empty,
+}",
+    )
+    .unwrap();
+
+    let variants = &schema.unions[0].variants;
+    assert!(variants[0].payload.is_some());
+    assert!(variants[1].payload.is_none());
+}
+
+#[test]
+pub fn polymorphic_recursion_is_limited() {
+    let err = generate(
+        "struct P<T> {
+    x: T,
+    n: P<P<T>>*,
+}
+
+struct S {
+    p: P<u8>,
+}",
+    )
+    .unwrap_err();
+
+    assert!(err.starts_with("S.p: PU8.n: "), "{err}");
+    assert!(
+        err.contains("nests generic structs 33 levels deep"),
+        "{err}"
+    );
+}
@@ -0,0 +1,91 @@
+use backend::helpers::{generate_lang_files, generate_schema};
+use backend::optimization::SchemaOptimizations;
+use binterop::target::TargetLayout;
+use std::path::PathBuf;
+use std::{env, fs};
+
+fn generate(gen_name: &str, name: &str, text: &str) -> PathBuf {
+    let schema = generate_schema(
+        None,
+        text,
+        SchemaOptimizations::new(false, true),
+        &TargetLayout::default(),
+    )
+    .unwrap();
+
+    let output_dir = env::temp_dir().join(format!("binterop-{name}"));
+    fs::create_dir_all(&output_dir).unwrap();
+    generate_lang_files(
+        &output_dir.join(format!("{name}.bintdef")),
+        gen_name,
+        &schema,
+    )
+    .unwrap();
+
+    output_dir
+}
+
+fn generate_rust(name: &str, text: &str) -> String {
+    let output_dir = generate("rust", name, text);
+    fs::read_to_string(output_dir.join(format!("{name}.rs"))).unwrap()
+}
+
+#[test]
+fn unions_referenced_by_fields_are_generated_once() {
+    let output = generate_rust(
+        "union_field",
+        "
+        struct S { u: U }
+        union U { a: u8, b: f32 }
+        ",
+    );
+
+    assert_eq!(output.matches("pub union UUnion").count(), 1);
+    assert_eq!(output.matches("pub enum UVariant").count(), 1);
+}
+
+#[test]
+fn types_holding_slices_take_a_lifetime() {
+    let output = generate_rust(
+        "slice_lifetime",
+        "
+        struct Bytes { data: &<u8> }
+        struct Packet { body: Bytes, next: Packet* }
+        union Payload { packet: Packet, empty }
+        alias Chunks = <Bytes>
+        struct Plain { x: f32 }
+        ",
+    );
+
+    assert!(output.contains("pub struct Bytes<'a> {"), "{output}");
+    assert!(output.contains("binterop::std::Slice<'a, u8>"), "{output}");
+    assert!(output.contains("pub struct Packet<'a> {"), "{output}");
+    assert!(output.contains("pub body: Bytes<'a>"), "{output}");
+    assert!(output.contains("*mut Packet<'a>"), "{output}");
+    assert!(output.contains("pub union PayloadUnion<'a> {"), "{output}");
+    assert!(output.contains("pub struct Payload<'a> {"), "{output}");
+    assert!(output.contains("pub type Chunks<'a> ="), "{output}");
+    assert!(output.contains("pub struct Plain {"), "{output}");
+    assert!(!output.contains("'static"), "{output}");
+}
+
+#[test]
+fn every_go_package_checks_the_pointer_width() {
+    let output_dir = generate(
+        "go",
+        "go_packages",
+        "
+        struct Root { x: u8 }
+        package game.net
+        struct Header { kind: u32 }
+        ",
+    );
+
+    for path in ["go_packages.go", "game/net/net.go"] {
+        let output = fs::read_to_string(output_dir.join("go_packages").join(path)).unwrap();
+        assert!(
+            output.contains("[unsafe.Sizeof(uintptr(0)) - 8]struct{}{}"),
+            "{path}: {output}"
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use backend::helpers::generate_schema;
+use backend::optimization::SchemaOptimizations;
+use binterop::target::TargetLayout;
+use binterop::types::primitives::PRIMITIVES;
+
+const MIXED: &str = "
+struct Mixed {
+    a: u8,
+    b: u64,
+    c: f64,
+    d: i128,
+    e: u16,
+    f: u8*,
+    g: usize,
+}
+";
+
+fn mixed_layout(target: TargetLayout) -> (Vec<usize>, usize, usize) {
+    let schema =
+        generate_schema(None, MIXED, SchemaOptimizations::new(false, true), &target).unwrap();
+    let mixed = schema
+        .types
+        .iter()
+        .find(|data_type| data_type.name == "Mixed")
+        .unwrap();
+
+    (
+        mixed.fields.iter().map(|field| field.offset).collect(),
+        mixed.try_size(&schema).unwrap(),
+        mixed.try_align(&schema).unwrap(),
+    )
+}
+
+#[test]
+pub fn wasm32_matches_c_layout() {
+    assert_eq!(
+        mixed_layout(TargetLayout::wasm32()),
+        (vec![0, 8, 16, 32, 48, 52, 56], 64, 16)
+    );
+}
+
+#[test]
+pub fn powerpc64_matches_c_layout() {
+    assert_eq!(
+        mixed_layout(TargetLayout::powerpc64_linux()),
+        (vec![0, 8, 16, 32, 48, 56, 64], 80, 16)
+    );
+}
+
+#[test]
+pub fn primitives_do_not_depend_on_host() {
+    for target in TargetLayout::builtin() {
+        for (name, size, align) in [("u64", 8, 8), ("f64", 8, 8), ("i128", 16, 16)] {
+            let primitive = target
+                .primitive(PRIMITIVES.index_of(name).unwrap())
+                .unwrap();
+
+            assert_eq!((primitive.size, primitive.align), (size, align), "{name}");
+        }
+    }
+}
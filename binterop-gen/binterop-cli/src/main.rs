@@ -1,6 +1,25 @@
-use backend::helpers::process_text;
-use std::path::PathBuf;
-use std::{env, fs};
+use backend::helpers::{check_compatibility, process_text};
+use std::path::{Path, PathBuf};
+use std::{env, fs, process};
+
+fn compat(args: &[String]) {
+    let [old_path, new_path] = args else {
+        eprintln!("Usage: binterop-cli compat <old schema> <new schema>");
+        process::exit(2);
+    };
+
+    match check_compatibility(Path::new(old_path), Path::new(new_path)) {
+        Ok(false) => println!("No breaking changes."),
+        Ok(true) => {
+            println!("Schemas are not ABI compatible!");
+            process::exit(1);
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(2);
+        }
+    }
+}
 
 fn main() {
     let args = env::args().skip(1).collect::<Vec<_>>();
@@ -10,6 +29,11 @@ fn main() {
         return;
     }
 
+    if args[0] == "compat" {
+        compat(&args[1..]);
+        return;
+    }
+
     for path in args.iter().map(PathBuf::from) {
         if path
             .as_os_str()
@@ -54,7 +54,7 @@ fn struct_derive(data_struct: DataStruct) -> TokenStream {
 
             let mut layout = Layout::from_size_align(0, 1).unwrap();
             for field in &mut data_type.fields {
-                let field_layout = field.layout(schema);
+                let field_layout = field.try_layout(schema).unwrap();
                 let (new_layout, offset) = layout.extend(field_layout).unwrap();
                 layout = new_layout;
                 field.offset = offset;
@@ -84,7 +84,7 @@ fn enum_derive(data_enum: DataEnum) -> TokenStream {
             use std::alloc::Layout;
             use std::any::type_name;
 
-            let enum_type = EnumType::new(type_name::<Self>(), &[#variant_names]);
+            let enum_type = EnumType::new(type_name::<Self>(), &[#variant_names], &[]);
 
             schema.enums.push(enum_type.clone());
 
@@ -0,0 +1,51 @@
+use crate::error::Error;
+
+pub fn attribute<'a>(attributes: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|(attribute_name, _)| attribute_name == name)
+        .map(|(_, value)| value.as_str())
+}
+
+pub fn bool_attribute(attributes: &[(String, String)], name: &str) -> Result<bool, Error> {
+    match attribute(attributes, name) {
+        None | Some("false") => Ok(false),
+        Some("true") => Ok(true),
+        Some(value) => Err(invalid_attribute(name, value, "expected true or false")),
+    }
+}
+
+pub fn usize_attribute(
+    attributes: &[(String, String)],
+    name: &str,
+) -> Result<Option<usize>, Error> {
+    attribute(attributes, name)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| invalid_attribute(name, value, "expected an unsigned integer"))
+        })
+        .transpose()
+}
+
+pub fn align_attribute(
+    attributes: &[(String, String)],
+    name: &str,
+) -> Result<Option<usize>, Error> {
+    match usize_attribute(attributes, name)? {
+        Some(align) if !align.is_power_of_two() => Err(invalid_attribute(
+            name,
+            &align.to_string(),
+            "expected a power of two",
+        )),
+        align => Ok(align),
+    }
+}
+
+pub fn invalid_attribute(name: &str, value: &str, reason: &str) -> Error {
+    Error::InvalidAttribute {
+        name: name.to_string(),
+        value: value.to_string(),
+        reason: reason.to_string(),
+    }
+}
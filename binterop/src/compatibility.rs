@@ -0,0 +1,629 @@
+use crate::error::Error;
+use crate::field::Bitfield;
+use crate::schema::{qualified_name, Schema};
+use crate::types::Type;
+use std::fmt::{self, Display};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    TypeAdded,
+    TypeRemoved,
+    KindChanged {
+        old: Type,
+        new: Type,
+    },
+    SizeChanged {
+        old: usize,
+        new: usize,
+    },
+    AlignChanged {
+        old: usize,
+        new: usize,
+    },
+    FieldAdded {
+        field_name: String,
+    },
+    FieldRemoved {
+        field_name: String,
+    },
+    FieldOffsetChanged {
+        field_name: String,
+        old: usize,
+        new: usize,
+    },
+    FieldTypeChanged {
+        field_name: String,
+        old: String,
+        new: String,
+    },
+    FieldBitfieldChanged {
+        field_name: String,
+        old: Option<Bitfield>,
+        new: Option<Bitfield>,
+    },
+    EnumVariantAdded {
+        variant: String,
+    },
+    EnumVariantRemoved {
+        variant: String,
+    },
+    EnumVariantMoved {
+        variant: String,
+        old: i128,
+        new: i128,
+    },
+    UnionMemberAdded {
+        member: String,
+    },
+    UnionMemberRemoved {
+        member: String,
+    },
+    UnionMemberMoved {
+        member: String,
+        old: usize,
+        new: usize,
+    },
+    UnionMemberPayloadChanged {
+        member: String,
+        old: String,
+        new: String,
+    },
+    SignatureChanged {
+        old: String,
+        new: String,
+    },
+    AliasTargetChanged {
+        old: String,
+        new: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    pub type_name: String,
+    pub kind: ChangeKind,
+}
+impl Change {
+    fn new(type_name: &str, kind: ChangeKind) -> Self {
+        Self {
+            type_name: type_name.to_string(),
+            kind,
+        }
+    }
+
+    pub fn is_breaking(&self) -> bool {
+        !matches!(
+            self.kind,
+            ChangeKind::TypeAdded
+                | ChangeKind::FieldAdded { .. }
+                | ChangeKind::EnumVariantAdded { .. }
+                | ChangeKind::UnionMemberAdded { .. }
+        )
+    }
+}
+impl Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = if self.is_breaking() {
+            "breaking"
+        } else {
+            "compatible"
+        };
+        write!(f, "[{label}] {}: ", self.type_name)?;
+
+        match &self.kind {
+            ChangeKind::TypeAdded => write!(f, "type was added"),
+            ChangeKind::TypeRemoved => write!(f, "type was removed"),
+            ChangeKind::KindChanged { old, new } => {
+                write!(f, "kind changed from {old:?} to {new:?}")
+            }
+            ChangeKind::SizeChanged { old, new } => write!(f, "size changed from {old} to {new}"),
+            ChangeKind::AlignChanged { old, new } => {
+                write!(f, "alignment changed from {old} to {new}")
+            }
+            ChangeKind::FieldAdded { field_name } => write!(f, "field {field_name} was added"),
+            ChangeKind::FieldRemoved { field_name } => {
+                write!(f, "field {field_name} was removed")
+            }
+            ChangeKind::FieldOffsetChanged {
+                field_name,
+                old,
+                new,
+            } => write!(f, "field {field_name} moved from offset {old} to {new}"),
+            ChangeKind::FieldTypeChanged {
+                field_name,
+                old,
+                new,
+            } => write!(f, "field {field_name} changed type from {old} to {new}"),
+            ChangeKind::FieldBitfieldChanged {
+                field_name,
+                old,
+                new,
+            } => {
+                let bits_text = |bitfield: &Option<Bitfield>| match bitfield {
+                    Some(bitfield) => format!(
+                        "bits {}..{}",
+                        bitfield.bit_offset,
+                        bitfield.bit_offset + bitfield.bit_width
+                    ),
+                    None => "the whole field".to_string(),
+                };
+
+                write!(
+                    f,
+                    "field {field_name} changed from {} to {}",
+                    bits_text(old),
+                    bits_text(new)
+                )
+            }
+            ChangeKind::EnumVariantAdded { variant } => write!(f, "variant {variant} was added"),
+            ChangeKind::EnumVariantRemoved { variant } => {
+                write!(f, "variant {variant} was removed")
+            }
+            ChangeKind::EnumVariantMoved { variant, old, new } => {
+                write!(f, "variant {variant} changed value from {old} to {new}")
+            }
+            ChangeKind::UnionMemberAdded { member } => write!(f, "member {member} was added"),
+            ChangeKind::UnionMemberRemoved { member } => write!(f, "member {member} was removed"),
+            ChangeKind::UnionMemberMoved { member, old, new } => {
+                write!(f, "member {member} changed tag from {old} to {new}")
+            }
+            ChangeKind::UnionMemberPayloadChanged { member, old, new } => {
+                write!(f, "member {member} changed payload from {old} to {new}")
+            }
+            ChangeKind::SignatureChanged { old, new } => {
+                write!(f, "signature changed from {old} to {new}")
+            }
+            ChangeKind::AliasTargetChanged { old, new } => {
+                write!(f, "alias target changed from {old} to {new}")
+            }
+        }
+    }
+}
+
+struct NamedType<'a> {
+    name: String,
+    type_id: Option<&'a str>,
+    r#type: Type,
+    index: usize,
+}
+
+fn type_id(attributes: &[(String, String)]) -> Option<&str> {
+    attributes
+        .iter()
+        .find(|(name, _)| name == "type_id")
+        .map(|(_, value)| value.as_str())
+}
+
+fn named_types(schema: &Schema) -> Vec<NamedType<'_>> {
+    let data_types = schema
+        .types
+        .iter()
+        .enumerate()
+        .map(|(index, data_type)| NamedType {
+            name: qualified_name(&data_type.namespace, &data_type.name),
+            type_id: type_id(&data_type.attributes),
+            r#type: Type::Data,
+            index,
+        });
+    let enum_types = schema
+        .enums
+        .iter()
+        .enumerate()
+        .map(|(index, enum_type)| NamedType {
+            name: qualified_name(&enum_type.namespace, &enum_type.name),
+            type_id: type_id(&enum_type.attributes),
+            r#type: Type::Enum,
+            index,
+        });
+    let union_types = schema
+        .unions
+        .iter()
+        .enumerate()
+        .map(|(index, union_type)| NamedType {
+            name: qualified_name(&union_type.namespace, &union_type.name),
+            type_id: type_id(&union_type.attributes),
+            r#type: Type::Union,
+            index,
+        });
+    let flags_types = schema
+        .flags
+        .iter()
+        .enumerate()
+        .map(|(index, flags_type)| NamedType {
+            name: qualified_name(&flags_type.namespace, &flags_type.name),
+            type_id: type_id(&flags_type.attributes),
+            r#type: Type::Flags,
+            index,
+        });
+    let alias_types = schema
+        .aliases
+        .iter()
+        .enumerate()
+        .map(|(index, alias_type)| NamedType {
+            name: qualified_name(&alias_type.namespace, &alias_type.name),
+            type_id: type_id(&alias_type.attributes),
+            r#type: Type::Alias,
+            index,
+        });
+    let function_types = schema
+        .functions
+        .iter()
+        .enumerate()
+        .map(|(index, function_type)| NamedType {
+            name: qualified_name(&function_type.namespace, &function_type.name),
+            type_id: None,
+            r#type: Type::Function,
+            index,
+        });
+
+    data_types
+        .chain(enum_types)
+        .chain(union_types)
+        .chain(flags_types)
+        .chain(alias_types)
+        .chain(function_types)
+        .collect()
+}
+
+fn is_same_type(old: &NamedType, new: &NamedType) -> bool {
+    match (old.type_id, new.type_id) {
+        (Some(old_type_id), Some(new_type_id)) => old_type_id == new_type_id,
+        _ => old.name == new.name,
+    }
+}
+
+fn compare_layouts(
+    old_schema: &Schema,
+    new_schema: &Schema,
+    old: &NamedType,
+    new: &NamedType,
+    changes: &mut Vec<Change>,
+) -> Result<(), Error> {
+    let old_size = old_schema.try_type_size(old.r#type, old.index)?;
+    let new_size = new_schema.try_type_size(new.r#type, new.index)?;
+    if old_size != new_size {
+        changes.push(Change::new(
+            &new.name,
+            ChangeKind::SizeChanged {
+                old: old_size,
+                new: new_size,
+            },
+        ));
+    }
+
+    let old_align = old_schema.try_type_align(old.r#type, old.index)?;
+    let new_align = new_schema.try_type_align(new.r#type, new.index)?;
+    if old_align != new_align {
+        changes.push(Change::new(
+            &new.name,
+            ChangeKind::AlignChanged {
+                old: old_align,
+                new: new_align,
+            },
+        ));
+    }
+
+    Ok(())
+}
+
+fn compare_data_types(
+    old_schema: &Schema,
+    new_schema: &Schema,
+    old: &NamedType,
+    new: &NamedType,
+    changes: &mut Vec<Change>,
+) -> Result<(), Error> {
+    let old_fields = &old_schema.types[old.index].fields;
+    let new_fields = &new_schema.types[new.index].fields;
+
+    for old_field in old_fields {
+        let Some(new_field) = new_fields
+            .iter()
+            .find(|new_field| new_field.name == old_field.name)
+        else {
+            changes.push(Change::new(
+                &new.name,
+                ChangeKind::FieldRemoved {
+                    field_name: old_field.name.clone(),
+                },
+            ));
+            continue;
+        };
+
+        if old_field.offset != new_field.offset {
+            changes.push(Change::new(
+                &new.name,
+                ChangeKind::FieldOffsetChanged {
+                    field_name: old_field.name.clone(),
+                    old: old_field.offset,
+                    new: new_field.offset,
+                },
+            ));
+        }
+
+        if old_field.bitfield != new_field.bitfield {
+            changes.push(Change::new(
+                &new.name,
+                ChangeKind::FieldBitfieldChanged {
+                    field_name: old_field.name.clone(),
+                    old: old_field.bitfield,
+                    new: new_field.bitfield,
+                },
+            ));
+        }
+
+        let old_type_name = old_schema.try_type_name(old_field.r#type, old_field.type_index)?;
+        let new_type_name = new_schema.try_type_name(new_field.r#type, new_field.type_index)?;
+        if old_type_name != new_type_name {
+            changes.push(Change::new(
+                &new.name,
+                ChangeKind::FieldTypeChanged {
+                    field_name: old_field.name.clone(),
+                    old: old_type_name.to_string(),
+                    new: new_type_name.to_string(),
+                },
+            ));
+        }
+    }
+
+    for new_field in new_fields {
+        if !old_fields
+            .iter()
+            .any(|old_field| old_field.name == new_field.name)
+        {
+            changes.push(Change::new(
+                &new.name,
+                ChangeKind::FieldAdded {
+                    field_name: new_field.name.clone(),
+                },
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn compare_enum_types(
+    old_schema: &Schema,
+    new_schema: &Schema,
+    old: &NamedType,
+    new: &NamedType,
+    changes: &mut Vec<Change>,
+) {
+    let old_enum_type = &old_schema.enums[old.index];
+    let new_enum_type = &new_schema.enums[new.index];
+
+    for old_variant in &old_enum_type.variants {
+        match new_enum_type.variant_by_name(&old_variant.name) {
+            Some(new_variant) if new_variant.value != old_variant.value => {
+                changes.push(Change::new(
+                    &new.name,
+                    ChangeKind::EnumVariantMoved {
+                        variant: old_variant.name.clone(),
+                        old: old_variant.value,
+                        new: new_variant.value,
+                    },
+                ))
+            }
+            Some(_) => {}
+            None => changes.push(Change::new(
+                &new.name,
+                ChangeKind::EnumVariantRemoved {
+                    variant: old_variant.name.clone(),
+                },
+            )),
+        }
+    }
+
+    for new_variant in &new_enum_type.variants {
+        if old_enum_type.variant_by_name(&new_variant.name).is_none() {
+            changes.push(Change::new(
+                &new.name,
+                ChangeKind::EnumVariantAdded {
+                    variant: new_variant.name.clone(),
+                },
+            ));
+        }
+    }
+}
+
+// Flags are reported like enum variants, with their masks as values
+fn compare_flags_types(
+    old_schema: &Schema,
+    new_schema: &Schema,
+    old: &NamedType,
+    new: &NamedType,
+    changes: &mut Vec<Change>,
+) {
+    let old_flags_type = &old_schema.flags[old.index];
+    let new_flags_type = &new_schema.flags[new.index];
+
+    for old_bit in &old_flags_type.bits {
+        match new_flags_type.bit_by_name(&old_bit.name) {
+            Some(new_bit) if new_bit.bit != old_bit.bit => changes.push(Change::new(
+                &new.name,
+                ChangeKind::EnumVariantMoved {
+                    variant: old_bit.name.clone(),
+                    old: old_bit.mask() as i128,
+                    new: new_bit.mask() as i128,
+                },
+            )),
+            Some(_) => {}
+            None => changes.push(Change::new(
+                &new.name,
+                ChangeKind::EnumVariantRemoved {
+                    variant: old_bit.name.clone(),
+                },
+            )),
+        }
+    }
+
+    for new_bit in &new_flags_type.bits {
+        if old_flags_type.bit_by_name(&new_bit.name).is_none() {
+            changes.push(Change::new(
+                &new.name,
+                ChangeKind::EnumVariantAdded {
+                    variant: new_bit.name.clone(),
+                },
+            ));
+        }
+    }
+}
+
+fn compare_union_types(
+    old_schema: &Schema,
+    new_schema: &Schema,
+    old: &NamedType,
+    new: &NamedType,
+    changes: &mut Vec<Change>,
+) -> Result<(), Error> {
+    let payload_name = |schema: &Schema, payload: Option<(usize, Type)>| match payload {
+        Some((index, r#type)) => schema
+            .try_type_name(r#type, index)
+            .map(|name| name.to_string()),
+        None => Ok("nothing".to_string()),
+    };
+    let old_members = &old_schema.unions[old.index].variants;
+    let new_members = &new_schema.unions[new.index].variants;
+
+    for (old_position, member) in old_members.iter().enumerate() {
+        let Some(new_position) = new_members
+            .iter()
+            .position(|new_member| new_member.name == member.name)
+        else {
+            changes.push(Change::new(
+                &new.name,
+                ChangeKind::UnionMemberRemoved {
+                    member: member.name.clone(),
+                },
+            ));
+            continue;
+        };
+
+        if new_position != old_position {
+            changes.push(Change::new(
+                &new.name,
+                ChangeKind::UnionMemberMoved {
+                    member: member.name.clone(),
+                    old: old_position,
+                    new: new_position,
+                },
+            ));
+        }
+
+        let old_payload = payload_name(old_schema, member.payload)?;
+        let new_payload = payload_name(new_schema, new_members[new_position].payload)?;
+        if old_payload != new_payload {
+            changes.push(Change::new(
+                &new.name,
+                ChangeKind::UnionMemberPayloadChanged {
+                    member: member.name.clone(),
+                    old: old_payload,
+                    new: new_payload,
+                },
+            ));
+        }
+    }
+
+    for member in new_members {
+        if !old_members
+            .iter()
+            .any(|old_member| old_member.name == member.name)
+        {
+            changes.push(Change::new(
+                &new.name,
+                ChangeKind::UnionMemberAdded {
+                    member: member.name.clone(),
+                },
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn compare(old_schema: &Schema, new_schema: &Schema) -> Result<Vec<Change>, Error> {
+    let old_types = named_types(old_schema);
+    let new_types = named_types(new_schema);
+    let mut changes = Vec::new();
+
+    for old in &old_types {
+        let Some(new) = new_types.iter().find(|new| is_same_type(old, new)) else {
+            changes.push(Change::new(&old.name, ChangeKind::TypeRemoved));
+            continue;
+        };
+
+        if old.r#type != new.r#type {
+            changes.push(Change::new(
+                &new.name,
+                ChangeKind::KindChanged {
+                    old: old.r#type,
+                    new: new.r#type,
+                },
+            ));
+            continue;
+        }
+
+        match new.r#type {
+            Type::Data => {
+                compare_layouts(old_schema, new_schema, old, new, &mut changes)?;
+                compare_data_types(old_schema, new_schema, old, new, &mut changes)?;
+            }
+            Type::Enum => {
+                compare_layouts(old_schema, new_schema, old, new, &mut changes)?;
+                compare_enum_types(old_schema, new_schema, old, new, &mut changes);
+            }
+            Type::Union => {
+                compare_layouts(old_schema, new_schema, old, new, &mut changes)?;
+                compare_union_types(old_schema, new_schema, old, new, &mut changes)?;
+            }
+            Type::Flags => {
+                compare_layouts(old_schema, new_schema, old, new, &mut changes)?;
+                compare_flags_types(old_schema, new_schema, old, new, &mut changes);
+            }
+            Type::Alias => {
+                compare_layouts(old_schema, new_schema, old, new, &mut changes)?;
+
+                let old_alias_type = &old_schema.aliases[old.index];
+                let new_alias_type = &new_schema.aliases[new.index];
+                let old_target = old_schema
+                    .try_type_name(old_alias_type.inner_type, old_alias_type.inner_type_index)?;
+                let new_target = new_schema
+                    .try_type_name(new_alias_type.inner_type, new_alias_type.inner_type_index)?;
+
+                if old_target != new_target {
+                    changes.push(Change::new(
+                        &new.name,
+                        ChangeKind::AliasTargetChanged {
+                            old: old_target.to_string(),
+                            new: new_target.to_string(),
+                        },
+                    ));
+                }
+            }
+            Type::Function => {
+                let old_signature = old_schema.try_type_name(old.r#type, old.index)?;
+                let new_signature = new_schema.try_type_name(new.r#type, new.index)?;
+
+                if old_signature != new_signature {
+                    changes.push(Change::new(
+                        &new.name,
+                        ChangeKind::SignatureChanged {
+                            old: old_signature.to_string(),
+                            new: new_signature.to_string(),
+                        },
+                    ));
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    for new in &new_types {
+        if !old_types.iter().any(|old| is_same_type(old, new)) {
+            changes.push(Change::new(&new.name, ChangeKind::TypeAdded));
+        }
+    }
+
+    Ok(changes)
+}
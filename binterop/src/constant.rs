@@ -0,0 +1,74 @@
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::types::primitives::{INTEGER_PRIMITIVE_NAMES, PRIMITIVES};
+use crate::types::Type;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Constant {
+    pub name: String,
+    #[serde(default)]
+    pub namespace: String,
+    pub type_index: usize,
+    pub value: i128,
+    #[serde(default)]
+    pub attributes: Vec<(String, String)>,
+    #[serde(default)]
+    pub doc: String,
+}
+impl Constant {
+    pub fn new(
+        name: &str,
+        type_index: usize,
+        value: i128,
+        attributes: &[(String, String)],
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            namespace: String::new(),
+            type_index,
+            value,
+            attributes: attributes.to_vec(),
+            doc: String::new(),
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        PRIMITIVES
+            .index(self.type_index)
+            .map(|primitive| primitive.name)
+            .unwrap_or("<invalid>")
+    }
+
+    pub fn check(&self, schema: &Schema) -> Result<(), Error> {
+        let type_name = self.type_name();
+        if !INTEGER_PRIMITIVE_NAMES.contains(&type_name) {
+            return Err(Error::InvalidConstantType {
+                name: self.name.clone(),
+                type_name: type_name.to_string(),
+            });
+        }
+
+        let bits = schema.try_type_size(Type::Primitive, self.type_index)? as u32 * 8;
+        let (min, max) = if type_name.starts_with('i') {
+            (i128::MIN >> (128 - bits), i128::MAX >> (128 - bits))
+        } else {
+            (
+                0,
+                u128::MAX
+                    .checked_shr(128 - bits)
+                    .unwrap_or_default()
+                    .min(i128::MAX as u128) as i128,
+            )
+        };
+        if self.value < min || self.value > max {
+            return Err(Error::ConstantOutOfRange {
+                name: self.name.clone(),
+                type_name: type_name.to_string(),
+                value: self.value,
+            });
+        }
+
+        Ok(())
+    }
+}
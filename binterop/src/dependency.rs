@@ -0,0 +1,273 @@
+use crate::error::Error;
+use crate::schema::{qualified_name, Schema};
+use crate::types::Type;
+use std::collections::HashMap;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Mark {
+    Visiting,
+    Done,
+}
+
+// Arrays, optionals, inline vectors and aliases store their inner type inline. Pointers, vectors,
+// strings, maps, slices and functions store it out of line, so they are what breaks recursion
+fn inline_named_type(schema: &Schema, r#type: Type, index: usize) -> Option<(Type, usize)> {
+    match r#type {
+        Type::Data | Type::Union => Some((r#type, index)),
+        Type::Array => {
+            let array_type = schema.arrays.get(index)?;
+            inline_named_type(schema, array_type.inner_type, array_type.inner_type_index)
+        }
+        Type::Optional => {
+            let optional_type = schema.optionals.get(index)?;
+            inline_named_type(
+                schema,
+                optional_type.inner_type,
+                optional_type.inner_type_index,
+            )
+        }
+        Type::InlineVector => {
+            let inline_vector_type = schema.inline_vectors.get(index)?;
+            inline_named_type(
+                schema,
+                inline_vector_type.inner_type,
+                inline_vector_type.inner_type_index,
+            )
+        }
+        Type::Alias => {
+            let alias_type = schema.aliases.get(index)?;
+            inline_named_type(schema, alias_type.inner_type, alias_type.inner_type_index)
+        }
+        _ => None,
+    }
+}
+
+// The structs and unions a struct or union contains by value, with the members containing them
+fn inline_members(schema: &Schema, r#type: Type, index: usize) -> Vec<(&str, (Type, usize))> {
+    match r#type {
+        Type::Data => schema.types.get(index).map_or_else(Vec::new, |data_type| {
+            data_type
+                .fields
+                .iter()
+                .filter_map(|field| {
+                    inline_named_type(schema, field.r#type, field.type_index)
+                        .map(|inner| (field.name.as_str(), inner))
+                })
+                .collect()
+        }),
+        Type::Union => schema
+            .unions
+            .get(index)
+            .map_or_else(Vec::new, |union_type| {
+                union_type
+                    .variants
+                    .iter()
+                    .filter_map(|variant| {
+                        let (payload_index, payload_type) = variant.payload?;
+                        inline_named_type(schema, payload_type, payload_index)
+                            .map(|inner| (variant.name.as_str(), inner))
+                    })
+                    .collect()
+            }),
+        _ => Vec::new(),
+    }
+}
+
+fn visit(
+    schema: &Schema,
+    node: (Type, usize),
+    marks: &mut HashMap<(Type, usize), Mark>,
+    path: &mut Vec<((Type, usize), String)>,
+    order: &mut Vec<(Type, usize)>,
+) -> Result<(), Error> {
+    match marks.get(&node) {
+        Some(Mark::Done) => return Ok(()),
+        Some(Mark::Visiting) => {
+            let start = path
+                .iter()
+                .position(|(path_node, _)| *path_node == node)
+                .unwrap_or_default();
+
+            return Err(Error::RecursiveWithoutIndirection {
+                type_name: schema.qualified_type_name(node.0, node.1).to_string(),
+                path: path[start..]
+                    .iter()
+                    .map(|(_, member)| member.clone())
+                    .collect(),
+            });
+        }
+        None => {}
+    }
+
+    marks.insert(node, Mark::Visiting);
+    for (member, inner) in inline_members(schema, node.0, node.1) {
+        let owner = schema.qualified_type_name(node.0, node.1);
+        path.push((node, format!("{owner}.{member}")));
+        visit(schema, inner, marks, path, order)?;
+        path.pop();
+    }
+    marks.insert(node, Mark::Done);
+    order.push(node);
+
+    Ok(())
+}
+
+// The types an alias or function type is built from, looking through wrapper types. Structs and
+// unions are named types of their own, so references to them end the walk
+fn structural_references(
+    schema: &Schema,
+    r#type: Type,
+    index: usize,
+    references: &mut Vec<(Type, usize)>,
+) {
+    let mut inner = |inner_type, inner_type_index| {
+        structural_references(schema, inner_type, inner_type_index, references)
+    };
+
+    match r#type {
+        Type::Alias | Type::Function => references.push((r#type, index)),
+        Type::Array => {
+            if let Some(array_type) = schema.arrays.get(index) {
+                inner(array_type.inner_type, array_type.inner_type_index);
+            }
+        }
+        Type::Vector => {
+            if let Some(vector_type) = schema.vectors.get(index) {
+                inner(vector_type.inner_type, vector_type.inner_type_index);
+            }
+        }
+        Type::Pointer => {
+            if let Some(pointer_type) = schema.pointers.get(index) {
+                inner(pointer_type.inner_type, pointer_type.inner_type_index);
+            }
+        }
+        Type::Optional => {
+            if let Some(optional_type) = schema.optionals.get(index) {
+                inner(optional_type.inner_type, optional_type.inner_type_index);
+            }
+        }
+        Type::Slice => {
+            if let Some(slice_type) = schema.slices.get(index) {
+                inner(slice_type.inner_type, slice_type.inner_type_index);
+            }
+        }
+        Type::InlineVector => {
+            if let Some(inline_vector_type) = schema.inline_vectors.get(index) {
+                inner(
+                    inline_vector_type.inner_type,
+                    inline_vector_type.inner_type_index,
+                );
+            }
+        }
+        Type::Map => {
+            if let Some(&map_type) = schema.maps.get(index) {
+                inner(map_type.key_type, map_type.key_type_index);
+                inner(map_type.value_type, map_type.value_type_index);
+            }
+        }
+        Type::Primitive
+        | Type::String
+        | Type::InlineString
+        | Type::Data
+        | Type::Enum
+        | Type::Union
+        | Type::Flags => {}
+    }
+}
+
+fn visit_alias(
+    schema: &Schema,
+    node: (Type, usize),
+    marks: &mut HashMap<(Type, usize), Mark>,
+) -> Result<(), Error> {
+    match marks.get(&node) {
+        Some(Mark::Done) => return Ok(()),
+        Some(Mark::Visiting) => {
+            // The type name of a function type is spelled out from its arguments, which would
+            // recurse forever here
+            let name = match node {
+                (Type::Function, index) => &schema.functions[index].name,
+                (_, index) => &schema.aliases[index].name,
+            };
+
+            return Err(Error::RecursiveAlias {
+                name: qualified_name(schema.type_namespace(node.0, node.1), name),
+            });
+        }
+        None => {}
+    }
+
+    let mut references = Vec::new();
+    match node {
+        (Type::Alias, index) => {
+            if let Some(alias_type) = schema.aliases.get(index) {
+                structural_references(
+                    schema,
+                    alias_type.inner_type,
+                    alias_type.inner_type_index,
+                    &mut references,
+                );
+            }
+        }
+        (Type::Function, index) => {
+            if let Some(function_type) = schema.functions.get(index) {
+                let types = function_type
+                    .args
+                    .iter()
+                    .filter_map(|arg| arg.r#type)
+                    .chain(function_type.return_type);
+                for type_data in types {
+                    structural_references(
+                        schema,
+                        type_data.r#type,
+                        type_data.index,
+                        &mut references,
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+
+    marks.insert(node, Mark::Visiting);
+    for reference in references {
+        visit_alias(schema, reference, marks)?;
+    }
+    marks.insert(node, Mark::Done);
+
+    Ok(())
+}
+
+// Aliases and function types are structural, so one that refers to itself without going through a
+// struct or union would have to be expanded forever. Sizes and layouts look through aliases, so this
+// has to be checked before them
+pub fn check_alias_cycles(schema: &Schema) -> Result<(), Error> {
+    let mut marks = HashMap::new();
+
+    let aliases = (0..schema.aliases.len()).map(|index| (Type::Alias, index));
+    let functions = (0..schema.functions.len()).map(|index| (Type::Function, index));
+    for node in aliases.chain(functions) {
+        visit_alias(schema, node, &mut marks)?;
+    }
+
+    Ok(())
+}
+
+// Orders structs and unions so that every type comes after the types it contains by value, which is
+// the order their layouts have to be computed in. A type containing itself by value has no finite
+// size, so such cycles are reported instead
+pub fn layout_order(schema: &Schema) -> Result<Vec<(Type, usize)>, Error> {
+    check_alias_cycles(schema)?;
+
+    let mut marks = HashMap::new();
+    let mut path = Vec::new();
+    let mut order = Vec::new();
+
+    let data_types = (0..schema.types.len()).map(|index| (Type::Data, index));
+    let unions = (0..schema.unions.len()).map(|index| (Type::Union, index));
+    for node in data_types.chain(unions) {
+        visit(schema, node, &mut marks, &mut path, &mut order)?;
+    }
+
+    Ok(order)
+}
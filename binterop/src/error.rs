@@ -0,0 +1,408 @@
+use crate::types::Type;
+use std::fmt::{self, Display};
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    UnknownType {
+        name: String,
+        suggestions: Vec<String>,
+    },
+    AmbiguousType {
+        name: String,
+        candidates: Vec<String>,
+    },
+    IndexOutOfRange {
+        r#type: Type,
+        index: usize,
+    },
+    ParseArray {
+        text: String,
+        reason: String,
+    },
+    ParseVector {
+        text: String,
+        reason: String,
+    },
+    ParseMap {
+        text: String,
+        reason: String,
+    },
+    ParseSlice {
+        text: String,
+        reason: String,
+    },
+    ParseInline {
+        text: String,
+        reason: String,
+    },
+    EmptyUnion {
+        name: String,
+    },
+    UnitVariantNamesType {
+        name: String,
+    },
+    ConflictingDefinition {
+        name: String,
+    },
+    DuplicateType {
+        name: String,
+    },
+    InvalidEnumRepr {
+        enum_name: String,
+        repr: String,
+    },
+    InvalidFlagsRepr {
+        flags_name: String,
+        repr: String,
+    },
+    InvalidFlagValue {
+        flags_name: String,
+        flag: String,
+        value: u128,
+    },
+    InvalidBitfield {
+        field_type: String,
+        bit_width: u32,
+    },
+    InvalidDefault {
+        field_type: String,
+        value: String,
+    },
+    InvalidAttribute {
+        name: String,
+        value: String,
+        reason: String,
+    },
+    ConflictingAttributes {
+        first: String,
+        second: String,
+    },
+    InvalidConstantType {
+        name: String,
+        type_name: String,
+    },
+    ConstantOutOfRange {
+        name: String,
+        type_name: String,
+        value: i128,
+    },
+    UnknownConstant {
+        name: String,
+        available: Vec<String>,
+    },
+    ConflictingConstant {
+        name: String,
+    },
+    RecursiveConstant {
+        name: String,
+        path: Vec<String>,
+    },
+    InvalidConstantExpression {
+        reason: String,
+    },
+    InvalidArrayLength {
+        len: i128,
+    },
+    GenericArgumentCount {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    InstanceNameTaken {
+        instance: String,
+        name: String,
+    },
+    InstantiationTooDeep {
+        name: String,
+        depth: usize,
+    },
+    TargetMismatch {
+        expected: String,
+        found: String,
+    },
+    UnknownTarget {
+        name: String,
+        available: Vec<String>,
+    },
+    RecursiveWithoutIndirection {
+        type_name: String,
+        path: Vec<String>,
+    },
+    RecursiveAlias {
+        name: String,
+    },
+    OutOfBounds {
+        offset: usize,
+        len: usize,
+        buffer_len: usize,
+    },
+    UnresolvedPointer {
+        address: u64,
+        len: usize,
+    },
+    AllocationFailed {
+        reason: String,
+    },
+    UnsupportedPointerWidth {
+        width: usize,
+    },
+    NotAnInteger {
+        type_name: String,
+    },
+    ValueMismatch {
+        value: String,
+        type_name: String,
+    },
+    ValueOutOfRange {
+        value: String,
+        type_name: String,
+    },
+    InvalidValue {
+        type_name: String,
+        reason: String,
+    },
+    UnknownMember {
+        owner: String,
+        member: String,
+    },
+    MissingField {
+        owner: String,
+        field: String,
+    },
+    CapacityExceeded {
+        type_name: String,
+        capacity: usize,
+        len: usize,
+    },
+    InType {
+        name: String,
+        error: Box<Error>,
+    },
+    InMember {
+        owner: String,
+        member: String,
+        error: Box<Error>,
+    },
+    InInclude {
+        path: PathBuf,
+        error: Box<Error>,
+    },
+}
+impl Error {
+    pub fn in_type(self, name: &str) -> Self {
+        Self::InType {
+            name: name.to_string(),
+            error: Box::new(self),
+        }
+    }
+
+    pub fn in_member(self, owner: &str, member: &str) -> Self {
+        Self::InMember {
+            owner: owner.to_string(),
+            member: member.to_string(),
+            error: Box::new(self),
+        }
+    }
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownType { name, suggestions } => {
+                write!(f, "Failed to find type with name {name:?}")?;
+                if let Some((last, rest)) = suggestions.split_last() {
+                    write!(f, "! Did you mean ")?;
+                    for (index, suggestion) in rest.iter().enumerate() {
+                        let separator = if index + 1 < rest.len() { ", " } else { " or " };
+                        write!(f, "{suggestion:?}{separator}")?;
+                    }
+                    write!(f, "{last:?}?")?;
+                }
+
+                Ok(())
+            }
+            Error::AmbiguousType { name, candidates } => write!(
+                f,
+                "Name {name:?} is ambiguous, qualify it as one of {candidates:?}"
+            ),
+            Error::IndexOutOfRange { r#type, index } => {
+                write!(f, "No {type:?} type with index {index}")
+            }
+            Error::ParseArray { text, reason } => {
+                write!(f, "Failed to parse array type {text:?}: {reason}")
+            }
+            Error::ParseVector { text, reason } => {
+                write!(f, "Failed to parse vector type {text:?}: {reason}")
+            }
+            Error::ParseMap { text, reason } => {
+                write!(f, "Failed to parse map type {text:?}: {reason}")
+            }
+            Error::ParseSlice { text, reason } => {
+                write!(f, "Failed to parse slice type {text:?}: {reason}")
+            }
+            Error::ParseInline { text, reason } => {
+                write!(f, "Failed to parse inline type {text:?}: {reason}")
+            }
+            Error::EmptyUnion { name } => write!(f, "Union {name} has no possible types"),
+            Error::UnitVariantNamesType { name } => write!(
+                f,
+                "Variant {name} names a type but has no payload, write it as `<variant>: {name}`"
+            ),
+            Error::ConflictingDefinition { name } => {
+                write!(f, "Type {name} is defined more than once with different layouts")
+            }
+            Error::DuplicateType { name } => write!(f, "Type {name} is declared more than once"),
+            Error::InvalidEnumRepr { enum_name, repr } => write!(
+                f,
+                "Enum {enum_name} uses {repr} as its repr, but only integer primitives are allowed"
+            ),
+            Error::InvalidFlagsRepr { flags_name, repr } => write!(
+                f,
+                "Flags {flags_name} use {repr} as their repr, but only unsigned integer primitives are allowed"
+            ),
+            Error::InvalidFlagValue {
+                flags_name,
+                flag,
+                value,
+            } => write!(
+                f,
+                "{flags_name}.{flag} has value {value:#x}, but flag values must have exactly one bit set"
+            ),
+            Error::InvalidBitfield {
+                field_type,
+                bit_width,
+            } => write!(
+                f,
+                "Bitfield of {bit_width} bits doesn't fit into {field_type}, bitfields must be non-empty integer primitives"
+            ),
+            Error::InvalidDefault { field_type, value } => {
+                write!(f, "Default value {value} is not a valid {field_type}")
+            }
+            Error::InvalidAttribute {
+                name,
+                value,
+                reason,
+            } => write!(f, "Invalid attribute {name}={value:?}: {reason}"),
+            Error::ConflictingAttributes { first, second } => {
+                write!(f, "Attributes {first} and {second} can't be used together")
+            }
+            Error::InvalidConstantType { name, type_name } => write!(
+                f,
+                "Constant {name} has type {type_name}, but only integer primitives are allowed"
+            ),
+            Error::ConstantOutOfRange {
+                name,
+                type_name,
+                value,
+            } => write!(f, "Constant {name} has value {value}, which doesn't fit into {type_name}"),
+            Error::UnknownConstant { name, available } => {
+                write!(f, "Failed to find constant with name {name:?}")?;
+                if !available.is_empty() {
+                    write!(f, "! Available constants: {available:?}")?;
+                }
+                Ok(())
+            }
+            Error::ConflictingConstant { name } => {
+                write!(f, "Constant {name} is defined more than once with different values")
+            }
+            Error::RecursiveConstant { name, path } => write!(
+                f,
+                "Constant {name} depends on itself through {}",
+                path.join(" -> ")
+            ),
+            Error::InvalidConstantExpression { reason } => {
+                write!(f, "Failed to evaluate constant expression: {reason}")
+            }
+            Error::InvalidArrayLength { len } => write!(f, "Array length {len} is out of range"),
+            Error::GenericArgumentCount {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Generic struct {name} takes {expected} type arguments, but {found} were given"
+            ),
+            Error::InstanceNameTaken { instance, name } => write!(
+                f,
+                "{instance} would be named {name}, but another type already has that name"
+            ),
+            Error::InstantiationTooDeep { name, depth } => write!(
+                f,
+                "Instantiating {name} nests generic structs {depth} levels deep, they likely instantiate themselves with growing arguments"
+            ),
+            Error::TargetMismatch { expected, found } => write!(
+                f,
+                "Schema for target {found} cannot be merged into schema for target {expected}"
+            ),
+            Error::UnknownTarget { name, available } => write!(
+                f,
+                "Unknown target {name:?}! Available targets: {available:?}"
+            ),
+            Error::RecursiveWithoutIndirection { type_name, path } => write!(
+                f,
+                "{type_name} contains itself by value through {}, use a pointer or a vector instead",
+                path.join(" -> ")
+            ),
+            Error::RecursiveAlias { name } => write!(
+                f,
+                "{name} refers to itself, only structs and unions can be recursive"
+            ),
+            Error::OutOfBounds {
+                offset,
+                len,
+                buffer_len,
+            } => write!(
+                f,
+                "Failed to access {len} bytes at offset {offset} in buffer of length {buffer_len}"
+            ),
+            Error::UnresolvedPointer { address, len } => {
+                write!(f, "Failed to resolve {len} bytes at address {address:#x}")
+            }
+            Error::AllocationFailed { reason } => write!(f, "Failed to allocate: {reason}"),
+            Error::UnsupportedPointerWidth { width } => {
+                write!(f, "Unsupported pointer width {width}")
+            }
+            Error::NotAnInteger { type_name } => {
+                write!(f, "{type_name} is not an integer primitive")
+            }
+            Error::ValueMismatch { value, type_name } => {
+                write!(f, "Cannot encode {value} as {type_name}")
+            }
+            Error::ValueOutOfRange { value, type_name } => {
+                write!(f, "{value} does not fit into {type_name}")
+            }
+            Error::InvalidValue { type_name, reason } => {
+                write!(f, "Invalid {type_name} value: {reason}")
+            }
+            Error::UnknownMember { owner, member } => {
+                write!(f, "{owner} has no member named {member}")
+            }
+            Error::MissingField { owner, field } => write!(f, "Missing value for {owner}.{field}"),
+            Error::CapacityExceeded {
+                type_name,
+                capacity,
+                len,
+            } => write!(
+                f,
+                "Length {len} exceeds the capacity {capacity} of {type_name}"
+            ),
+            Error::InType { name, error } => write!(f, "{name}: {error}"),
+            Error::InMember {
+                owner,
+                member,
+                error,
+            } => write!(f, "{owner}.{member}: {error}"),
+            Error::InInclude { path, error } => write!(f, "{}: {error}", path.display()),
+        }
+    }
+}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InType { error, .. }
+            | Error::InMember { error, .. }
+            | Error::InInclude { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
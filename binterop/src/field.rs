@@ -1,8 +1,35 @@
+use crate::attributes::{align_attribute, attribute, invalid_attribute, usize_attribute};
+use crate::error::Error;
 use crate::types::Type;
+use crate::value::Value;
 use crate::{schema::Schema, types::WrappedType};
 use serde::{Deserialize, Serialize};
 use std::{alloc::Layout, borrow::Cow};
 
+// Bitfields share their storage unit, which starts at the field offset, with the bitfields
+// before them as long as the bits fit. Only a bitfield with bit_offset 0 starts a new unit
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bitfield {
+    pub bit_offset: u32,
+    pub bit_width: u32,
+}
+impl Bitfield {
+    pub fn new(bit_offset: u32, bit_width: u32) -> Self {
+        Self {
+            bit_offset,
+            bit_width,
+        }
+    }
+
+    pub fn mask(&self) -> u128 {
+        let width_mask = u128::MAX
+            .checked_shr(128 - self.bit_width)
+            .unwrap_or_default();
+
+        width_mask.checked_shl(self.bit_offset).unwrap_or_default()
+    }
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Field {
     pub name: String,
@@ -11,6 +38,12 @@ pub struct Field {
     pub offset: usize,
     pub padding_size: usize,
     pub attributes: Vec<(String, String)>,
+    #[serde(default)]
+    pub bitfield: Option<Bitfield>,
+    #[serde(default)]
+    pub doc: String,
+    #[serde(default)]
+    pub default: Option<Value>,
 }
 impl Field {
     pub fn new(
@@ -28,6 +61,9 @@ impl Field {
             offset,
             padding_size,
             attributes: attributes.to_vec(),
+            bitfield: None,
+            doc: String::new(),
+            default: None,
         }
     }
 
@@ -39,49 +75,114 @@ impl Field {
             offset: 0,
             padding_size: 0,
             attributes: Vec::new(),
+            bitfield: None,
+            doc: String::new(),
+            default: None,
         }
     }
 
+    pub fn with_bitfield(mut self, bitfield: Bitfield) -> Self {
+        self.bitfield = Some(bitfield);
+        self
+    }
+
+    pub fn with_doc(mut self, doc: &str) -> Self {
+        self.doc = doc.to_string();
+        self
+    }
+
+    pub fn with_default(mut self, default: Value) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    pub fn shares_storage(&self) -> bool {
+        self.bitfield
+            .is_some_and(|bitfield| bitfield.bit_offset != 0)
+    }
+
     pub fn new_from_wrapped(name: &str, wrapped_type: &WrappedType, schema: &Schema) -> Self {
-        let type_index = schema.wrapped_type_index(wrapped_type).unwrap_or_else(|| {
-            if let WrappedType::String = wrapped_type {
-                0
-            } else {
-                panic!("Provided schema doesnt contain type {wrapped_type:#?}")
+        Self::try_new_from_wrapped(name, wrapped_type, schema).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_new_from_wrapped(
+        name: &str,
+        wrapped_type: &WrappedType,
+        schema: &Schema,
+    ) -> Result<Self, Error> {
+        let type_index = match wrapped_type {
+            WrappedType::String => 0,
+            wrapped_type => {
+                schema
+                    .wrapped_type_index(wrapped_type)
+                    .ok_or_else(|| Error::UnknownType {
+                        name: wrapped_type.name(),
+                        suggestions: Vec::new(),
+                    })?
             }
-        });
+        };
 
-        Self {
+        Ok(Self {
             name: name.to_string(),
             r#type: wrapped_type.r#type(),
             type_index,
             ..Default::default()
-        }
+        })
+    }
+
+    pub fn try_size(&self, schema: &Schema) -> Result<usize, Error> {
+        schema.try_type_size(self.r#type, self.type_index)
+    }
+
+    pub fn try_align(&self, schema: &Schema) -> Result<usize, Error> {
+        let type_align = schema.try_type_align(self.r#type, self.type_index)?;
+
+        Ok(type_align.max(self.try_align_attribute()?.unwrap_or(1)))
+    }
+
+    pub fn try_align_attribute(&self) -> Result<Option<usize>, Error> {
+        align_attribute(&self.attributes, "align")
     }
 
-    pub fn size(&self, schema: &Schema) -> usize {
-        schema
-            .type_size(self.r#type, self.type_index)
-            .expect("Provided schema does not contain this type!")
+    pub fn try_offset_attribute(&self) -> Result<Option<usize>, Error> {
+        usize_attribute(&self.attributes, "offset")
     }
 
-    pub fn align(&self, schema: &Schema) -> usize {
-        schema
-            .type_align(self.r#type, self.type_index)
-            .expect("Provided schema does not contain this type!")
+    pub fn try_check_attributes(&self) -> Result<(), Error> {
+        for name in ["packed", "no_reorder"] {
+            if let Some(value) = attribute(&self.attributes, name) {
+                return Err(invalid_attribute(name, value, "only applies to structs"));
+            }
+        }
+
+        let align = self.try_align_attribute()?;
+        let offset = self.try_offset_attribute()?;
+        if let (Some(align), Some(offset)) = (align, offset) {
+            if offset % align != 0 {
+                return Err(invalid_attribute(
+                    "offset",
+                    &offset.to_string(),
+                    &format!("not a multiple of the field align {align}"),
+                ));
+            }
+        }
+
+        Ok(())
     }
 
-    pub fn layout(&self, schema: &Schema) -> Layout {
-        Layout::from_size_align(self.size(schema), self.align(schema)).unwrap()
+    pub fn try_layout(&self, schema: &Schema) -> Result<Layout, Error> {
+        Ok(Layout::from_size_align(self.try_size(schema)?, self.try_align(schema)?).unwrap())
     }
 
-    pub fn is_copy(&self, schema: &Schema) -> bool {
-        schema
-            .is_copy(self.r#type, self.type_index)
-            .expect("Provided schema does not contain this type!")
+    pub fn try_is_copy(&self, schema: &Schema) -> Result<bool, Error> {
+        schema.try_is_copy(self.r#type, self.type_index)
     }
 
     pub fn type_name<'a>(&self, schema: &'a Schema) -> Cow<'a, str> {
         schema.type_name(self.r#type, self.type_index)
     }
+
+    pub fn try_type_name<'a>(&self, schema: &'a Schema) -> Result<Cow<'a, str>, Error> {
+        schema.try_type_name(self.r#type, self.type_index)
+    }
 }
@@ -0,0 +1,213 @@
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::types::primitives::PRIMITIVES;
+use crate::types::{Type, TypeData};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+struct Fingerprinter<'a> {
+    schema: &'a Schema,
+    hash: u64,
+    visiting: Vec<(Type, usize)>,
+}
+impl Fingerprinter<'_> {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.hash ^= byte as u64;
+            self.hash = self.hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write(&(value as u64).to_le_bytes());
+    }
+
+    fn write_str(&mut self, text: &str) {
+        self.write_usize(text.len());
+        self.write(text.as_bytes());
+    }
+
+    // Types in the root namespace hash just their name, so their fingerprints don't change
+    fn write_name(&mut self, namespace: &str, name: &str) {
+        if !namespace.is_empty() {
+            self.write_str(namespace);
+            self.write_str(".");
+        }
+        self.write_str(name);
+    }
+
+    // Layout attributes like align and packed can change the size or alignment of a type while
+    // keeping every field offset the same
+    fn write_layout(&mut self, size: usize, align: usize) {
+        self.write_usize(size);
+        self.write_usize(align);
+    }
+
+    fn write_type_data(&mut self, type_data: Option<TypeData>) -> Result<(), Error> {
+        match type_data {
+            Some(type_data) => self.write_type(type_data.r#type, type_data.index),
+            None => {
+                self.write_str("?");
+                Ok(())
+            }
+        }
+    }
+
+    fn write_type(&mut self, r#type: Type, index: usize) -> Result<(), Error> {
+        if !self.schema.contains_type(r#type, index) {
+            return Err(Error::IndexOutOfRange { r#type, index });
+        }
+
+        self.write_str(&format!("{type:?}"));
+
+        // Recursive types refer back to an enclosing type by its distance up the stack
+        if let Some(position) = self
+            .visiting
+            .iter()
+            .rposition(|&visiting| visiting == (r#type, index))
+        {
+            self.write_str("@");
+            self.write_usize(self.visiting.len() - position);
+            return Ok(());
+        }
+
+        self.visiting.push((r#type, index));
+
+        let schema = self.schema;
+        match r#type {
+            Type::Primitive => self.write_str(PRIMITIVES.name_of(index).unwrap()),
+            Type::Data => {
+                let data_type = &schema.types[index];
+
+                self.write_name(&data_type.namespace, &data_type.name);
+                self.write_layout(data_type.try_size(schema)?, data_type.try_align(schema)?);
+                self.write_usize(data_type.fields.len());
+                for field in &data_type.fields {
+                    self.write_str(&field.name);
+                    self.write_usize(field.offset);
+                    if let Some(bitfield) = field.bitfield {
+                        self.write_str(":");
+                        self.write_usize(bitfield.bit_offset as usize);
+                        self.write_usize(bitfield.bit_width as usize);
+                    }
+                    self.write_type(field.r#type, field.type_index)?;
+                }
+            }
+            Type::Enum => {
+                let enum_type = &schema.enums[index];
+
+                self.write_name(&enum_type.namespace, &enum_type.name);
+                self.write_type(Type::Primitive, enum_type.repr_index)?;
+                self.write_usize(enum_type.variants.len());
+                for variant in &enum_type.variants {
+                    self.write_str(&variant.name);
+                    self.write(&variant.value.to_le_bytes());
+                }
+            }
+            Type::Flags => {
+                let flags_type = &schema.flags[index];
+
+                self.write_name(&flags_type.namespace, &flags_type.name);
+                self.write_type(Type::Primitive, flags_type.repr_index)?;
+                self.write_usize(flags_type.bits.len());
+                for bit in &flags_type.bits {
+                    self.write_str(&bit.name);
+                    self.write_usize(bit.bit as usize);
+                }
+            }
+            Type::Alias => {
+                let alias_type = &schema.aliases[index];
+
+                self.write_name(&alias_type.namespace, &alias_type.name);
+                self.write_usize(alias_type.is_distinct as usize);
+                self.write_type(alias_type.inner_type, alias_type.inner_type_index)?;
+            }
+            Type::Union => {
+                let union_type = &schema.unions[index];
+
+                self.write_name(&union_type.namespace, &union_type.name);
+                self.write_layout(union_type.try_size(schema)?, union_type.try_align(schema)?);
+                self.write_usize(union_type.variants.len());
+                for variant in &union_type.variants {
+                    self.write_str(&variant.name);
+                    match variant.payload {
+                        Some((index, r#type)) => self.write_type(r#type, index)?,
+                        None => self.write_str("?"),
+                    }
+                }
+            }
+            Type::Array => {
+                let array_type = schema.arrays[index];
+
+                self.write_usize(array_type.len);
+                self.write_type(array_type.inner_type, array_type.inner_type_index)?;
+            }
+            Type::Vector => {
+                let vector_type = schema.vectors[index];
+
+                self.write_type(vector_type.inner_type, vector_type.inner_type_index)?;
+            }
+            Type::Pointer => {
+                let pointer_type = schema.pointers[index];
+
+                self.write_type(pointer_type.inner_type, pointer_type.inner_type_index)?;
+            }
+            Type::Optional => {
+                let optional_type = schema.optionals[index];
+
+                self.write_type(optional_type.inner_type, optional_type.inner_type_index)?;
+            }
+            Type::Map => {
+                let map_type = schema.maps[index];
+
+                self.write_type(map_type.key_type, map_type.key_type_index)?;
+                self.write_type(map_type.value_type, map_type.value_type_index)?;
+            }
+            Type::Slice => {
+                let slice_type = schema.slices[index];
+
+                self.write_type(slice_type.inner_type, slice_type.inner_type_index)?;
+            }
+            Type::InlineString => {
+                self.write_usize(schema.inline_strings[index].capacity);
+            }
+            Type::InlineVector => {
+                let inline_vector_type = schema.inline_vectors[index];
+
+                self.write_usize(inline_vector_type.capacity);
+                self.write_type(
+                    inline_vector_type.inner_type,
+                    inline_vector_type.inner_type_index,
+                )?;
+            }
+            Type::String => {}
+            Type::Function => {
+                let function_type = &schema.functions[index];
+
+                self.write_name(&function_type.namespace, &function_type.name);
+                self.write_usize(function_type.args.len());
+                for arg in &function_type.args {
+                    self.write_str(&arg.name);
+                    self.write_type_data(arg.r#type)?;
+                }
+                self.write_type_data(function_type.return_type)?;
+            }
+        }
+
+        self.visiting.pop();
+
+        Ok(())
+    }
+}
+
+pub fn fingerprint(schema: &Schema, r#type: Type, index: usize) -> Result<u64, Error> {
+    let mut fingerprinter = Fingerprinter {
+        schema,
+        hash: FNV_OFFSET_BASIS,
+        visiting: Vec::new(),
+    };
+    fingerprinter.write_type(r#type, index)?;
+
+    Ok(fingerprinter.hash)
+}
@@ -1,12 +1,23 @@
+pub use error::Error;
 use schema::Schema;
 use types::pointer::PointerType;
 use types::primitives::PRIMITIVES;
 use types::{array::ArrayType, WrappedType};
 
+pub mod attributes;
+pub mod compatibility;
+pub mod constant;
+pub mod dependency;
+pub mod error;
 pub mod field;
+pub mod fingerprint;
+pub mod merge;
 pub mod schema;
 pub mod std;
+pub mod target;
 pub mod types;
+pub mod validation;
+pub mod value;
 
 pub trait Binterop {
     fn binterop_type(schema: &mut Schema) -> WrappedType;
@@ -14,14 +25,19 @@ pub trait Binterop {
 
 macro_rules! binterop_primitive {
     ($primitive_type:ty) => {
+        binterop_primitive!($primitive_type, stringify!($primitive_type));
+    };
+    ($primitive_type:ty, $primitive_name:expr) => {
         impl Binterop for $primitive_type {
             fn binterop_type(_: &mut Schema) -> WrappedType {
-                WrappedType::Primitive(PRIMITIVES[stringify!($primitive_type)])
+                WrappedType::Primitive(PRIMITIVES[$primitive_name])
             }
         }
     };
 }
 
+binterop_primitive!(bool);
+
 binterop_primitive!(i8);
 binterop_primitive!(u8);
 binterop_primitive!(i16);
@@ -30,9 +46,16 @@ binterop_primitive!(i32);
 binterop_primitive!(u32);
 binterop_primitive!(i64);
 binterop_primitive!(u64);
+binterop_primitive!(i128);
+binterop_primitive!(u128);
+binterop_primitive!(isize);
+binterop_primitive!(usize);
 
 binterop_primitive!(f32);
 binterop_primitive!(f64);
+binterop_primitive!(crate::std::F16, "f16");
+
+binterop_primitive!(char, "char32");
 
 impl<T: Binterop, const N: usize> Binterop for [T; N] {
     fn binterop_type(schema: &mut Schema) -> WrappedType {
@@ -0,0 +1,414 @@
+use crate::error::Error;
+use crate::schema::{qualified_name, Schema};
+use crate::types::function::Arg;
+use crate::types::{Type, TypeData};
+
+fn named_type(schema: &Schema, namespace: &str, name: &str) -> Option<(Type, usize)> {
+    schema
+        .named_types()
+        .find(|&(type_namespace, type_name, _)| type_namespace == namespace && type_name == name)
+        .map(|(_, _, named_type)| named_type)
+}
+
+fn find_or_push<T: PartialEq>(items: &mut Vec<T>, item: T) -> usize {
+    items
+        .iter()
+        .position(|existing_item| existing_item == &item)
+        .unwrap_or_else(|| {
+            items.push(item);
+            items.len() - 1
+        })
+}
+
+struct Merger<'a> {
+    schema: &'a mut Schema,
+    other: &'a Schema,
+    types: Vec<usize>,
+    enums: Vec<usize>,
+    unions: Vec<usize>,
+    flags: Vec<usize>,
+    aliases: Vec<usize>,
+    functions: Vec<usize>,
+    arrays: Vec<Option<usize>>,
+    vectors: Vec<Option<usize>>,
+    pointers: Vec<Option<usize>>,
+    optionals: Vec<Option<usize>>,
+    maps: Vec<Option<usize>>,
+    slices: Vec<Option<usize>>,
+    inline_vectors: Vec<Option<usize>>,
+}
+impl Merger<'_> {
+    fn map_named_types<'b>(
+        &self,
+        r#type: Type,
+        names: impl Iterator<Item = (&'b String, &'b String)>,
+        mut next_index: usize,
+    ) -> Result<Vec<usize>, Error> {
+        names
+            .enumerate()
+            .map(|(other_index, (namespace, name))| {
+                match named_type(self.schema, namespace, name) {
+                    Some((existing_type, existing_index)) => {
+                        if existing_type == r#type
+                            && self.schema.try_fingerprint(existing_type, existing_index)?
+                                == self.other.try_fingerprint(r#type, other_index)?
+                        {
+                            Ok(existing_index)
+                        } else {
+                            Err(Error::ConflictingDefinition {
+                                name: qualified_name(namespace, name),
+                            })
+                        }
+                    }
+                    None => {
+                        next_index += 1;
+                        Ok(next_index - 1)
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn remap(&mut self, r#type: Type, index: usize) -> Result<usize, Error> {
+        let out_of_range = || Error::IndexOutOfRange { r#type, index };
+
+        match r#type {
+            Type::Primitive | Type::String => Ok(index),
+            Type::Data => self.types.get(index).copied().ok_or_else(out_of_range),
+            Type::Enum => self.enums.get(index).copied().ok_or_else(out_of_range),
+            Type::Union => self.unions.get(index).copied().ok_or_else(out_of_range),
+            Type::Flags => self.flags.get(index).copied().ok_or_else(out_of_range),
+            Type::Alias => self.aliases.get(index).copied().ok_or_else(out_of_range),
+            Type::Function => self.functions.get(index).copied().ok_or_else(out_of_range),
+            Type::Array => {
+                if let Some(mapped_index) = *self.arrays.get(index).ok_or_else(out_of_range)? {
+                    return Ok(mapped_index);
+                }
+
+                let mut array_type = self.other.arrays[index];
+                array_type.inner_type_index =
+                    self.remap(array_type.inner_type, array_type.inner_type_index)?;
+
+                let mapped_index = find_or_push(&mut self.schema.arrays, array_type);
+                self.arrays[index] = Some(mapped_index);
+
+                Ok(mapped_index)
+            }
+            Type::Vector => {
+                if let Some(mapped_index) = *self.vectors.get(index).ok_or_else(out_of_range)? {
+                    return Ok(mapped_index);
+                }
+
+                let mut vector_type = self.other.vectors[index];
+                vector_type.inner_type_index =
+                    self.remap(vector_type.inner_type, vector_type.inner_type_index)?;
+
+                let mapped_index = find_or_push(&mut self.schema.vectors, vector_type);
+                self.vectors[index] = Some(mapped_index);
+
+                Ok(mapped_index)
+            }
+            Type::Pointer => {
+                if let Some(mapped_index) = *self.pointers.get(index).ok_or_else(out_of_range)? {
+                    return Ok(mapped_index);
+                }
+
+                let mut pointer_type = self.other.pointers[index];
+                pointer_type.inner_type_index =
+                    self.remap(pointer_type.inner_type, pointer_type.inner_type_index)?;
+
+                let mapped_index = find_or_push(&mut self.schema.pointers, pointer_type);
+                self.pointers[index] = Some(mapped_index);
+
+                Ok(mapped_index)
+            }
+            Type::Optional => {
+                if let Some(mapped_index) = *self.optionals.get(index).ok_or_else(out_of_range)? {
+                    return Ok(mapped_index);
+                }
+
+                let mut optional_type = self.other.optionals[index];
+                optional_type.inner_type_index =
+                    self.remap(optional_type.inner_type, optional_type.inner_type_index)?;
+
+                let mapped_index = find_or_push(&mut self.schema.optionals, optional_type);
+                self.optionals[index] = Some(mapped_index);
+
+                Ok(mapped_index)
+            }
+            Type::Map => {
+                if let Some(mapped_index) = *self.maps.get(index).ok_or_else(out_of_range)? {
+                    return Ok(mapped_index);
+                }
+
+                let mut map_type = self.other.maps[index];
+                map_type.key_type_index = self.remap(map_type.key_type, map_type.key_type_index)?;
+                map_type.value_type_index =
+                    self.remap(map_type.value_type, map_type.value_type_index)?;
+
+                let mapped_index = find_or_push(&mut self.schema.maps, map_type);
+                self.maps[index] = Some(mapped_index);
+
+                Ok(mapped_index)
+            }
+            Type::Slice => {
+                if let Some(mapped_index) = *self.slices.get(index).ok_or_else(out_of_range)? {
+                    return Ok(mapped_index);
+                }
+
+                let mut slice_type = self.other.slices[index];
+                slice_type.inner_type_index =
+                    self.remap(slice_type.inner_type, slice_type.inner_type_index)?;
+
+                let mapped_index = find_or_push(&mut self.schema.slices, slice_type);
+                self.slices[index] = Some(mapped_index);
+
+                Ok(mapped_index)
+            }
+            Type::InlineString => {
+                let inline_string_type = *self
+                    .other
+                    .inline_strings
+                    .get(index)
+                    .ok_or_else(out_of_range)?;
+
+                Ok(find_or_push(
+                    &mut self.schema.inline_strings,
+                    inline_string_type,
+                ))
+            }
+            Type::InlineVector => {
+                if let Some(mapped_index) =
+                    *self.inline_vectors.get(index).ok_or_else(out_of_range)?
+                {
+                    return Ok(mapped_index);
+                }
+
+                let mut inline_vector_type = self.other.inline_vectors[index];
+                inline_vector_type.inner_type_index = self.remap(
+                    inline_vector_type.inner_type,
+                    inline_vector_type.inner_type_index,
+                )?;
+
+                let mapped_index =
+                    find_or_push(&mut self.schema.inline_vectors, inline_vector_type);
+                self.inline_vectors[index] = Some(mapped_index);
+
+                Ok(mapped_index)
+            }
+        }
+    }
+
+    fn remap_type_data(&mut self, type_data: Option<TypeData>) -> Result<Option<TypeData>, Error> {
+        type_data
+            .map(|mut type_data| {
+                type_data.index = self.remap(type_data.r#type, type_data.index)?;
+                Ok(type_data)
+            })
+            .transpose()
+    }
+
+    fn merge(&mut self) -> Result<(), Error> {
+        let other = self.other;
+
+        for constant in &other.constants {
+            match self.schema.constants.iter().find(|existing| {
+                existing.namespace == constant.namespace && existing.name == constant.name
+            }) {
+                Some(existing) if existing == constant => {}
+                Some(_) => {
+                    return Err(Error::ConflictingConstant {
+                        name: qualified_name(&constant.namespace, &constant.name),
+                    })
+                }
+                None => self.schema.constants.push(constant.clone()),
+            }
+        }
+
+        for index in 0..other.arrays.len() {
+            self.remap(Type::Array, index)?;
+        }
+        for index in 0..other.vectors.len() {
+            self.remap(Type::Vector, index)?;
+        }
+        for index in 0..other.pointers.len() {
+            self.remap(Type::Pointer, index)?;
+        }
+        for index in 0..other.optionals.len() {
+            self.remap(Type::Optional, index)?;
+        }
+        for index in 0..other.maps.len() {
+            self.remap(Type::Map, index)?;
+        }
+        for index in 0..other.slices.len() {
+            self.remap(Type::Slice, index)?;
+        }
+        for index in 0..other.inline_strings.len() {
+            self.remap(Type::InlineString, index)?;
+        }
+        for index in 0..other.inline_vectors.len() {
+            self.remap(Type::InlineVector, index)?;
+        }
+
+        let mut new_types = Vec::new();
+        for (index, data_type) in other.types.iter().enumerate() {
+            if self.types[index] < self.schema.types.len() {
+                continue;
+            }
+
+            let mut data_type = data_type.clone();
+            for field in &mut data_type.fields {
+                field.type_index = self.remap(field.r#type, field.type_index)?;
+            }
+            new_types.push(data_type);
+        }
+
+        let mut new_unions = Vec::new();
+        for (index, union_type) in other.unions.iter().enumerate() {
+            if self.unions[index] < self.schema.unions.len() {
+                continue;
+            }
+
+            let mut union_type = union_type.clone();
+            for (type_index, r#type) in union_type
+                .variants
+                .iter_mut()
+                .filter_map(|variant| variant.payload.as_mut())
+            {
+                *type_index = self.remap(*r#type, *type_index)?;
+            }
+            new_unions.push(union_type);
+        }
+
+        let mut new_functions = Vec::new();
+        for (index, function_type) in other.functions.iter().enumerate() {
+            if self.functions[index] < self.schema.functions.len() {
+                continue;
+            }
+
+            let mut function_type = function_type.clone();
+            function_type.args = function_type
+                .args
+                .into_iter()
+                .map(|arg| Ok(Arg::new(arg.name, self.remap_type_data(arg.r#type)?)))
+                .collect::<Result<_, Error>>()?;
+            function_type.return_type = self.remap_type_data(function_type.return_type)?;
+            new_functions.push(function_type);
+        }
+
+        let new_enums = other
+            .enums
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| self.enums[index] >= self.schema.enums.len())
+            .map(|(_, enum_type)| enum_type.clone())
+            .collect::<Vec<_>>();
+        let new_flags = other
+            .flags
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| self.flags[index] >= self.schema.flags.len())
+            .map(|(_, flags_type)| flags_type.clone())
+            .collect::<Vec<_>>();
+
+        let mut new_aliases = Vec::new();
+        for (index, alias_type) in other.aliases.iter().enumerate() {
+            if self.aliases[index] < self.schema.aliases.len() {
+                continue;
+            }
+
+            let mut alias_type = alias_type.clone();
+            alias_type.inner_type_index =
+                self.remap(alias_type.inner_type, alias_type.inner_type_index)?;
+            new_aliases.push(alias_type);
+        }
+
+        self.schema.is_packed |= other.is_packed;
+        self.schema.types.extend(new_types);
+        self.schema.enums.extend(new_enums);
+        self.schema.flags.extend(new_flags);
+        self.schema.aliases.extend(new_aliases);
+        self.schema.unions.extend(new_unions);
+        self.schema.functions.extend(new_functions);
+
+        Ok(())
+    }
+}
+
+pub fn merge(schema: &mut Schema, other: &Schema) -> Result<(), Error> {
+    let mut merger = Merger {
+        schema,
+        other,
+        types: Vec::new(),
+        enums: Vec::new(),
+        unions: Vec::new(),
+        flags: Vec::new(),
+        aliases: Vec::new(),
+        functions: Vec::new(),
+        arrays: vec![None; other.arrays.len()],
+        vectors: vec![None; other.vectors.len()],
+        pointers: vec![None; other.pointers.len()],
+        optionals: vec![None; other.optionals.len()],
+        maps: vec![None; other.maps.len()],
+        slices: vec![None; other.slices.len()],
+        inline_vectors: vec![None; other.inline_vectors.len()],
+    };
+
+    if merger.schema.target != other.target {
+        return Err(Error::TargetMismatch {
+            expected: merger.schema.target.name.clone(),
+            found: other.target.name.clone(),
+        });
+    }
+
+    merger.types = merger.map_named_types(
+        Type::Data,
+        other
+            .types
+            .iter()
+            .map(|data_type| (&data_type.namespace, &data_type.name)),
+        merger.schema.types.len(),
+    )?;
+    merger.enums = merger.map_named_types(
+        Type::Enum,
+        other
+            .enums
+            .iter()
+            .map(|enum_type| (&enum_type.namespace, &enum_type.name)),
+        merger.schema.enums.len(),
+    )?;
+    merger.unions = merger.map_named_types(
+        Type::Union,
+        other
+            .unions
+            .iter()
+            .map(|union_type| (&union_type.namespace, &union_type.name)),
+        merger.schema.unions.len(),
+    )?;
+    merger.flags = merger.map_named_types(
+        Type::Flags,
+        other
+            .flags
+            .iter()
+            .map(|flags_type| (&flags_type.namespace, &flags_type.name)),
+        merger.schema.flags.len(),
+    )?;
+    merger.aliases = merger.map_named_types(
+        Type::Alias,
+        other
+            .aliases
+            .iter()
+            .map(|alias_type| (&alias_type.namespace, &alias_type.name)),
+        merger.schema.aliases.len(),
+    )?;
+    merger.functions = merger.map_named_types(
+        Type::Function,
+        other
+            .functions
+            .iter()
+            .map(|function_type| (&function_type.namespace, &function_type.name)),
+        merger.schema.functions.len(),
+    )?;
+
+    merger.merge()
+}
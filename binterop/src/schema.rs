@@ -1,17 +1,161 @@
+use crate::compatibility::{self, Change};
+use crate::constant::Constant;
+use crate::error::Error;
+use crate::fingerprint;
+use crate::merge;
+use crate::target::TargetLayout;
+use crate::types::alias::AliasType;
 use crate::types::array::ArrayType;
 use crate::types::data::DataType;
+use crate::types::flags::FlagsType;
 use crate::types::function::FunctionType;
+use crate::types::inline::{self, InlineStringType, InlineVectorType};
+use crate::types::map::MapType;
+use crate::types::optional::OptionalType;
 use crate::types::pointer::PointerType;
 use crate::types::primitives::PRIMITIVES;
 use crate::types::r#enum::EnumType;
+use crate::types::slice::SliceType;
 use crate::types::union::UnionType;
 use crate::types::vector::VectorType;
 use crate::types::{Type, TypeData};
+use crate::validation::{self, ValidationError};
 use crate::WrappedType;
 use serde::{Deserialize, Serialize};
 use std::alloc::Layout;
 use std::borrow::Cow;
-use std::mem::{align_of, size_of};
+use std::iter;
+use std::mem::size_of;
+
+type NamedTypeEntry<'a> = (&'a str, &'a str, (Type, usize));
+
+pub fn qualified_name(namespace: &str, name: &str) -> String {
+    if namespace.is_empty() {
+        name.to_string()
+    } else {
+        format!("{namespace}.{name}")
+    }
+}
+
+// Qualified names match every namespace ending with their qualifier, unqualified ones are looked
+// up in the given namespace first and then in the root namespace
+pub fn resolve_name<'n, T: Copy>(
+    candidates: impl Iterator<Item = (&'n str, &'n str, T)>,
+    name: &str,
+    namespace: &str,
+) -> Result<Option<T>, Error> {
+    let matches = match name.rsplit_once('.') {
+        Some((qualifier, base_name)) => {
+            let suffix = format!(".{qualifier}");
+            candidates
+                .filter(|&(candidate_namespace, candidate_name, _)| {
+                    candidate_name == base_name
+                        && (candidate_namespace == qualifier
+                            || candidate_namespace.ends_with(&suffix))
+                })
+                .collect::<Vec<_>>()
+        }
+        None => {
+            let candidates = candidates
+                .filter(|&(_, candidate_name, _)| candidate_name == name)
+                .collect::<Vec<_>>();
+            let in_namespace = |namespace: &str| {
+                candidates
+                    .iter()
+                    .copied()
+                    .filter(|&(candidate_namespace, _, _)| candidate_namespace == namespace)
+                    .collect::<Vec<_>>()
+            };
+
+            let local_matches = in_namespace(namespace);
+            if local_matches.is_empty() {
+                in_namespace("")
+            } else {
+                local_matches
+            }
+        }
+    };
+
+    match matches.first() {
+        None => Ok(None),
+        Some(&(first_namespace, _, value))
+            if matches
+                .iter()
+                .all(|&(candidate_namespace, _, _)| candidate_namespace == first_namespace) =>
+        {
+            Ok(Some(value))
+        }
+        Some(_) => Err(Error::AmbiguousType {
+            name: name.to_string(),
+            candidates: matches
+                .iter()
+                .map(|&(candidate_namespace, candidate_name, _)| {
+                    qualified_name(candidate_namespace, candidate_name)
+                })
+                .collect(),
+        }),
+    }
+}
+
+// Optimal string alignment distance, so swapping two adjacent characters counts as one edit
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.chars().collect::<Vec<_>>(), b.chars().collect::<Vec<_>>());
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    distances[0] = (0..=b.len()).collect();
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = distances[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = substitution
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
+
+// Names within a few edits of the given one, closest first. The name is compared with the base name
+// of a candidate and every way of qualifying it, so "Heder" and "net.Heder" both suggest
+// "game.net.Header"
+pub fn similar_names<'n>(
+    candidates: impl Iterator<Item = (&'n str, &'n str)>,
+    name: &str,
+) -> Vec<String> {
+    const MAX_SUGGESTIONS: usize = 3;
+
+    let name = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(1);
+
+    let mut similar = candidates
+        .filter_map(|(namespace, candidate_name)| {
+            let qualified = qualified_name(namespace, candidate_name);
+            let lowercase = qualified.to_lowercase();
+            let distance = iter::once(0)
+                .chain(lowercase.match_indices('.').map(|(index, _)| index + 1))
+                .map(|start| edit_distance(&name, &lowercase[start..]))
+                .min()
+                .unwrap_or(usize::MAX);
+
+            (distance <= max_distance).then_some((distance, qualified))
+        })
+        .collect::<Vec<_>>();
+    similar.sort();
+    similar.dedup_by(|(_, a), (_, b)| a == b);
+
+    similar
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, name)| name)
+        .collect()
+}
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Schema {
@@ -23,43 +167,78 @@ pub struct Schema {
     pub pointers: Vec<PointerType>,
     pub vectors: Vec<VectorType>,
     pub functions: Vec<FunctionType>,
+    #[serde(default)]
+    pub optionals: Vec<OptionalType>,
+    #[serde(default)]
+    pub maps: Vec<MapType>,
+    #[serde(default)]
+    pub slices: Vec<SliceType>,
+    #[serde(default)]
+    pub inline_strings: Vec<InlineStringType>,
+    #[serde(default)]
+    pub inline_vectors: Vec<InlineVectorType>,
+    #[serde(default)]
+    pub flags: Vec<FlagsType>,
+    #[serde(default)]
+    pub constants: Vec<Constant>,
+    #[serde(default)]
+    pub aliases: Vec<AliasType>,
+    #[serde(default)]
+    pub target: TargetLayout,
 }
 impl Schema {
-    pub fn new(
-        is_packed: bool,
-        types: &[DataType],
-        enums: &[EnumType],
-        unions: &[UnionType],
-        arrays: &[ArrayType],
-        pointers: &[PointerType],
-        vectors: &[VectorType],
-        functions: &[FunctionType],
-    ) -> Self {
-        Self {
-            is_packed,
-            types: types.to_vec(),
-            enums: enums.to_vec(),
-            unions: unions.to_vec(),
-            arrays: arrays.to_vec(),
-            pointers: pointers.to_vec(),
-            vectors: vectors.to_vec(),
-            functions: functions.to_vec(),
-        }
+    pub fn type_name(&'_ self, r#type: Type, index: usize) -> Cow<'_, str> {
+        self.try_type_name(r#type, index)
+            .unwrap_or_else(|err| panic!("{err}"))
     }
 
-    pub fn type_name(&'_ self, r#type: Type, index: usize) -> Cow<'_, str> {
-        match r#type {
-            Type::Primitive => Cow::Borrowed(PRIMITIVES.name_of(index).unwrap()),
-            Type::Data => Cow::Borrowed(&self.types[index].name),
-            Type::Enum => Cow::Borrowed(&self.enums[index].name),
-            Type::Union => Cow::Borrowed(&self.unions[index].name),
+    pub fn try_type_name(&'_ self, r#type: Type, index: usize) -> Result<Cow<'_, str>, Error> {
+        let out_of_range = || Error::IndexOutOfRange { r#type, index };
+
+        let type_name = match r#type {
+            Type::Primitive => Cow::Borrowed(PRIMITIVES.name_of(index).ok_or_else(out_of_range)?),
+            Type::Data => Cow::Borrowed(
+                self.types
+                    .get(index)
+                    .ok_or_else(out_of_range)?
+                    .name
+                    .as_str(),
+            ),
+            Type::Enum => Cow::Borrowed(
+                self.enums
+                    .get(index)
+                    .ok_or_else(out_of_range)?
+                    .name
+                    .as_str(),
+            ),
+            Type::Union => Cow::Borrowed(
+                self.unions
+                    .get(index)
+                    .ok_or_else(out_of_range)?
+                    .name
+                    .as_str(),
+            ),
+            Type::Flags => Cow::Borrowed(
+                self.flags
+                    .get(index)
+                    .ok_or_else(out_of_range)?
+                    .name
+                    .as_str(),
+            ),
+            Type::Alias => Cow::Borrowed(
+                self.aliases
+                    .get(index)
+                    .ok_or_else(out_of_range)?
+                    .name
+                    .as_str(),
+            ),
             Type::Array => {
                 let ArrayType {
                     inner_type,
                     inner_type_index,
                     len,
-                } = self.arrays[index];
-                let inner_type_name = self.type_name(inner_type, inner_type_index);
+                } = *self.arrays.get(index).ok_or_else(out_of_range)?;
+                let inner_type_name = self.try_type_name(inner_type, inner_type_index)?;
 
                 Cow::Owned(format!("[{inner_type_name}:{len}]"))
             }
@@ -67,8 +246,8 @@ impl Schema {
                 let VectorType {
                     inner_type,
                     inner_type_index,
-                } = self.vectors[index];
-                let inner_type_name = self.type_name(inner_type, inner_type_index);
+                } = *self.vectors.get(index).ok_or_else(out_of_range)?;
+                let inner_type_name = self.try_type_name(inner_type, inner_type_index)?;
 
                 Cow::Owned(format!("<{inner_type_name}>"))
             }
@@ -76,86 +255,353 @@ impl Schema {
                 let PointerType {
                     inner_type,
                     inner_type_index,
-                } = self.pointers[index];
-                let inner_type_name = self.type_name(inner_type, inner_type_index);
+                } = *self.pointers.get(index).ok_or_else(out_of_range)?;
+                let inner_type_name = self.try_type_name(inner_type, inner_type_index)?;
 
                 Cow::Owned(format!("{inner_type_name}*"))
             }
             Type::String => Cow::Owned("String".to_string()),
             Type::Function => {
-                let function = &self.functions[index];
+                let function = self.functions.get(index).ok_or_else(out_of_range)?;
                 let args = function
                     .args
                     .iter()
-                    .map(|arg| {
-                        let arg_type = arg.r#type.as_ref().unwrap();
-                        self.type_name(arg_type.r#type, arg_type.index)
+                    .map(|arg| match &arg.r#type {
+                        Some(arg_type) => self.try_type_name(arg_type.r#type, arg_type.index),
+                        None => Ok(Cow::Borrowed("?")),
                     })
-                    .collect::<Vec<_>>();
+                    .collect::<Result<Vec<_>, _>>()?;
                 Cow::Owned(format!("Function({})", args.join(", ")))
             }
-        }
+            Type::Optional => {
+                let OptionalType {
+                    inner_type,
+                    inner_type_index,
+                } = *self.optionals.get(index).ok_or_else(out_of_range)?;
+                let inner_type_name = self.try_type_name(inner_type, inner_type_index)?;
+
+                Cow::Owned(format!("{inner_type_name}?"))
+            }
+            Type::Map => {
+                let map_type = *self.maps.get(index).ok_or_else(out_of_range)?;
+                let key_type_name =
+                    self.try_type_name(map_type.key_type, map_type.key_type_index)?;
+                let value_type_name =
+                    self.try_type_name(map_type.value_type, map_type.value_type_index)?;
+
+                Cow::Owned(format!("{{{key_type_name}: {value_type_name}}}"))
+            }
+            Type::Slice => {
+                let SliceType {
+                    inner_type,
+                    inner_type_index,
+                } = *self.slices.get(index).ok_or_else(out_of_range)?;
+                let inner_type_name = self.try_type_name(inner_type, inner_type_index)?;
+
+                Cow::Owned(format!("&<{inner_type_name}>"))
+            }
+            Type::InlineString => {
+                let InlineStringType { capacity } =
+                    *self.inline_strings.get(index).ok_or_else(out_of_range)?;
+
+                Cow::Owned(format!("String<{capacity}>"))
+            }
+            Type::InlineVector => {
+                let InlineVectorType {
+                    inner_type,
+                    inner_type_index,
+                    capacity,
+                } = *self.inline_vectors.get(index).ok_or_else(out_of_range)?;
+                let inner_type_name = self.try_type_name(inner_type, inner_type_index)?;
+
+                Cow::Owned(format!("<{inner_type_name}:{capacity}>"))
+            }
+        };
+
+        Ok(type_name)
     }
 
-    pub fn type_size(&self, r#type: Type, index: usize) -> Option<usize> {
-        match r#type {
-            Type::Primitive => PRIMITIVES.index(index).map(|primitive| primitive.size),
-            Type::Data => self.types.get(index).map(|data_type| data_type.size(self)),
-            Type::Enum => Some(EnumType::size()),
+    pub fn type_namespace(&self, r#type: Type, index: usize) -> &str {
+        let namespace = match r#type {
+            Type::Data => self.types.get(index).map(|data_type| &data_type.namespace),
+            Type::Enum => self.enums.get(index).map(|enum_type| &enum_type.namespace),
             Type::Union => self
                 .unions
                 .get(index)
-                .map(|union_type| union_type.size(self)),
-            Type::Array => self
-                .arrays
+                .map(|union_type| &union_type.namespace),
+            Type::Flags => self
+                .flags
+                .get(index)
+                .map(|flags_type| &flags_type.namespace),
+            Type::Alias => self
+                .aliases
+                .get(index)
+                .map(|alias_type| &alias_type.namespace),
+            Type::Function => self
+                .functions
                 .get(index)
-                .map(|array_type| array_type.size(self)),
-            Type::Vector | Type::String => Some(VectorType::size()),
-            Type::Pointer => Some(PointerType::size()),
-            Type::Function => Some(FunctionType::size()),
+                .map(|function_type| &function_type.namespace),
+            _ => None,
+        };
+
+        namespace.map(String::as_str).unwrap_or_default()
+    }
+
+    pub fn qualified_type_name(&'_ self, r#type: Type, index: usize) -> Cow<'_, str> {
+        let type_name = self.type_name(r#type, index);
+
+        match self.type_namespace(r#type, index) {
+            "" => type_name,
+            namespace => Cow::Owned(qualified_name(namespace, &type_name)),
+        }
+    }
+
+    pub fn named_types(&self) -> impl Iterator<Item = NamedTypeEntry<'_>> {
+        fn named<'a>(
+            r#type: Type,
+        ) -> impl Fn((usize, (&'a String, &'a String))) -> NamedTypeEntry<'a> {
+            move |(index, (namespace, name))| (namespace, name, (r#type, index))
+        }
+
+        self.types
+            .iter()
+            .map(|data_type| (&data_type.namespace, &data_type.name))
+            .enumerate()
+            .map(named(Type::Data))
+            .chain(
+                self.enums
+                    .iter()
+                    .map(|enum_type| (&enum_type.namespace, &enum_type.name))
+                    .enumerate()
+                    .map(named(Type::Enum)),
+            )
+            .chain(
+                self.unions
+                    .iter()
+                    .map(|union_type| (&union_type.namespace, &union_type.name))
+                    .enumerate()
+                    .map(named(Type::Union)),
+            )
+            .chain(
+                self.flags
+                    .iter()
+                    .map(|flags_type| (&flags_type.namespace, &flags_type.name))
+                    .enumerate()
+                    .map(named(Type::Flags)),
+            )
+            .chain(
+                self.aliases
+                    .iter()
+                    .map(|alias_type| (&alias_type.namespace, &alias_type.name))
+                    .enumerate()
+                    .map(named(Type::Alias)),
+            )
+            .chain(
+                self.functions
+                    .iter()
+                    .map(|function_type| (&function_type.namespace, &function_type.name))
+                    .enumerate()
+                    .map(named(Type::Function)),
+            )
+    }
+
+    pub fn try_resolve_type(
+        &self,
+        name: &str,
+        namespace: &str,
+    ) -> Result<Option<(Type, usize)>, Error> {
+        resolve_name(self.named_types(), name, namespace)
+    }
+
+    pub fn type_size(&self, r#type: Type, index: usize) -> Option<usize> {
+        self.try_type_size(r#type, index).ok()
+    }
+
+    pub fn try_type_size(&self, r#type: Type, index: usize) -> Result<usize, Error> {
+        if !self.contains_type(r#type, index) {
+            return Err(Error::IndexOutOfRange { r#type, index });
+        }
+
+        match r#type {
+            Type::Primitive => Ok(self.target.primitive(index).unwrap().size),
+            Type::Data => self.types[index].try_size(self),
+            Type::Enum => self.enums[index].try_size(self),
+            Type::Flags => self.flags[index].try_size(self),
+            Type::Alias => self.aliases[index].try_size(self),
+            Type::Union => self.unions[index].try_size(self),
+            Type::Array => self.arrays[index].try_size(self),
+            Type::Vector | Type::String => Ok(VectorType::size()),
+            Type::Map => Ok(MapType::size()),
+            Type::Slice => Ok(SliceType::size()),
+            Type::Pointer => Ok(PointerType::size(&self.target)),
+            Type::Function => Ok(FunctionType::size(&self.target)),
+            Type::Optional => self.optionals[index].try_size(self),
+            Type::InlineString => Ok(self.inline_strings[index].size()),
+            Type::InlineVector => self.inline_vectors[index].try_size(self),
         }
     }
 
     pub fn is_copy(&self, r#type: Type, index: usize) -> Option<bool> {
+        self.try_is_copy(r#type, index).ok()
+    }
+
+    pub fn try_is_copy(&self, r#type: Type, index: usize) -> Result<bool, Error> {
+        if !self.contains_type(r#type, index) {
+            return Err(Error::IndexOutOfRange { r#type, index });
+        }
+
         match r#type {
-            Type::Primitive => Some(true),
-            Type::Data => self
-                .types
-                .get(index)
-                .map(|data_type| data_type.is_copy(self)),
-            Type::Enum => Some(true),
-            Type::Union => self
-                .unions
-                .get(index)
-                .map(|union_type| union_type.is_copy(self)),
-            Type::Array => Some(true),
-            Type::Vector | Type::String => Some(false),
-            Type::Pointer => Some(true),
-            Type::Function => Some(true),
+            Type::Primitive => Ok(true),
+            Type::Data => self.types[index].try_is_copy(self),
+            Type::Enum | Type::Flags => Ok(true),
+            Type::Union => Ok(self.unions[index].is_copy(self)),
+            Type::Array => {
+                let array_type = self.arrays[index];
+                self.try_is_copy(array_type.inner_type, array_type.inner_type_index)
+            }
+            Type::Vector | Type::String => Ok(false),
+            Type::Map => Ok(MapType::is_copy()),
+            Type::Slice => Ok(SliceType::is_copy()),
+            Type::Pointer => Ok(true),
+            Type::Function => Ok(true),
+            Type::Optional => {
+                let optional_type = self.optionals[index];
+                self.try_is_copy(optional_type.inner_type, optional_type.inner_type_index)
+            }
+            Type::InlineString => Ok(true),
+            Type::Alias => {
+                let alias_type = &self.aliases[index];
+                self.try_is_copy(alias_type.inner_type, alias_type.inner_type_index)
+            }
+            Type::InlineVector => {
+                let inline_vector_type = self.inline_vectors[index];
+                self.try_is_copy(
+                    inline_vector_type.inner_type,
+                    inline_vector_type.inner_type_index,
+                )
+            }
         }
     }
 
     pub fn type_align(&self, r#type: Type, index: usize) -> Option<usize> {
+        self.try_type_align(r#type, index).ok()
+    }
+
+    pub fn try_type_align(&self, r#type: Type, index: usize) -> Result<usize, Error> {
+        if !self.contains_type(r#type, index) {
+            return Err(Error::IndexOutOfRange { r#type, index });
+        }
+
         match r#type {
-            Type::Primitive => PRIMITIVES.index(index).map(|primitive| primitive.align),
-            Type::Data => self.types.get(index).map(|data_type| data_type.align(self)),
-            Type::Enum => Some(EnumType::align()),
+            Type::Primitive => Ok(self.target.primitive(index).unwrap().align),
+            Type::Data => self.types[index].try_align(self),
+            Type::Enum => self.enums[index].try_align(self),
+            Type::Flags => self.flags[index].try_align(self),
+            Type::Alias => self.aliases[index].try_align(self),
+            Type::Union => self.unions[index].try_align(self),
+            Type::Array => self.arrays[index].try_align(self),
+            Type::Vector | Type::String | Type::Map => Ok(Layout::from_size_align(
+                size_of::<u64>() * 3,
+                size_of::<u64>(),
+            )
+            .unwrap()
+            .align()),
+            Type::Pointer | Type::Function => Ok(self.target.pointer_width),
+            Type::Optional => self.optionals[index].try_align(self),
+            Type::Slice => Ok(size_of::<u64>()),
+            Type::InlineString => Ok(InlineStringType::align()),
+            Type::InlineVector => self.inline_vectors[index].try_align(self),
+        }
+    }
+
+    pub fn contains_type(&self, r#type: Type, index: usize) -> bool {
+        match r#type {
+            Type::Primitive => PRIMITIVES.index(index).is_some(),
+            Type::Data => index < self.types.len(),
+            Type::Enum => index < self.enums.len(),
+            Type::Flags => index < self.flags.len(),
+            Type::Alias => index < self.aliases.len(),
+            Type::Union => index < self.unions.len(),
+            Type::Array => index < self.arrays.len(),
+            Type::Vector => index < self.vectors.len(),
+            Type::Pointer => index < self.pointers.len(),
+            Type::String => true,
+            Type::Function => index < self.functions.len(),
+            Type::Optional => index < self.optionals.len(),
+            Type::Map => index < self.maps.len(),
+            Type::Slice => index < self.slices.len(),
+            Type::InlineString => index < self.inline_strings.len(),
+            Type::InlineVector => index < self.inline_vectors.len(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        validation::validate(self)
+    }
+
+    pub fn compare(&self, new_schema: &Self) -> Result<Vec<Change>, Error> {
+        compatibility::compare(self, new_schema)
+    }
+
+    pub fn fingerprint(&self, r#type: Type, index: usize) -> u64 {
+        self.try_fingerprint(r#type, index)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_fingerprint(&self, r#type: Type, index: usize) -> Result<u64, Error> {
+        fingerprint::fingerprint(self, r#type, index)
+    }
+
+    pub fn update_fingerprints(&mut self) -> Result<(), Error> {
+        for index in 0..self.types.len() {
+            self.types[index].fingerprint = self.try_fingerprint(Type::Data, index)?;
+        }
+        for index in 0..self.enums.len() {
+            self.enums[index].fingerprint = self.try_fingerprint(Type::Enum, index)?;
+        }
+        for index in 0..self.unions.len() {
+            self.unions[index].fingerprint = self.try_fingerprint(Type::Union, index)?;
+        }
+        for index in 0..self.flags.len() {
+            self.flags[index].fingerprint = self.try_fingerprint(Type::Flags, index)?;
+        }
+        for index in 0..self.aliases.len() {
+            self.aliases[index].fingerprint = self.try_fingerprint(Type::Alias, index)?;
+        }
+        for index in 0..self.functions.len() {
+            self.functions[index].fingerprint = self.try_fingerprint(Type::Function, index)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn type_id(&self, r#type: Type, index: usize) -> String {
+        let attributes = match r#type {
+            Type::Data => self.types.get(index).map(|data_type| &data_type.attributes),
+            Type::Enum => self.enums.get(index).map(|enum_type| &enum_type.attributes),
             Type::Union => self
                 .unions
                 .get(index)
-                .map(|union_type| union_type.align(self)),
-            Type::Array => self
-                .arrays
+                .map(|union_type| &union_type.attributes),
+            Type::Flags => self
+                .flags
                 .get(index)
-                .map(|array_type| array_type.align(self)),
-            Type::Vector | Type::String => Some(
-                Layout::from_size_align(size_of::<u64>() * 3, size_of::<u64>())
-                    .unwrap()
-                    .align(),
-            ),
-            Type::Pointer => Some(align_of::<u64>()),
-            Type::Function => Some(align_of::<u64>()),
-        }
+                .map(|flags_type| &flags_type.attributes),
+            Type::Alias => self
+                .aliases
+                .get(index)
+                .map(|alias_type| &alias_type.attributes),
+            _ => None,
+        };
+
+        attributes
+            .and_then(|attributes| {
+                attributes
+                    .iter()
+                    .find(|(name, _)| name == "type_id")
+                    .map(|(_, value)| value.clone())
+            })
+            .unwrap_or_else(|| format!("{:016x}", self.fingerprint(r#type, index)))
     }
 
     pub fn wrapped_type_index(&self, wrapped_type: &WrappedType) -> Option<usize> {
@@ -164,23 +610,31 @@ impl Schema {
                 .arrays
                 .iter()
                 .position(|schema_array_type| schema_array_type == array_type),
-            WrappedType::Data(data_type) => self
-                .types
-                .iter()
-                .position(|schema_data_type| schema_data_type.name == data_type.name),
-            WrappedType::Enum(enum_type) => self
-                .enums
-                .iter()
-                .position(|schema_enum_type| schema_enum_type.name == enum_type.name),
+            WrappedType::Data(data_type) => self.types.iter().position(|schema_data_type| {
+                schema_data_type.name == data_type.name
+                    && schema_data_type.namespace == data_type.namespace
+            }),
+            WrappedType::Enum(enum_type) => self.enums.iter().position(|schema_enum_type| {
+                schema_enum_type.name == enum_type.name
+                    && schema_enum_type.namespace == enum_type.namespace
+            }),
             WrappedType::Pointer(pointer_type) => self
                 .pointers
                 .iter()
                 .position(|schema_pointer_type| schema_pointer_type == pointer_type),
             WrappedType::Primitive(primitive_type) => PRIMITIVES.index_of(primitive_type.name),
-            WrappedType::Union(union_type) => self
-                .unions
-                .iter()
-                .position(|schema_union_type| schema_union_type.name == union_type.name),
+            WrappedType::Union(union_type) => self.unions.iter().position(|schema_union_type| {
+                schema_union_type.name == union_type.name
+                    && schema_union_type.namespace == union_type.namespace
+            }),
+            WrappedType::Flags(flags_type) => self.flags.iter().position(|schema_flags_type| {
+                schema_flags_type.name == flags_type.name
+                    && schema_flags_type.namespace == flags_type.namespace
+            }),
+            WrappedType::Alias(alias_type) => self.aliases.iter().position(|schema_alias_type| {
+                schema_alias_type.name == alias_type.name
+                    && schema_alias_type.namespace == alias_type.namespace
+            }),
             WrappedType::Vector(vector_type) => self
                 .vectors
                 .iter()
@@ -190,96 +644,43 @@ impl Schema {
                 .functions
                 .iter()
                 .position(|schema_function_type| schema_function_type == function_type),
+            WrappedType::Optional(optional_type) => self
+                .optionals
+                .iter()
+                .position(|schema_optional_type| schema_optional_type == optional_type),
+            WrappedType::Map(map_type) => self
+                .maps
+                .iter()
+                .position(|schema_map_type| schema_map_type == map_type),
+            WrappedType::Slice(slice_type) => self
+                .slices
+                .iter()
+                .position(|schema_slice_type| schema_slice_type == slice_type),
+            WrappedType::InlineString(inline_string_type) => {
+                self.inline_strings
+                    .iter()
+                    .position(|schema_inline_string_type| {
+                        schema_inline_string_type == inline_string_type
+                    })
+            }
+            WrappedType::InlineVector(inline_vector_type) => {
+                self.inline_vectors
+                    .iter()
+                    .position(|schema_inline_vector_type| {
+                        schema_inline_vector_type == inline_vector_type
+                    })
+            }
         }
     }
 
-    pub fn type_data(&self, index: usize, r#type: Type) -> Result<TypeData, String> {
-        match r#type {
-            Type::Primitive => {
-                let primitive = PRIMITIVES
-                    .index(index)
-                    .ok_or(format!("No primitive with index {index}"))?;
+    pub fn type_data(&self, index: usize, r#type: Type) -> Result<TypeData, Error> {
+        let size = self.try_type_size(r#type, index)?;
+        let is_copy = self.try_is_copy(r#type, index)?;
 
-                Ok(TypeData::new(index, r#type, primitive.size, true))
-            }
-            Type::Data => {
-                let data_type = self
-                    .types
-                    .get(index)
-                    .ok_or(format!("No data type with index {index}"))?;
-
-                Ok(TypeData::new(
-                    index,
-                    r#type,
-                    data_type.size(self),
-                    data_type.is_copy(self),
-                ))
-            }
-            Type::Enum => {
-                if self.enums.get(index).is_none() {
-                    Err(format!("No enum with index {index}"))
-                } else {
-                    Ok(TypeData::new(index, r#type, EnumType::size(), true))
-                }
-            }
-            Type::Union => {
-                let union_type = self
-                    .types
-                    .get(index)
-                    .ok_or(format!("No union type with index {index}"))?;
-
-                Ok(TypeData::new(
-                    index,
-                    r#type,
-                    union_type.size(self),
-                    union_type.is_copy(self),
-                ))
-            }
-            Type::Array => {
-                let array_type = self
-                    .arrays
-                    .get(index)
-                    .ok_or(format!("No array type with index {index}"))?;
-
-                Ok(TypeData::new(
-                    index,
-                    r#type,
-                    array_type.size(self),
-                    self.is_copy(array_type.inner_type, array_type.inner_type_index)
-                        .unwrap(),
-                ))
-            }
-            Type::Vector => {
-                self.vectors
-                    .get(index)
-                    .ok_or(format!("No vector type with index {index}"))?;
-
-                Ok(TypeData::new(
-                    index,
-                    r#type,
-                    VectorType::size(),
-                    VectorType::is_copy(),
-                ))
-            }
-            Type::Pointer => {
-                if self.pointers.get(index).is_none() {
-                    Err(format!("No pointer type with index {index}"))
-                } else {
-                    Ok(TypeData::new(index, r#type, PointerType::size(), true))
-                }
-            }
-            Type::String => Ok(TypeData::new(0, r#type, VectorType::size(), false)),
-            Type::Function => {
-                if self.functions.get(index).is_none() {
-                    Err(format!("No function type with index {index}"))
-                } else {
-                    Ok(TypeData::new(index, r#type, FunctionType::size(), true))
-                }
-            }
-        }
+        Ok(TypeData::new(index, r#type, size, is_copy))
     }
 
-    pub fn type_data_by_name(&mut self, name: &str) -> Result<TypeData, String> {
+    pub fn type_data_by_name(&mut self, name: &str) -> Result<TypeData, Error> {
         if name.starts_with('[') && name.ends_with(']') {
             let looked_up_array = ArrayType::parse(name, self)?;
 
@@ -295,6 +696,69 @@ impl Schema {
             return self.type_data(array_index, Type::Array);
         }
 
+        if name.starts_with('{') && name.ends_with('}') {
+            let looked_up_map = MapType::parse(name, self)?;
+
+            let map_index = self
+                .maps
+                .iter()
+                .position(|map| map == &looked_up_map)
+                .unwrap_or_else(|| {
+                    self.maps.push(looked_up_map);
+                    self.maps.len() - 1
+                });
+
+            return self.type_data(map_index, Type::Map);
+        }
+
+        if name.starts_with("&<") && name.ends_with('>') {
+            let looked_up_slice = SliceType::parse(name, self)?;
+
+            let slice_index = self
+                .slices
+                .iter()
+                .position(|slice| slice == &looked_up_slice)
+                .unwrap_or_else(|| {
+                    self.slices.push(looked_up_slice);
+                    self.slices.len() - 1
+                });
+
+            return self.type_data(slice_index, Type::Slice);
+        }
+
+        if name.starts_with("String<") && name.ends_with('>') {
+            let looked_up_inline_string = InlineStringType::parse(name)?;
+
+            let inline_string_index = self
+                .inline_strings
+                .iter()
+                .position(|inline_string| inline_string == &looked_up_inline_string)
+                .unwrap_or_else(|| {
+                    self.inline_strings.push(looked_up_inline_string);
+                    self.inline_strings.len() - 1
+                });
+
+            return self.type_data(inline_string_index, Type::InlineString);
+        }
+
+        if name.starts_with('<')
+            && name.ends_with('>')
+            && inline::split_capacity(&name[1..name.len() - 1]).is_some()
+        {
+            let looked_up_inline_vector = InlineVectorType::parse(name, self)?;
+
+            let inline_vector_index = self
+                .inline_vectors
+                .iter()
+                .position(|inline_vector| inline_vector == &looked_up_inline_vector)
+                .unwrap_or_else(|| {
+                    self.inline_vectors.push(looked_up_inline_vector);
+                    self.inline_vectors.len() - 1
+                });
+
+            return self.type_data(inline_vector_index, Type::InlineVector);
+        }
+
         if name.starts_with('<') && name.ends_with('>') {
             let looked_up_vec = VectorType::parse(name, self)?;
 
@@ -310,6 +774,21 @@ impl Schema {
             return self.type_data(vector_index, Type::Vector);
         }
 
+        if name.ends_with('?') {
+            let looked_up_optional = OptionalType::parse(name, self)?;
+
+            let optional_index = self
+                .optionals
+                .iter()
+                .position(|optional| optional == &looked_up_optional)
+                .unwrap_or_else(|| {
+                    self.optionals.push(looked_up_optional);
+                    self.optionals.len() - 1
+                });
+
+            return self.type_data(optional_index, Type::Optional);
+        }
+
         if name.ends_with('*') {
             let inner_type_name = &name[..name.len() - 1];
             let inner_type_data = self.type_data_by_name(inner_type_name)?;
@@ -339,77 +818,36 @@ impl Schema {
             return self.type_data(index, Type::Primitive);
         }
 
-        if let Some((index, _)) = self
-            .types
-            .iter()
-            .enumerate()
-            .find(|(_, data_type)| data_type.name == name)
-        {
-            return self.type_data(index, Type::Data);
-        }
-
-        if let Some(index) = self
-            .enums
-            .iter()
-            .enumerate()
-            .find(|(_, enum_type)| enum_type.name == *name)
-            .map(|(index, _)| index)
-        {
-            return self.type_data(index, Type::Enum);
-        }
-
-        if let Some(index) = self
-            .unions
-            .iter()
-            .enumerate()
-            .find(|(_, union_type)| union_type.name == *name)
-            .map(|(index, _)| index)
-        {
-            return self.type_data(index, Type::Union);
-        }
-
-        if let Some(index) = self
-            .functions
-            .iter()
-            .enumerate()
-            .find(|(_, function_type)| function_type.name == *name)
-            .map(|(index, _)| index)
-        {
-            return self.type_data(index, Type::Function);
+        if let Some((r#type, index)) = self.try_resolve_type(name, "")? {
+            return self.type_data(index, r#type);
         }
 
-        let available_type_names = self
-            .types
-            .iter()
-            .map(|data_type| data_type.name.clone())
-            .collect::<Vec<_>>();
-        let available_enum_names = self
-            .enums
-            .iter()
-            .map(|enum_type| enum_type.name.clone())
-            .collect::<Vec<_>>();
-        let available_union_names = self
-            .unions
-            .iter()
-            .map(|union_type| union_type.name.clone())
-            .collect::<Vec<_>>();
-        let available_function_names = self
-            .functions
-            .iter()
-            .map(|function_type| function_type.name.clone())
-            .collect::<Vec<_>>();
+        Err(Error::UnknownType {
+            name: name.to_string(),
+            suggestions: self.similar_type_names(name),
+        })
+    }
 
-        Err(format!("Failed to find type with name {name:?}!\n\tAvailable types: {available_type_names:?}\n\tAvailable enums: {available_enum_names:?}\n\tAvailable unions: {available_union_names:?}\n\tAvailable functions: {available_function_names:?}"))
+    pub fn type_names(&self) -> Vec<String> {
+        self.named_types()
+            .map(|(namespace, name, _)| qualified_name(namespace, name))
+            .collect()
     }
 
-    pub fn append(&mut self, schema: &mut Self) {
-        self.is_packed |= schema.is_packed;
+    pub fn similar_type_names(&self, name: &str) -> Vec<String> {
+        let builtin_names = PRIMITIVES
+            .names()
+            .into_iter()
+            .chain(["String"])
+            .map(|name| ("", name));
+        let type_names = self
+            .named_types()
+            .map(|(namespace, name, _)| (namespace, name));
+
+        similar_names(builtin_names.chain(type_names), name)
+    }
 
-        self.types.append(&mut schema.types);
-        self.enums.append(&mut schema.enums);
-        self.unions.append(&mut schema.unions);
-        self.arrays.append(&mut schema.arrays);
-        self.pointers.append(&mut schema.pointers);
-        self.vectors.append(&mut schema.vectors);
+    pub fn merge(&mut self, other: &Self) -> Result<(), Error> {
+        merge::merge(self, other)
     }
 }
@@ -1,13 +1,21 @@
 use std::{
+    cmp::Ordering,
     fmt::{self, Debug},
     marker::PhantomData,
-    mem::ManuallyDrop,
+    mem::{ManuallyDrop, MaybeUninit},
     str::Utf8Error,
 };
 
 use crate::{
     schema::Schema,
-    types::{vector::VectorType, WrappedType},
+    types::{
+        inline::{InlineStringType, InlineVectorType},
+        map::MapType,
+        optional::OptionalType,
+        slice::SliceType,
+        vector::VectorType,
+        WrappedType,
+    },
     Binterop,
 };
 
@@ -127,7 +135,7 @@ impl<T> Vector<T> {
         *self = vec.into();
     }
 
-    pub fn pop(&mut self) -> Option<T> {
+    pub fn pop(&mut self) -> std::option::Option<T> {
         let mut vec = unsafe {
             Vec::from_raw_parts(self.ptr as _, self.length as usize, self.capacity as usize)
         };
@@ -139,6 +147,88 @@ impl<T> Vector<T> {
     }
 }
 
+/// Borrows the elements it points to, so it can't outlive them:
+///
+/// ```compile_fail,E0505
+/// let elements = vec![1u16, 2, 3];
+/// let slice = binterop::std::Slice::from(elements.as_slice());
+/// drop(elements);
+/// slice.as_slice();
+/// ```
+#[repr(C)]
+pub struct Slice<'a, T> {
+    ptr: u64,
+    length: u64,
+    _p: PhantomData<&'a [T]>,
+}
+impl<T: Binterop> Binterop for Slice<'_, T> {
+    fn binterop_type(schema: &mut Schema) -> WrappedType {
+        let inner_type = T::binterop_type(schema);
+        let inner_type_index = schema
+            .wrapped_type_index(&inner_type)
+            .expect("Provided schema doesnt contain this type!");
+
+        let slice_type = SliceType::new(inner_type.r#type(), inner_type_index);
+        schema.slices.push(slice_type);
+
+        WrappedType::Slice(slice_type)
+    }
+}
+impl<T: Debug> Debug for Slice<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+impl<T> Clone for Slice<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for Slice<'_, T> {}
+impl<'a, T> From<&'a [T]> for Slice<'a, T> {
+    fn from(value: &'a [T]) -> Self {
+        Self {
+            ptr: value.as_ptr().addr() as u64,
+            length: value.len() as u64,
+            _p: PhantomData,
+        }
+    }
+}
+impl<'a, T> From<&'a Vector<T>> for Slice<'a, T> {
+    fn from(value: &'a Vector<T>) -> Self {
+        value.as_slice().into()
+    }
+}
+impl<'a, T> Slice<'a, T> {
+    /// # Safety
+    ///
+    /// `ptr` must point to `length` initialized elements that stay alive and unmodified for `'a`,
+    /// same as for `std::slice::from_raw_parts`.
+    pub unsafe fn from_raw_parts(ptr: u64, length: u64) -> Self {
+        Self {
+            ptr,
+            length,
+            _p: PhantomData,
+        }
+    }
+
+    pub fn ptr(&self) -> u64 {
+        self.ptr
+    }
+
+    pub fn len(&self) -> usize {
+        self.length as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn as_slice(&self) -> &'a [T] {
+        unsafe { std::slice::from_raw_parts(self.ptr as _, self.length as usize) }
+    }
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct String(pub Vector<u8>);
@@ -147,6 +237,22 @@ impl Debug for String {
         write!(f, "{:?}", self.as_str().unwrap_or("Invalid UTF-8"))
     }
 }
+impl PartialEq for String {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_slice() == other.0.as_slice()
+    }
+}
+impl Eq for String {}
+impl PartialOrd for String {
+    fn partial_cmp(&self, other: &Self) -> std::option::Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for String {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.as_slice().cmp(other.0.as_slice())
+    }
+}
 impl String {
     pub fn as_str(&self) -> Result<&str, Utf8Error> {
         std::str::from_utf8(self.0.as_slice())
@@ -176,3 +282,392 @@ impl From<std::string::String> for String {
         Self(vec.into())
     }
 }
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct InlineString<const N: usize> {
+    pub length: u32,
+    pub bytes: [u8; N],
+}
+impl<const N: usize> Binterop for InlineString<N> {
+    fn binterop_type(schema: &mut Schema) -> WrappedType {
+        let inline_string_type = InlineStringType::new(N);
+        schema.inline_strings.push(inline_string_type);
+
+        WrappedType::InlineString(inline_string_type)
+    }
+}
+impl<const N: usize> Debug for InlineString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_str().unwrap_or("Invalid UTF-8"))
+    }
+}
+impl<const N: usize> Default for InlineString<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<const N: usize> TryFrom<&str> for InlineString<N> {
+    type Error = std::string::String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut string = Self::new();
+        string.push_str(value)?;
+
+        Ok(string)
+    }
+}
+impl<const N: usize> InlineString<N> {
+    pub fn new() -> Self {
+        Self {
+            length: 0,
+            bytes: [0; N],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..(self.length as usize).min(N)]
+    }
+
+    pub fn as_str(&self) -> Result<&str, Utf8Error> {
+        std::str::from_utf8(self.as_bytes())
+    }
+
+    pub fn push_str(&mut self, s: &str) -> Result<(), std::string::String> {
+        let length = self.len();
+        if length + s.len() > N {
+            return Err(format!(
+                "String of length {} doesn't fit into String<{N}> with {length} bytes used",
+                s.len()
+            ));
+        }
+
+        self.bytes[length..length + s.len()].copy_from_slice(s.as_bytes());
+        self.length = (length + s.len()) as u32;
+
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        self.length = 0;
+    }
+}
+
+#[repr(C)]
+pub struct InlineVector<T, const N: usize> {
+    pub length: u32,
+    pub items: [MaybeUninit<T>; N],
+}
+impl<T: Binterop, const N: usize> Binterop for InlineVector<T, N> {
+    fn binterop_type(schema: &mut Schema) -> WrappedType {
+        let inner_type = T::binterop_type(schema);
+        let inner_type_index = schema
+            .wrapped_type_index(&inner_type)
+            .expect("Provided schema doesnt contain this type!");
+
+        let inline_vector_type = InlineVectorType::new(inner_type.r#type(), inner_type_index, N);
+        schema.inline_vectors.push(inline_vector_type);
+
+        WrappedType::InlineVector(inline_vector_type)
+    }
+}
+impl<T: Debug, const N: usize> Debug for InlineVector<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+impl<T: Clone, const N: usize> Clone for InlineVector<T, N> {
+    fn clone(&self) -> Self {
+        let mut vector = Self::new();
+        for item in self.as_slice() {
+            let _ = vector.push(item.clone());
+        }
+
+        vector
+    }
+}
+impl<T: Copy, const N: usize> Copy for InlineVector<T, N> {}
+impl<T, const N: usize> Default for InlineVector<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T, const N: usize> InlineVector<T, N> {
+    pub fn new() -> Self {
+        Self {
+            length: 0,
+            items: [const { MaybeUninit::uninit() }; N],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        (self.length as usize).min(N)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.items.as_ptr() as _, self.len()) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.items.as_mut_ptr() as _, self.len()) }
+    }
+
+    pub fn push(&mut self, elem: T) -> Result<(), T> {
+        let length = self.len();
+        if length == N {
+            return Err(elem);
+        }
+
+        self.items[length] = MaybeUninit::new(elem);
+        self.length += 1;
+
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> std::option::Option<T> {
+        let length = self.len();
+        if length == 0 {
+            return None;
+        }
+
+        self.length -= 1;
+
+        Some(unsafe { self.items[length - 1].assume_init_read() })
+    }
+}
+
+#[repr(C)]
+pub struct Option<T> {
+    pub is_some: bool,
+    pub value: MaybeUninit<T>,
+}
+impl<T: Binterop> Binterop for Option<T> {
+    fn binterop_type(schema: &mut Schema) -> WrappedType {
+        let inner_type = T::binterop_type(schema);
+        let inner_type_index = schema
+            .wrapped_type_index(&inner_type)
+            .expect("Provided schema doesnt contain this type!");
+
+        let optional_type = OptionalType::new(inner_type.r#type(), inner_type_index);
+        schema.optionals.push(optional_type);
+
+        WrappedType::Optional(optional_type)
+    }
+}
+impl<T: Debug> Debug for Option<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_ref())
+    }
+}
+impl<T: Clone> Clone for Option<T> {
+    fn clone(&self) -> Self {
+        self.as_ref().cloned().into()
+    }
+}
+impl<T: Copy> Copy for Option<T> {}
+impl<T> Default for Option<T> {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+impl<T> From<std::option::Option<T>> for Option<T> {
+    fn from(value: std::option::Option<T>) -> Self {
+        match value {
+            Some(value) => Self::some(value),
+            None => Self::none(),
+        }
+    }
+}
+impl<T> From<Option<T>> for std::option::Option<T> {
+    fn from(value: Option<T>) -> Self {
+        value.is_some.then(|| unsafe { value.value.assume_init() })
+    }
+}
+impl<T> Option<T> {
+    pub fn some(value: T) -> Self {
+        Self {
+            is_some: true,
+            value: MaybeUninit::new(value),
+        }
+    }
+
+    pub fn none() -> Self {
+        Self {
+            is_some: false,
+            value: MaybeUninit::uninit(),
+        }
+    }
+
+    pub fn is_some(&self) -> bool {
+        self.is_some
+    }
+
+    pub fn is_none(&self) -> bool {
+        !self.is_some
+    }
+
+    pub fn as_ref(&self) -> std::option::Option<&T> {
+        self.is_some
+            .then(|| unsafe { self.value.assume_init_ref() })
+    }
+
+    pub fn as_mut(&mut self) -> std::option::Option<&mut T> {
+        self.is_some
+            .then(|| unsafe { self.value.assume_init_mut() })
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Debug)]
+pub struct MapEntry<K, V> {
+    pub key: K,
+    pub value: V,
+}
+
+#[repr(C)]
+pub struct Map<K, V> {
+    pub entries: Vector<MapEntry<K, V>>,
+}
+impl<K: Binterop, V: Binterop> Binterop for Map<K, V> {
+    fn binterop_type(schema: &mut Schema) -> WrappedType {
+        let key_type = K::binterop_type(schema);
+        let key_type_index = schema
+            .wrapped_type_index(&key_type)
+            .expect("Provided schema doesnt contain this type!");
+        let value_type = V::binterop_type(schema);
+        let value_type_index = schema
+            .wrapped_type_index(&value_type)
+            .expect("Provided schema doesnt contain this type!");
+
+        let map_type = MapType::new(
+            key_type.r#type(),
+            key_type_index,
+            value_type.r#type(),
+            value_type_index,
+        );
+        schema.maps.push(map_type);
+
+        WrappedType::Map(map_type)
+    }
+}
+impl<K: Debug, V: Debug> Debug for Map<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|entry| (&entry.key, &entry.value)))
+            .finish()
+    }
+}
+impl<K: Clone, V: Clone> Clone for Map<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+        }
+    }
+}
+impl<K, V> Default for Map<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<K: Ord, V> From<Vec<(K, V)>> for Map<K, V> {
+    fn from(value: Vec<(K, V)>) -> Self {
+        let mut map = Self::new();
+        for (key, value) in value {
+            map.insert(key, value);
+        }
+
+        map
+    }
+}
+impl<K, V> Map<K, V> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vector::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.length as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.length == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &MapEntry<K, V>> {
+        self.entries.as_slice().iter()
+    }
+}
+impl<K: Ord, V> Map<K, V> {
+    fn search(&self, key: &K) -> Result<usize, usize> {
+        self.entries
+            .as_slice()
+            .binary_search_by(|entry| entry.key.cmp(key))
+    }
+
+    pub fn get(&self, key: &K) -> std::option::Option<&V> {
+        let index = self.search(key).ok()?;
+
+        Some(&self.entries.as_slice()[index].value)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> std::option::Option<&mut V> {
+        let index = self.search(key).ok()?;
+
+        Some(&mut self.entries.as_mut_slice()[index].value)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.search(key).is_ok()
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> std::option::Option<V> {
+        match self.search(&key) {
+            Ok(index) => Some(std::mem::replace(
+                &mut self.entries.as_mut_slice()[index].value,
+                value,
+            )),
+            Err(index) => {
+                let mut entries: Vec<_> =
+                    std::mem::replace(&mut self.entries, Vector::new()).into();
+                entries.insert(index, MapEntry { key, value });
+                self.entries = entries.into();
+
+                None
+            }
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct F16(pub u16);
+impl F16 {
+    pub fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    pub fn to_bits(self) -> u16 {
+        self.0
+    }
+}
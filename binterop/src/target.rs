@@ -0,0 +1,100 @@
+use crate::error::Error;
+use crate::types::primitives::{PrimitiveType, PRIMITIVES};
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TargetLayout {
+    pub name: String,
+    pub pointer_width: usize,
+    pub endianness: Endianness,
+    pub primitive_overrides: Vec<(String, usize, usize)>,
+}
+impl Default for TargetLayout {
+    fn default() -> Self {
+        Self::x86_64_linux()
+    }
+}
+impl TargetLayout {
+    pub fn new(
+        name: &str,
+        pointer_width: usize,
+        endianness: Endianness,
+        primitive_overrides: &[(&str, usize, usize)],
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            pointer_width,
+            endianness,
+            primitive_overrides: primitive_overrides
+                .iter()
+                .map(|&(name, size, align)| (name.to_string(), size, align))
+                .collect(),
+        }
+    }
+
+    // The primitive table already matches the C ABI of these targets, only pointer sized
+    // primitives differ between them and those follow the pointer width
+    pub fn x86_64_linux() -> Self {
+        Self::new("x86_64-linux", 8, Endianness::Little, &[])
+    }
+
+    pub fn aarch64_linux() -> Self {
+        Self::new("aarch64-linux", 8, Endianness::Little, &[])
+    }
+
+    pub fn wasm32() -> Self {
+        Self::new("wasm32", 4, Endianness::Little, &[])
+    }
+
+    pub fn powerpc64_linux() -> Self {
+        Self::new("powerpc64-linux", 8, Endianness::Big, &[])
+    }
+
+    pub fn builtin() -> Vec<Self> {
+        vec![
+            Self::x86_64_linux(),
+            Self::aarch64_linux(),
+            Self::wasm32(),
+            Self::powerpc64_linux(),
+        ]
+    }
+
+    pub fn by_name(name: &str) -> Result<Self, Error> {
+        let builtin = Self::builtin();
+
+        builtin
+            .iter()
+            .find(|target| target.name == name)
+            .cloned()
+            .ok_or_else(|| Error::UnknownTarget {
+                name: name.to_string(),
+                available: builtin.into_iter().map(|target| target.name).collect(),
+            })
+    }
+
+    pub fn primitive(&self, index: usize) -> Option<PrimitiveType> {
+        let mut primitive = PRIMITIVES.index(index)?;
+
+        if matches!(primitive.name, "usize" | "isize") {
+            primitive.size = self.pointer_width;
+            primitive.align = self.pointer_width;
+        }
+        if let Some(&(_, size, align)) = self
+            .primitive_overrides
+            .iter()
+            .find(|(name, _, _)| name == primitive.name)
+        {
+            primitive.size = size;
+            primitive.align = align;
+        }
+
+        Some(primitive)
+    }
+}
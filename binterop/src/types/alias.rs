@@ -0,0 +1,49 @@
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::types::Type;
+use serde::{Deserialize, Serialize};
+
+// Aliases are transparent names for their inner type, distinct ones are generated as newtypes
+// with the same layout
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AliasType {
+    pub name: String,
+    #[serde(default)]
+    pub namespace: String,
+    #[serde(default)]
+    pub doc: String,
+    pub inner_type: Type,
+    pub inner_type_index: usize,
+    pub is_distinct: bool,
+    pub attributes: Vec<(String, String)>,
+    #[serde(default)]
+    pub fingerprint: u64,
+}
+impl AliasType {
+    pub fn new(
+        name: &str,
+        inner_type: Type,
+        inner_type_index: usize,
+        is_distinct: bool,
+        attributes: &[(String, String)],
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            namespace: String::new(),
+            doc: String::new(),
+            inner_type,
+            inner_type_index,
+            is_distinct,
+            attributes: attributes.to_vec(),
+            fingerprint: 0,
+        }
+    }
+
+    pub fn try_size(&self, schema: &Schema) -> Result<usize, Error> {
+        schema.try_type_size(self.inner_type, self.inner_type_index)
+    }
+
+    pub fn try_align(&self, schema: &Schema) -> Result<usize, Error> {
+        schema.try_type_align(self.inner_type, self.inner_type_index)
+    }
+}
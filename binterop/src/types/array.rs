@@ -1,3 +1,4 @@
+use crate::error::Error;
 use crate::schema::Schema;
 use crate::types::Type;
 use serde::{Deserialize, Serialize};
@@ -17,33 +18,35 @@ impl ArrayType {
         }
     }
 
-    pub fn size(&self, schema: &Schema) -> usize {
-        let inner_type_size = schema
-            .type_size(self.inner_type, self.inner_type_index)
-            .expect("Provided schema does not contain inner type!");
+    pub fn try_size(&self, schema: &Schema) -> Result<usize, Error> {
+        let inner_type_size = schema.try_type_size(self.inner_type, self.inner_type_index)?;
 
-        inner_type_size * self.len
+        Ok(inner_type_size * self.len)
     }
 
-    pub fn align(&self, schema: &Schema) -> usize {
-        schema
-            .type_align(self.inner_type, self.inner_type_index)
-            .expect("Provided schema does not contain inner type!")
+    pub fn try_align(&self, schema: &Schema) -> Result<usize, Error> {
+        schema.try_type_align(self.inner_type, self.inner_type_index)
     }
 
-    pub fn parse(s: &str, schema: &mut Schema) -> Result<Self, String> {
+    pub fn parse(s: &str, schema: &mut Schema) -> Result<Self, Error> {
+        let parse_error = |reason: String| Error::ParseArray {
+            text: s.to_string(),
+            reason,
+        };
         let mut s_split = s[1..(s.len() - 1)].split(':');
 
         let inner_type_data = if let Some(type_name) = s_split.next() {
             schema.type_data_by_name(type_name)?
         } else {
-            return Err(format!("Failed to split type name from {s}"));
+            return Err(parse_error("missing type name".to_string()));
         };
 
         let len = if let Some(len_str) = s_split.next() {
-            len_str.parse::<usize>().map_err(|err| err.to_string())
+            len_str
+                .parse::<usize>()
+                .map_err(|err| parse_error(err.to_string()))
         } else {
-            Err(format!("Failed to split length from {s}"))
+            Err(parse_error("missing length".to_string()))
         }?;
 
         Ok(Self::new(
@@ -1,43 +1,68 @@
+use crate::attributes::{align_attribute, attribute, bool_attribute, invalid_attribute};
+use crate::error::Error;
 use crate::field::Field;
 use crate::schema::Schema;
 use crate::types::primitives::{PrimitiveType, PRIMITIVES};
 use crate::types::Type;
 use serde::{Deserialize, Serialize};
-use smallvec::SmallVec;
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct DataType {
     pub name: String,
+    #[serde(default)]
+    pub namespace: String,
+    #[serde(default)]
+    pub doc: String,
     pub fields: Vec<Field>,
     pub attributes: Vec<(String, String)>,
+    #[serde(default)]
+    pub fingerprint: u64,
 }
 impl DataType {
     pub fn new(schema: &Schema, name: &str, field_data: &[(&str, Type, usize)], attributes: &[(String, String)]) -> Self {
+        Self::try_new(schema, name, field_data, attributes).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_new(
+        schema: &Schema,
+        name: &str,
+        field_data: &[(&str, Type, usize)],
+        attributes: &[(String, String)],
+    ) -> Result<Self, Error> {
+        let mut current_offset = 0;
         let fields = field_data
             .iter()
             .copied()
-            .enumerate()
-            .map(|(index, (name, r#type, type_index))| {
-                let type_size = schema.type_size(r#type, index).unwrap_or_else(|| {
-                    panic!("Provided schema does not contain type {type:?} with index {index}!")
-                });
+            .map(|(field_name, r#type, type_index)| {
+                let type_size = schema
+                    .try_type_size(r#type, type_index)
+                    .map_err(|err| err.in_member(name, field_name))?;
+
+                let field = Field::new(field_name, r#type, type_index, current_offset, 0, &[]);
+                current_offset += type_size;
 
-                Field::new(name, r#type, type_index, index * type_size, 0, &[])
+                Ok(field)
             })
-            .collect();
+            .collect::<Result<_, Error>>()?;
 
-        Self {
+        Ok(Self {
             name: name.to_string(),
+            namespace: String::new(),
+            doc: String::new(),
             fields,
             attributes: attributes.to_vec(),
-        }
+            fingerprint: 0,
+        })
     }
 
     pub fn default_with_name(name: &str) -> Self {
         Self {
             name: name.to_string(),
+            namespace: String::new(),
+            doc: String::new(),
             fields: Vec::new(),
             attributes: Vec::new(),
+            fingerprint: 0,
         }
     }
 
@@ -47,7 +72,7 @@ impl DataType {
             .iter()
             .map(|(name, r#type)| {
                 let primitive_index = PRIMITIVES.index_of(r#type.name).unwrap();
-                let primitive_size = PRIMITIVES[name].size;
+                let primitive_size = r#type.size;
 
                 let field = Field::new(name, Type::Primitive, primitive_index, previous_offset, 0, &[]);
 
@@ -59,78 +84,121 @@ impl DataType {
 
         Self {
             name: name.to_string(),
+            namespace: String::new(),
+            doc: String::new(),
             fields,
             attributes: Vec::new(),
+            fingerprint: 0,
         }
     }
 
     pub fn from_fields(name: &str, fields: &[Field]) -> Self {
         Self {
             name: name.to_string(),
+            namespace: String::new(),
+            doc: String::new(),
             fields: fields.to_vec(),
             attributes: Vec::new(),
+            fingerprint: 0,
         }
     }
 
-    pub fn size(&self, schema: &Schema) -> usize {
-        let self_indices = self
-            .fields
-            .iter()
-            .enumerate()
-            .filter_map(|(index, field)| {
-                if field.type_name(schema) == self.name {
-                    Some(index)
-                } else {
-                    None
-                }
-            })
-            .collect::<SmallVec<[usize; 32]>>();
+    pub fn try_size(&self, schema: &Schema) -> Result<usize, Error> {
+        let mut fields_end = 0;
+        for field in &self.fields {
+            fields_end = fields_end.max(field.offset + field.try_size(schema)?);
+        }
 
-        let self_size: usize = self
-            .fields
-            .iter()
-            .enumerate()
-            .filter_map(|(index, field)| {
-                if !self_indices.contains(&index) {
-                    Some(field.size(schema))
-                } else {
-                    None
-                }
-            })
-            .sum();
+        if schema.is_packed || self.try_is_packed()? {
+            Ok(fields_end)
+        } else {
+            Ok(fields_end.next_multiple_of(self.try_align(schema)?))
+        }
+    }
+
+    pub fn try_align(&self, schema: &Schema) -> Result<usize, Error> {
+        if self.try_is_packed()? {
+            return Ok(1);
+        }
+
+        let mut align = self.try_align_attribute()?.unwrap_or(1);
+        for field in &self.fields {
+            align = align.max(field.try_align(schema)?);
+        }
 
-        self_size + (self_indices.len() * self_size)
+        Ok(align)
     }
 
-    pub fn align(&self, schema: &Schema) -> usize {
-        let self_indices = self
-            .fields
-            .iter()
-            .enumerate()
-            .filter_map(|(index, field)| {
-                if field.type_name(schema) == self.name {
-                    Some(index)
-                } else {
-                    None
-                }
-            })
-            .collect::<SmallVec<[usize; 32]>>();
+    pub fn try_is_packed(&self) -> Result<bool, Error> {
+        bool_attribute(&self.attributes, "packed")
+    }
 
-        self.fields
-            .iter()
-            .enumerate()
-            .filter_map(|(index, field)| {
-                if !self_indices.contains(&index) {
-                    Some(field.align(schema))
-                } else {
-                    None
+    pub fn try_align_attribute(&self) -> Result<Option<usize>, Error> {
+        align_attribute(&self.attributes, "align")
+    }
+
+    // Explicit field offsets pin the fields to their declaration order
+    pub fn try_keeps_order(&self) -> Result<bool, Error> {
+        if bool_attribute(&self.attributes, "no_reorder")? {
+            return Ok(true);
+        }
+
+        for field in &self.fields {
+            if field.try_offset_attribute()?.is_some() {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    pub fn try_check_attributes(&self) -> Result<(), Error> {
+        let in_type = |err: Error| err.in_type(&self.name);
+
+        if let Some(value) = attribute(&self.attributes, "offset") {
+            return Err(in_type(invalid_attribute("offset", value, "only applies to fields")));
+        }
+
+        let is_packed = self.try_is_packed().map_err(in_type)?;
+        bool_attribute(&self.attributes, "no_reorder").map_err(in_type)?;
+        if is_packed && self.try_align_attribute().map_err(in_type)?.is_some() {
+            return Err(in_type(Error::ConflictingAttributes {
+                first: "packed".to_string(),
+                second: "align".to_string(),
+            }));
+        }
+
+        for field in &self.fields {
+            let in_field = |err: Error| err.in_member(&self.name, &field.name);
+
+            field.try_check_attributes().map_err(in_field)?;
+            if is_packed && field.try_align_attribute()?.is_some() {
+                return Err(in_field(Error::ConflictingAttributes {
+                    first: "packed".to_string(),
+                    second: "align".to_string(),
+                }));
+            }
+            if let Some(offset) = field.try_offset_attribute()? {
+                if field.shares_storage() {
+                    return Err(in_field(invalid_attribute(
+                        "offset",
+                        &offset.to_string(),
+                        "bitfields sharing a storage unit can't be placed on their own",
+                    )));
                 }
-            })
-            .max()
-            .unwrap_or(1)
+            }
+        }
+
+        Ok(())
     }
 
-    pub fn is_copy(&self, schema: &Schema) -> bool {
-        self.fields.iter().all(|field| field.is_copy(schema))
+    pub fn try_is_copy(&self, schema: &Schema) -> Result<bool, Error> {
+        for field in &self.fields {
+            if !field.try_is_copy(schema)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
     }
 }
@@ -1,18 +1,54 @@
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::types::primitives::{INTEGER_PRIMITIVE_NAMES, PRIMITIVES};
+use crate::types::Type;
 use serde::{Deserialize, Serialize};
-use std::mem::{align_of, size_of};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnumVariant {
+    pub name: String,
+    pub value: i128,
+    #[serde(default)]
+    pub doc: String,
+}
+impl EnumVariant {
+    pub fn new(name: &str, value: i128) -> Self {
+        Self {
+            name: name.to_string(),
+            value,
+            doc: String::new(),
+        }
+    }
+}
+
+fn default_repr_index() -> usize {
+    PRIMITIVES.index_of("i32").unwrap()
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EnumType {
     pub name: String,
-    pub variants: Vec<String>,
+    #[serde(default)]
+    pub namespace: String,
+    #[serde(default)]
+    pub doc: String,
+    pub variants: Vec<EnumVariant>,
+    #[serde(default = "default_repr_index")]
+    pub repr_index: usize,
     pub attributes: Vec<(String, String)>,
+    #[serde(default)]
+    pub fingerprint: u64,
 }
 impl Default for EnumType {
     fn default() -> Self {
         Self {
             name: "".to_string(),
+            namespace: String::new(),
+            doc: String::new(),
             variants: Vec::new(),
+            repr_index: default_repr_index(),
             attributes: Vec::new(),
+            fingerprint: 0,
         }
     }
 }
@@ -20,8 +56,31 @@ impl EnumType {
     pub fn new(name: &str, variants: &[&str], attributes: &[(String, String)]) -> Self {
         Self {
             name: name.to_string(),
-            variants: variants.iter().map(ToString::to_string).collect(),
+            variants: variants
+                .iter()
+                .enumerate()
+                .map(|(value, variant)| EnumVariant::new(variant, value as i128))
+                .collect(),
+            attributes: attributes.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_values(
+        name: &str,
+        repr_index: usize,
+        variants: &[(&str, i128)],
+        attributes: &[(String, String)],
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            variants: variants
+                .iter()
+                .map(|&(variant, value)| EnumVariant::new(variant, value))
+                .collect(),
+            repr_index,
             attributes: attributes.to_vec(),
+            ..Default::default()
         }
     }
 
@@ -32,11 +91,38 @@ impl EnumType {
         }
     }
 
-    pub fn size() -> usize {
-        size_of::<i32>()
+    pub fn variant_by_value(&self, value: i128) -> Option<&EnumVariant> {
+        self.variants.iter().find(|variant| variant.value == value)
+    }
+
+    pub fn variant_by_name(&self, name: &str) -> Option<&EnumVariant> {
+        self.variants.iter().find(|variant| variant.name == name)
+    }
+
+    pub fn try_size(&self, schema: &Schema) -> Result<usize, Error> {
+        schema.try_type_size(Type::Primitive, self.repr_index)
+    }
+
+    pub fn try_align(&self, schema: &Schema) -> Result<usize, Error> {
+        schema.try_type_align(Type::Primitive, self.repr_index)
     }
 
-    pub fn align() -> usize {
-        align_of::<i32>()
+    pub fn value_range(&self, schema: &Schema) -> Result<(i128, i128), Error> {
+        let repr_name = schema.try_type_name(Type::Primitive, self.repr_index)?;
+        if !INTEGER_PRIMITIVE_NAMES.contains(&repr_name.as_ref()) {
+            return Err(Error::InvalidEnumRepr {
+                enum_name: self.name.clone(),
+                repr: repr_name.to_string(),
+            });
+        }
+
+        let bits = self.try_size(schema)? as u32 * 8;
+        if repr_name.starts_with('i') {
+            Ok((i128::MIN >> (128 - bits), i128::MAX >> (128 - bits)))
+        } else {
+            let max = (u128::MAX >> (128 - bits)).min(i128::MAX as u128);
+
+            Ok((0, max as i128))
+        }
     }
 }
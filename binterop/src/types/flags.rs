@@ -0,0 +1,114 @@
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::types::primitives::{INTEGER_PRIMITIVE_NAMES, PRIMITIVES};
+use crate::types::Type;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlagsBit {
+    pub name: String,
+    pub bit: u32,
+    #[serde(default)]
+    pub doc: String,
+}
+impl FlagsBit {
+    pub fn new(name: &str, bit: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            bit,
+            doc: String::new(),
+        }
+    }
+
+    pub fn mask(&self) -> u128 {
+        1u128.checked_shl(self.bit).unwrap_or_default()
+    }
+}
+
+fn default_repr_index() -> usize {
+    PRIMITIVES.index_of("u32").unwrap()
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FlagsType {
+    pub name: String,
+    #[serde(default)]
+    pub namespace: String,
+    #[serde(default)]
+    pub doc: String,
+    pub bits: Vec<FlagsBit>,
+    #[serde(default = "default_repr_index")]
+    pub repr_index: usize,
+    pub attributes: Vec<(String, String)>,
+    #[serde(default)]
+    pub fingerprint: u64,
+}
+impl Default for FlagsType {
+    fn default() -> Self {
+        Self {
+            name: "".to_string(),
+            namespace: String::new(),
+            doc: String::new(),
+            bits: Vec::new(),
+            repr_index: default_repr_index(),
+            attributes: Vec::new(),
+            fingerprint: 0,
+        }
+    }
+}
+impl FlagsType {
+    pub fn new(
+        name: &str,
+        repr_index: usize,
+        bits: &[(&str, u32)],
+        attributes: &[(String, String)],
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            namespace: String::new(),
+            doc: String::new(),
+            bits: bits
+                .iter()
+                .map(|&(bit_name, bit)| FlagsBit::new(bit_name, bit))
+                .collect(),
+            repr_index,
+            attributes: attributes.to_vec(),
+            fingerprint: 0,
+        }
+    }
+
+    pub fn default_with_name(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn bit_by_name(&self, name: &str) -> Option<&FlagsBit> {
+        self.bits.iter().find(|bit| bit.name == name)
+    }
+
+    pub fn try_size(&self, schema: &Schema) -> Result<usize, Error> {
+        schema.try_type_size(Type::Primitive, self.repr_index)
+    }
+
+    pub fn try_align(&self, schema: &Schema) -> Result<usize, Error> {
+        schema.try_type_align(Type::Primitive, self.repr_index)
+    }
+
+    pub fn bit_count(&self, schema: &Schema) -> Result<u32, Error> {
+        let repr_name = schema.try_type_name(Type::Primitive, self.repr_index)?;
+        if !is_flags_repr(&repr_name) {
+            return Err(Error::InvalidFlagsRepr {
+                flags_name: self.name.clone(),
+                repr: repr_name.to_string(),
+            });
+        }
+
+        Ok(self.try_size(schema)? as u32 * 8)
+    }
+}
+
+pub fn is_flags_repr(name: &str) -> bool {
+    INTEGER_PRIMITIVE_NAMES.contains(&name) && name.starts_with('u')
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+use crate::target::TargetLayout;
 use crate::types::{pointer::PointerType, TypeData};
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
@@ -16,15 +17,24 @@ impl Arg {
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct FunctionType {
     pub name: String,
+    #[serde(default)]
+    pub namespace: String,
+    #[serde(default)]
+    pub doc: String,
     pub args: Vec<Arg>,
     pub return_type: Option<TypeData>,
+    #[serde(default)]
+    pub fingerprint: u64,
 }
 impl FunctionType {
     pub fn new(name: String, args: Vec<Arg>, return_type: Option<TypeData>) -> Self {
         FunctionType {
             name,
+            namespace: String::new(),
+            doc: String::new(),
             args,
             return_type,
+            fingerprint: 0,
         }
     }
 
@@ -35,7 +45,7 @@ impl FunctionType {
         }
     }
 
-    pub fn size() -> usize {
-        PointerType::size()
+    pub fn size(target: &TargetLayout) -> usize {
+        PointerType::size(target)
     }
 }
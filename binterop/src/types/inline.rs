@@ -0,0 +1,116 @@
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::types::Type;
+use serde::{Deserialize, Serialize};
+use std::cmp::max;
+use std::mem::{align_of, size_of};
+
+// Both inline types are laid out as #[repr(C)] struct { len: u32, items: [T; capacity] }
+#[derive(PartialEq, Eq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct InlineStringType {
+    pub capacity: usize,
+}
+impl InlineStringType {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity }
+    }
+
+    pub fn size(&self) -> usize {
+        (Self::data_offset() + self.capacity).next_multiple_of(Self::align())
+    }
+
+    pub fn align() -> usize {
+        align_of::<u32>()
+    }
+
+    pub fn data_offset() -> usize {
+        size_of::<u32>()
+    }
+
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let capacity = s["String<".len()..(s.len() - 1)].trim().parse().map_err(
+            |err: std::num::ParseIntError| Error::ParseInline {
+                text: s.to_string(),
+                reason: err.to_string(),
+            },
+        )?;
+
+        Ok(Self::new(capacity))
+    }
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct InlineVectorType {
+    pub inner_type: Type,
+    pub inner_type_index: usize,
+    pub capacity: usize,
+}
+impl InlineVectorType {
+    pub fn new(inner_type: Type, inner_type_index: usize, capacity: usize) -> Self {
+        Self {
+            inner_type,
+            inner_type_index,
+            capacity,
+        }
+    }
+
+    pub fn try_size(&self, schema: &Schema) -> Result<usize, Error> {
+        let inner_type_size = schema.try_type_size(self.inner_type, self.inner_type_index)?;
+
+        Ok(
+            (self.try_data_offset(schema)? + inner_type_size * self.capacity)
+                .next_multiple_of(self.try_align(schema)?),
+        )
+    }
+
+    pub fn try_align(&self, schema: &Schema) -> Result<usize, Error> {
+        Ok(max(
+            align_of::<u32>(),
+            schema.try_type_align(self.inner_type, self.inner_type_index)?,
+        ))
+    }
+
+    pub fn try_data_offset(&self, schema: &Schema) -> Result<usize, Error> {
+        let inner_type_align = schema.try_type_align(self.inner_type, self.inner_type_index)?;
+
+        Ok(size_of::<u32>().next_multiple_of(inner_type_align))
+    }
+
+    pub fn parse(s: &str, schema: &mut Schema) -> Result<Self, Error> {
+        let parse_error = |reason: String| Error::ParseInline {
+            text: s.to_string(),
+            reason,
+        };
+        let (inner_type_name, capacity) = split_capacity(&s[1..(s.len() - 1)])
+            .ok_or_else(|| parse_error("missing capacity".to_string()))?;
+        if inner_type_name.is_empty() {
+            return Err(parse_error("missing inner type name".to_string()));
+        }
+
+        let capacity = capacity
+            .parse()
+            .map_err(|err: std::num::ParseIntError| parse_error(err.to_string()))?;
+        let inner_type_data = schema.type_data_by_name(inner_type_name)?;
+
+        Ok(Self::new(
+            inner_type_data.r#type,
+            inner_type_data.index,
+            capacity,
+        ))
+    }
+}
+
+pub(crate) fn split_capacity(inner: &str) -> Option<(&str, &str)> {
+    let mut depth = 0usize;
+    let mut separator = None;
+    for (position, c) in inner.char_indices() {
+        match c {
+            '[' | '<' | '{' => depth += 1,
+            ']' | '>' | '}' => depth = depth.saturating_sub(1),
+            ':' if depth == 0 => separator = Some(position),
+            _ => {}
+        }
+    }
+
+    separator.map(|position| (inner[..position].trim(), inner[position + 1..].trim()))
+}
@@ -0,0 +1,101 @@
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::types::vector::VectorType;
+use crate::types::Type;
+use serde::{Deserialize, Serialize};
+use std::cmp::max;
+
+// Stored like a vector of #[repr(C)] struct { key: K, value: V } entries, sorted by key
+#[derive(PartialEq, Eq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct MapType {
+    pub key_type: Type,
+    pub key_type_index: usize,
+    pub value_type: Type,
+    pub value_type_index: usize,
+}
+impl MapType {
+    pub fn new(
+        key_type: Type,
+        key_type_index: usize,
+        value_type: Type,
+        value_type_index: usize,
+    ) -> Self {
+        Self {
+            key_type,
+            key_type_index,
+            value_type,
+            value_type_index,
+        }
+    }
+
+    pub fn size() -> usize {
+        VectorType::size()
+    }
+
+    pub fn is_copy() -> bool {
+        false
+    }
+
+    pub fn try_entry_size(&self, schema: &Schema) -> Result<usize, Error> {
+        let value_size = schema.try_type_size(self.value_type, self.value_type_index)?;
+
+        Ok((self.try_value_offset(schema)? + value_size)
+            .next_multiple_of(self.try_entry_align(schema)?))
+    }
+
+    pub fn try_entry_align(&self, schema: &Schema) -> Result<usize, Error> {
+        Ok(max(
+            schema.try_type_align(self.key_type, self.key_type_index)?,
+            schema.try_type_align(self.value_type, self.value_type_index)?,
+        ))
+    }
+
+    pub fn try_value_offset(&self, schema: &Schema) -> Result<usize, Error> {
+        let key_size = schema.try_type_size(self.key_type, self.key_type_index)?;
+        let value_align = schema.try_type_align(self.value_type, self.value_type_index)?;
+
+        Ok(key_size.next_multiple_of(value_align))
+    }
+
+    pub fn parse(s: &str, schema: &mut Schema) -> Result<Self, Error> {
+        let parse_error = |reason: &str| Error::ParseMap {
+            text: s.to_string(),
+            reason: reason.to_string(),
+        };
+        let inner = &s[1..(s.len() - 1)];
+
+        // Key types can contain ':' themselves, like {[u8:4]: u32}
+        let mut depth = 0usize;
+        let separator = inner
+            .char_indices()
+            .find(|&(_, c)| {
+                match c {
+                    '[' | '<' | '{' => depth += 1,
+                    ']' | '>' | '}' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                c == ':' && depth == 0
+            })
+            .map(|(position, _)| position)
+            .ok_or_else(|| parse_error("missing ':' between key and value types"))?;
+
+        let (key_type_name, value_type_name) =
+            (inner[..separator].trim(), inner[separator + 1..].trim());
+        if key_type_name.is_empty() {
+            return Err(parse_error("missing key type name"));
+        }
+        if value_type_name.is_empty() {
+            return Err(parse_error("missing value type name"));
+        }
+
+        let key_type_data = schema.type_data_by_name(key_type_name)?;
+        let value_type_data = schema.type_data_by_name(value_type_name)?;
+
+        Ok(Self::new(
+            key_type_data.r#type,
+            key_type_data.index,
+            value_type_data.r#type,
+            value_type_data.index,
+        ))
+    }
+}
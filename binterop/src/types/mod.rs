@@ -1,20 +1,32 @@
+use alias::AliasType;
 use array::ArrayType;
 use data::DataType;
+use flags::FlagsType;
+use inline::{InlineStringType, InlineVectorType};
+use map::MapType;
+use optional::OptionalType;
 use pointer::PointerType;
 use primitives::PrimitiveType;
 use r#enum::EnumType;
+use slice::SliceType;
 use serde::{Deserialize, Serialize};
 use union::UnionType;
 use vector::VectorType;
 
 use crate::types::function::FunctionType;
 
+pub mod alias;
 pub mod array;
 pub mod data;
 pub mod r#enum;
+pub mod flags;
 pub mod function;
+pub mod inline;
+pub mod map;
+pub mod optional;
 pub mod pointer;
 pub mod primitives;
+pub mod slice;
 pub mod union;
 pub mod vector;
 
@@ -36,7 +48,7 @@ impl TypeData {
     }
 }
 
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum Type {
     #[default]
     Primitive,
@@ -48,6 +60,13 @@ pub enum Type {
     Pointer,
     String,
     Function,
+    Optional,
+    Map,
+    Slice,
+    InlineString,
+    InlineVector,
+    Flags,
+    Alias,
 }
 
 #[derive(Clone, Debug)]
@@ -61,6 +80,13 @@ pub enum WrappedType {
     Vector(VectorType),
     String,
     Function(FunctionType),
+    Optional(OptionalType),
+    Map(MapType),
+    Slice(SliceType),
+    InlineString(InlineStringType),
+    InlineVector(InlineVectorType),
+    Flags(FlagsType),
+    Alias(AliasType),
 }
 impl WrappedType {
     pub fn r#type(&self) -> Type {
@@ -74,6 +100,27 @@ impl WrappedType {
             WrappedType::Vector(_) => Type::Vector,
             WrappedType::String => Type::String,
             WrappedType::Function(_) => Type::Function,
+            WrappedType::Optional(_) => Type::Optional,
+            WrappedType::Map(_) => Type::Map,
+            WrappedType::Slice(_) => Type::Slice,
+            WrappedType::InlineString(_) => Type::InlineString,
+            WrappedType::InlineVector(_) => Type::InlineVector,
+            WrappedType::Flags(_) => Type::Flags,
+            WrappedType::Alias(_) => Type::Alias,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            WrappedType::Data(data_type) => data_type.name.clone(),
+            WrappedType::Enum(enum_type) => enum_type.name.clone(),
+            WrappedType::Union(union_type) => union_type.name.clone(),
+            WrappedType::Flags(flags_type) => flags_type.name.clone(),
+            WrappedType::Alias(alias_type) => alias_type.name.clone(),
+            WrappedType::Function(function_type) => function_type.name.clone(),
+            WrappedType::Primitive(primitive_type) => primitive_type.name.to_string(),
+            WrappedType::String => "String".to_string(),
+            wrapped_type => format!("{wrapped_type:?}"),
         }
     }
 }
@@ -0,0 +1,42 @@
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::types::Type;
+use serde::{Deserialize, Serialize};
+use std::mem::size_of;
+
+// Laid out as a bool tag followed by the value at the next multiple of its alignment, like
+// #[repr(C)] struct { is_some: bool, value: T }
+#[derive(PartialEq, Eq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct OptionalType {
+    pub inner_type: Type,
+    pub inner_type_index: usize,
+}
+impl OptionalType {
+    pub fn new(inner_type: Type, inner_type_index: usize) -> Self {
+        Self {
+            inner_type,
+            inner_type_index,
+        }
+    }
+
+    pub fn try_size(&self, schema: &Schema) -> Result<usize, Error> {
+        let inner_type_size = schema.try_type_size(self.inner_type, self.inner_type_index)?;
+        let align = self.try_align(schema)?;
+
+        Ok((self.try_data_offset(schema)? + inner_type_size).next_multiple_of(align))
+    }
+
+    pub fn try_align(&self, schema: &Schema) -> Result<usize, Error> {
+        schema.try_type_align(self.inner_type, self.inner_type_index)
+    }
+
+    pub fn try_data_offset(&self, schema: &Schema) -> Result<usize, Error> {
+        Ok(size_of::<bool>().next_multiple_of(self.try_align(schema)?))
+    }
+
+    pub fn parse(s: &str, schema: &mut Schema) -> Result<Self, Error> {
+        let inner_type_data = schema.type_data_by_name(&s[..s.len() - 1])?;
+
+        Ok(Self::new(inner_type_data.r#type, inner_type_data.index))
+    }
+}
@@ -1,6 +1,6 @@
+use crate::target::TargetLayout;
 use crate::types::Type;
 use serde::{Deserialize, Serialize};
-use std::mem::size_of;
 
 #[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct PointerType {
@@ -15,7 +15,7 @@ impl PointerType {
         }
     }
 
-    pub fn size() -> usize {
-        size_of::<u64>()
+    pub fn size(target: &TargetLayout) -> usize {
+        target.pointer_width
     }
 }
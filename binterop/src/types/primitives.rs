@@ -1,5 +1,4 @@
 use serde::{Deserialize, Serialize};
-use std::mem::{align_of, size_of};
 use std::ops::Index;
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
@@ -11,62 +10,94 @@ pub struct PrimitiveType {
 
 pub struct Primitives(phf::OrderedMap<&'static str, PrimitiveType>);
 impl Primitives {
+    // Sizes and alignments are the C ABI ones shared by every built-in target, so layouts don't
+    // depend on the host running the generator. Targets that differ use primitive overrides
     const fn new() -> Self {
         let map = phf::phf_ordered_map! {
             "bool" => PrimitiveType {
                 name: "bool",
-                size: size_of::<bool>(),
-                align: align_of::<bool>()
+                size: 1,
+                align: 1
             },
             "i8" => PrimitiveType {
                 name: "i8",
-                size: size_of::<i8>(),
-                align: align_of::<i8>()
+                size: 1,
+                align: 1
             },
             "u8" => PrimitiveType {
                 name: "u8",
-                size: size_of::<u8>(),
-                align: align_of::<u8>()
+                size: 1,
+                align: 1
             },
             "i16" => PrimitiveType {
                 name: "i16",
-                size: size_of::<i16>(),
-                align: align_of::<i16>()
+                size: 2,
+                align: 2
             },
             "u16" => PrimitiveType {
                 name: "u16",
-                size: size_of::<u16>(),
-                align: align_of::<u16>()
+                size: 2,
+                align: 2
             },
             "i32" => PrimitiveType {
                 name: "i32",
-                size: size_of::<i32>(),
-                align: align_of::<i32>()
+                size: 4,
+                align: 4
             },
             "u32" => PrimitiveType {
                 name: "u32",
-                size: size_of::<u32>(),
-                align: align_of::<u32>()
+                size: 4,
+                align: 4
             },
             "i64" => PrimitiveType {
                 name: "i64",
-                size: size_of::<i64>(),
-                align: align_of::<i64>()
+                size: 8,
+                align: 8
             },
             "u64" => PrimitiveType {
                 name: "u64",
-                size: size_of::<u64>(),
-                align: align_of::<u64>()
+                size: 8,
+                align: 8
             },
             "f32" => PrimitiveType {
                 name: "f32",
-                size: size_of::<f32>(),
-                align: align_of::<f32>()
+                size: 4,
+                align: 4
             },
             "f64" => PrimitiveType {
                 name: "f64",
-                size: size_of::<f64>(),
-                align: align_of::<f64>()
+                size: 8,
+                align: 8
+            },
+            "i128" => PrimitiveType {
+                name: "i128",
+                size: 16,
+                align: 16
+            },
+            "u128" => PrimitiveType {
+                name: "u128",
+                size: 16,
+                align: 16
+            },
+            "f16" => PrimitiveType {
+                name: "f16",
+                size: 2,
+                align: 2
+            },
+            "char32" => PrimitiveType {
+                name: "char32",
+                size: 4,
+                align: 4
+            },
+            "usize" => PrimitiveType {
+                name: "usize",
+                size: 8,
+                align: 8
+            },
+            "isize" => PrimitiveType {
+                name: "isize",
+                size: 8,
+                align: 8
             }
         };
 
@@ -102,5 +133,6 @@ impl Index<&str> for Primitives {
 }
 
 pub const PRIMITIVES: Primitives = Primitives::new();
-pub const INTEGER_PRIMITIVE_NAMES: [&str; 8] =
-    ["u8", "u16", "u32", "u64", "i8", "i16", "i32", "i64"];
+pub const INTEGER_PRIMITIVE_NAMES: [&str; 12] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
@@ -0,0 +1,41 @@
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::types::Type;
+use serde::{Deserialize, Serialize};
+use std::mem::size_of;
+
+#[derive(PartialEq, Eq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct SliceType {
+    pub inner_type: Type,
+    pub inner_type_index: usize,
+}
+impl SliceType {
+    pub fn new(inner_type: Type, inner_type_index: usize) -> Self {
+        Self {
+            inner_type,
+            inner_type_index,
+        }
+    }
+
+    pub fn size() -> usize {
+        size_of::<u64>() * 2
+    }
+
+    pub fn is_copy() -> bool {
+        true
+    }
+
+    pub fn parse(s: &str, schema: &mut Schema) -> Result<Self, Error> {
+        let inner_type_name = &s[2..(s.len() - 1)];
+        if inner_type_name.trim().is_empty() {
+            return Err(Error::ParseSlice {
+                text: s.to_string(),
+                reason: "missing inner type name".to_string(),
+            });
+        }
+
+        let inner_type_data = schema.type_data_by_name(inner_type_name)?;
+
+        Ok(Self::new(inner_type_data.r#type, inner_type_data.index))
+    }
+}
@@ -1,30 +1,60 @@
+use crate::error::Error;
 use crate::schema::Schema;
 use crate::types::Type;
 use serde::{Deserialize, Serialize};
 use std::cmp::max;
 use std::mem::{align_of, size_of};
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnionVariant {
+    pub name: String,
+    pub payload: Option<(usize, Type)>,
+    #[serde(default)]
+    pub doc: String,
+}
+impl UnionVariant {
+    pub fn new(name: &str, payload: Option<(usize, Type)>) -> Self {
+        Self {
+            name: name.to_string(),
+            payload,
+            doc: String::new(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct UnionType {
     pub name: String,
-    pub possible_types: Vec<(usize, Type)>,
+    #[serde(default)]
+    pub namespace: String,
+    #[serde(default)]
+    pub doc: String,
+    pub variants: Vec<UnionVariant>,
     pub attributes: Vec<(String, String)>,
+    #[serde(default)]
+    pub fingerprint: u64,
 }
 impl Default for UnionType {
     fn default() -> Self {
         Self {
             name: "".to_string(),
-            possible_types: vec![],
+            namespace: String::new(),
+            doc: String::new(),
+            variants: vec![],
             attributes: Vec::new(),
+            fingerprint: 0,
         }
     }
 }
 impl UnionType {
-    pub fn new(name: &str, possible_types: &[(usize, Type)], attributes: &[(String, String)]) -> Self {
+    pub fn new(name: &str, variants: &[UnionVariant], attributes: &[(String, String)]) -> Self {
         Self {
             name: name.to_string(),
-            possible_types: possible_types.to_vec(),
+            namespace: String::new(),
+            doc: String::new(),
+            variants: variants.to_vec(),
             attributes: attributes.to_vec(),
+            fingerprint: 0,
         }
     }
 
@@ -35,40 +65,66 @@ impl UnionType {
         }
     }
 
-    pub fn size(&self, schema: &Schema) -> usize {
-        let max_possible_type_size = self
-            .possible_types
+    pub fn variant_by_name(&self, name: &str) -> Option<(usize, &UnionVariant)> {
+        self.variants
             .iter()
-            .map(|&(index, r#type)| {
-                schema.type_size(r#type, index).unwrap_or_else(|| {
-                    panic!("Provided schema does not contain type {type:?} with index {index}!")
-                })
-            })
+            .enumerate()
+            .find(|(_, variant)| variant.name == name)
+    }
+
+    pub fn payloads(&self) -> impl Iterator<Item = (usize, Type)> + '_ {
+        self.variants.iter().filter_map(|variant| variant.payload)
+    }
+
+    pub fn try_size(&self, schema: &Schema) -> Result<usize, Error> {
+        if self.variants.is_empty() {
+            return Err(self.empty_error());
+        }
+
+        let max_payload_size = self
+            .payloads()
+            .map(|(index, r#type)| schema.try_type_size(r#type, index))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
             .max()
-            .unwrap();
+            .unwrap_or_default();
 
-        size_of::<i32>() + max_possible_type_size
+        Ok((self.try_data_offset(schema)? + max_payload_size)
+            .next_multiple_of(self.try_align(schema)?))
     }
 
-    pub fn align(&self, schema: &Schema) -> usize {
+    pub fn try_align(&self, schema: &Schema) -> Result<usize, Error> {
         let repr_type_align = align_of::<i32>();
-        let max_possible_type_align = self
-            .possible_types
-            .iter()
-            .map(|&(index, r#type)| {
-                schema.type_align(r#type, index).unwrap_or_else(|| {
-                    panic!("Provided schema does not contain type {type:?} with index {index}!")
-                })
-            })
+
+        Ok(max(repr_type_align, self.max_payload_align(schema)?))
+    }
+
+    pub fn try_data_offset(&self, schema: &Schema) -> Result<usize, Error> {
+        Ok(size_of::<i32>().next_multiple_of(self.max_payload_align(schema)?))
+    }
+
+    fn max_payload_align(&self, schema: &Schema) -> Result<usize, Error> {
+        if self.variants.is_empty() {
+            return Err(self.empty_error());
+        }
+
+        Ok(self
+            .payloads()
+            .map(|(index, r#type)| schema.try_type_align(r#type, index))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
             .max()
-            .unwrap();
+            .unwrap_or(1))
+    }
 
-        max(repr_type_align, max_possible_type_align)
+    fn empty_error(&self) -> Error {
+        Error::EmptyUnion {
+            name: self.name.clone(),
+        }
     }
 
     pub fn is_copy(&self, schema: &Schema) -> bool {
-        self.possible_types
-            .iter()
-            .all(|&(index, r#type)| schema.is_copy(r#type, index).unwrap_or_default())
+        self.payloads()
+            .all(|(index, r#type)| schema.is_copy(r#type, index).unwrap_or_default())
     }
 }
@@ -1,5 +1,6 @@
+use crate::error::Error;
+use crate::schema::Schema;
 use crate::types::Type;
-use crate::{schema::Schema, types::pointer::PointerType};
 use serde::{Deserialize, Serialize};
 use std::mem::size_of;
 
@@ -17,15 +18,22 @@ impl VectorType {
     }
 
     pub fn size() -> usize {
-        PointerType::size() + size_of::<u64>() * 2
+        size_of::<u64>() * 3
     }
 
     pub fn is_copy() -> bool {
         false
     }
 
-    pub fn parse(s: &str, schema: &mut Schema) -> Result<Self, String> {
+    pub fn parse(s: &str, schema: &mut Schema) -> Result<Self, Error> {
         let inner_type_name = &s[1..(s.len() - 1)];
+        if inner_type_name.trim().is_empty() {
+            return Err(Error::ParseVector {
+                text: s.to_string(),
+                reason: "missing inner type name".to_string(),
+            });
+        }
+
         let inner_type_data = schema.type_data_by_name(inner_type_name)?;
 
         Ok(Self::new(inner_type_data.r#type, inner_type_data.index))
@@ -0,0 +1,669 @@
+use crate::dependency::layout_order;
+use crate::error::Error;
+use crate::schema::{qualified_name, Schema};
+use crate::types::primitives::INTEGER_PRIMITIVE_NAMES;
+use crate::types::Type;
+use std::fmt::{self, Display};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    DanglingTypeIndex {
+        owner: String,
+        member: Option<String>,
+        r#type: Type,
+        index: usize,
+    },
+    EmptyUnion {
+        union_name: String,
+    },
+    DuplicateUnionVariant {
+        union_name: String,
+        variant: String,
+    },
+    OverlappingFields {
+        type_name: String,
+        field_name: String,
+        other_field_name: String,
+    },
+    MisalignedField {
+        type_name: String,
+        field_name: String,
+        offset: usize,
+        align: usize,
+    },
+    MissingArgType {
+        function_name: String,
+        arg_name: String,
+    },
+    InvalidEnumRepr {
+        enum_name: String,
+        repr: String,
+    },
+    EnumValueOutOfRange {
+        enum_name: String,
+        variant: String,
+        value: i128,
+    },
+    DuplicateEnumValue {
+        enum_name: String,
+        variant: String,
+        other_variant: String,
+    },
+    InvalidFlagsRepr {
+        flags_name: String,
+        repr: String,
+    },
+    FlagBitOutOfRange {
+        flags_name: String,
+        flag: String,
+        bit: u32,
+    },
+    DuplicateFlagBit {
+        flags_name: String,
+        flag: String,
+        other_flag: String,
+    },
+    InvalidBitfield {
+        type_name: String,
+        field_name: String,
+        field_type: String,
+        bit_offset: u32,
+        bit_width: u32,
+    },
+    InvalidConstantType {
+        constant_name: String,
+        type_name: String,
+    },
+    ConstantValueOutOfRange {
+        constant_name: String,
+        type_name: String,
+        value: i128,
+    },
+    DuplicateConstant {
+        constant_name: String,
+    },
+    InvalidAttributes {
+        type_name: String,
+        error: Error,
+    },
+    RecursiveWithoutIndirection {
+        type_name: String,
+        path: Vec<String>,
+    },
+    RecursiveAlias {
+        type_name: String,
+    },
+}
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::DanglingTypeIndex {
+                owner,
+                member,
+                r#type,
+                index,
+            } => {
+                write!(f, "{owner}")?;
+                if let Some(member) = member {
+                    write!(f, ".{member}")?;
+                }
+                write!(f, " references missing {type:?} type with index {index}")
+            }
+            ValidationError::EmptyUnion { union_name } => {
+                write!(f, "Union {union_name} has no variants")
+            }
+            ValidationError::DuplicateUnionVariant {
+                union_name,
+                variant,
+            } => write!(
+                f,
+                "Union {union_name} declares variant {variant} more than once"
+            ),
+            ValidationError::OverlappingFields {
+                type_name,
+                field_name,
+                other_field_name,
+            } => write!(
+                f,
+                "{type_name}.{field_name} overlaps with {type_name}.{other_field_name}"
+            ),
+            ValidationError::MisalignedField {
+                type_name,
+                field_name,
+                offset,
+                align,
+            } => write!(
+                f,
+                "{type_name}.{field_name} at offset {offset} is not aligned to {align}"
+            ),
+            ValidationError::MissingArgType {
+                function_name,
+                arg_name,
+            } => write!(
+                f,
+                "Argument {arg_name} of function {function_name} has no type"
+            ),
+            ValidationError::InvalidEnumRepr { enum_name, repr } => write!(
+                f,
+                "Enum {enum_name} uses {repr} as its repr, but only integer primitives are allowed"
+            ),
+            ValidationError::EnumValueOutOfRange {
+                enum_name,
+                variant,
+                value,
+            } => write!(
+                f,
+                "{enum_name}.{variant} has value {value} which does not fit into its repr"
+            ),
+            ValidationError::DuplicateEnumValue {
+                enum_name,
+                variant,
+                other_variant,
+            } => write!(
+                f,
+                "{enum_name}.{variant} has the same value as {enum_name}.{other_variant}"
+            ),
+            ValidationError::InvalidFlagsRepr { flags_name, repr } => write!(
+                f,
+                "Flags {flags_name} use {repr} as their repr, but only unsigned integer primitives are allowed"
+            ),
+            ValidationError::FlagBitOutOfRange {
+                flags_name,
+                flag,
+                bit,
+            } => write!(
+                f,
+                "{flags_name}.{flag} uses bit {bit} which does not fit into its repr"
+            ),
+            ValidationError::DuplicateFlagBit {
+                flags_name,
+                flag,
+                other_flag,
+            } => write!(
+                f,
+                "{flags_name}.{flag} uses the same bit as {flags_name}.{other_flag}"
+            ),
+            ValidationError::InvalidBitfield {
+                type_name,
+                field_name,
+                field_type,
+                bit_offset,
+                bit_width,
+            } => write!(
+                f,
+                "{type_name}.{field_name} uses {bit_width} bits at bit {bit_offset}, which don't fit into {field_type}"
+            ),
+            ValidationError::InvalidConstantType {
+                constant_name,
+                type_name,
+            } => write!(
+                f,
+                "Constant {constant_name} has type {type_name}, but only integer primitives are allowed"
+            ),
+            ValidationError::ConstantValueOutOfRange {
+                constant_name,
+                type_name,
+                value,
+            } => write!(
+                f,
+                "Constant {constant_name} has value {value} which does not fit into {type_name}"
+            ),
+            ValidationError::DuplicateConstant { constant_name } => {
+                write!(f, "Constant {constant_name} is declared more than once")
+            }
+            ValidationError::InvalidAttributes { error, .. } => write!(f, "{error}"),
+            ValidationError::RecursiveWithoutIndirection { type_name, path } => write!(
+                f,
+                "{type_name} contains itself by value through {}",
+                path.join(" -> ")
+            ),
+            ValidationError::RecursiveAlias { type_name } => {
+                write!(f, "{type_name} refers to itself")
+            }
+        }
+    }
+}
+impl std::error::Error for ValidationError {}
+
+fn check_type_index(
+    schema: &Schema,
+    errors: &mut Vec<ValidationError>,
+    owner: &str,
+    member: Option<&str>,
+    r#type: Type,
+    index: usize,
+) {
+    if !schema.contains_type(r#type, index) {
+        errors.push(ValidationError::DanglingTypeIndex {
+            owner: owner.to_string(),
+            member: member.map(ToString::to_string),
+            r#type,
+            index,
+        });
+    }
+}
+
+fn validate_references(schema: &Schema, errors: &mut Vec<ValidationError>) {
+    for data_type in &schema.types {
+        for field in &data_type.fields {
+            check_type_index(
+                schema,
+                errors,
+                &data_type.name,
+                Some(&field.name),
+                field.r#type,
+                field.type_index,
+            );
+        }
+    }
+
+    for enum_type in &schema.enums {
+        check_type_index(
+            schema,
+            errors,
+            &enum_type.name,
+            None,
+            Type::Primitive,
+            enum_type.repr_index,
+        );
+    }
+
+    for flags_type in &schema.flags {
+        check_type_index(
+            schema,
+            errors,
+            &flags_type.name,
+            None,
+            Type::Primitive,
+            flags_type.repr_index,
+        );
+    }
+
+    for alias_type in &schema.aliases {
+        check_type_index(
+            schema,
+            errors,
+            &alias_type.name,
+            None,
+            alias_type.inner_type,
+            alias_type.inner_type_index,
+        );
+    }
+
+    for union_type in &schema.unions {
+        if union_type.variants.is_empty() {
+            errors.push(ValidationError::EmptyUnion {
+                union_name: union_type.name.clone(),
+            });
+        }
+
+        for (position, variant) in union_type.variants.iter().enumerate() {
+            if union_type.variants[..position]
+                .iter()
+                .any(|other_variant| other_variant.name == variant.name)
+            {
+                errors.push(ValidationError::DuplicateUnionVariant {
+                    union_name: union_type.name.clone(),
+                    variant: variant.name.clone(),
+                });
+            }
+
+            if let Some((index, r#type)) = variant.payload {
+                check_type_index(
+                    schema,
+                    errors,
+                    &union_type.name,
+                    Some(&variant.name),
+                    r#type,
+                    index,
+                );
+            }
+        }
+    }
+
+    for (array_index, array_type) in schema.arrays.iter().enumerate() {
+        check_type_index(
+            schema,
+            errors,
+            &format!("Array #{array_index}"),
+            None,
+            array_type.inner_type,
+            array_type.inner_type_index,
+        );
+    }
+    for (vector_index, vector_type) in schema.vectors.iter().enumerate() {
+        check_type_index(
+            schema,
+            errors,
+            &format!("Vector #{vector_index}"),
+            None,
+            vector_type.inner_type,
+            vector_type.inner_type_index,
+        );
+    }
+    for (pointer_index, pointer_type) in schema.pointers.iter().enumerate() {
+        check_type_index(
+            schema,
+            errors,
+            &format!("Pointer #{pointer_index}"),
+            None,
+            pointer_type.inner_type,
+            pointer_type.inner_type_index,
+        );
+    }
+    for (optional_index, optional_type) in schema.optionals.iter().enumerate() {
+        check_type_index(
+            schema,
+            errors,
+            &format!("Optional #{optional_index}"),
+            None,
+            optional_type.inner_type,
+            optional_type.inner_type_index,
+        );
+    }
+    for (inline_vector_index, inline_vector_type) in schema.inline_vectors.iter().enumerate() {
+        check_type_index(
+            schema,
+            errors,
+            &format!("Inline vector #{inline_vector_index}"),
+            None,
+            inline_vector_type.inner_type,
+            inline_vector_type.inner_type_index,
+        );
+    }
+    for (slice_index, slice_type) in schema.slices.iter().enumerate() {
+        check_type_index(
+            schema,
+            errors,
+            &format!("Slice #{slice_index}"),
+            None,
+            slice_type.inner_type,
+            slice_type.inner_type_index,
+        );
+    }
+    for (map_index, map_type) in schema.maps.iter().enumerate() {
+        check_type_index(
+            schema,
+            errors,
+            &format!("Map #{map_index}"),
+            Some("key"),
+            map_type.key_type,
+            map_type.key_type_index,
+        );
+        check_type_index(
+            schema,
+            errors,
+            &format!("Map #{map_index}"),
+            Some("value"),
+            map_type.value_type,
+            map_type.value_type_index,
+        );
+    }
+
+    for function_type in &schema.functions {
+        for arg in &function_type.args {
+            match arg.r#type {
+                Some(type_data) => check_type_index(
+                    schema,
+                    errors,
+                    &function_type.name,
+                    Some(&arg.name),
+                    type_data.r#type,
+                    type_data.index,
+                ),
+                None => errors.push(ValidationError::MissingArgType {
+                    function_name: function_type.name.clone(),
+                    arg_name: arg.name.clone(),
+                }),
+            }
+        }
+
+        if let Some(return_type) = function_type.return_type {
+            check_type_index(
+                schema,
+                errors,
+                &function_type.name,
+                Some("return"),
+                return_type.r#type,
+                return_type.index,
+            );
+        }
+    }
+}
+
+fn validate_enums(schema: &Schema, errors: &mut Vec<ValidationError>) {
+    for enum_type in &schema.enums {
+        let (min, max) = match enum_type.value_range(schema) {
+            Ok(range) => range,
+            Err(_) => {
+                errors.push(ValidationError::InvalidEnumRepr {
+                    enum_name: enum_type.name.clone(),
+                    repr: schema
+                        .type_name(Type::Primitive, enum_type.repr_index)
+                        .to_string(),
+                });
+                continue;
+            }
+        };
+
+        for (index, variant) in enum_type.variants.iter().enumerate() {
+            if variant.value < min || variant.value > max {
+                errors.push(ValidationError::EnumValueOutOfRange {
+                    enum_name: enum_type.name.clone(),
+                    variant: variant.name.clone(),
+                    value: variant.value,
+                });
+            }
+
+            if let Some(other_variant) = enum_type.variants[..index]
+                .iter()
+                .find(|other_variant| other_variant.value == variant.value)
+            {
+                errors.push(ValidationError::DuplicateEnumValue {
+                    enum_name: enum_type.name.clone(),
+                    variant: variant.name.clone(),
+                    other_variant: other_variant.name.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn validate_flags(schema: &Schema, errors: &mut Vec<ValidationError>) {
+    for flags_type in &schema.flags {
+        let Ok(bit_count) = flags_type.bit_count(schema) else {
+            errors.push(ValidationError::InvalidFlagsRepr {
+                flags_name: flags_type.name.clone(),
+                repr: schema
+                    .type_name(Type::Primitive, flags_type.repr_index)
+                    .to_string(),
+            });
+            continue;
+        };
+
+        for (index, bit) in flags_type.bits.iter().enumerate() {
+            if bit.bit >= bit_count {
+                errors.push(ValidationError::FlagBitOutOfRange {
+                    flags_name: flags_type.name.clone(),
+                    flag: bit.name.clone(),
+                    bit: bit.bit,
+                });
+            }
+
+            if let Some(other_bit) = flags_type.bits[..index]
+                .iter()
+                .find(|other_bit| other_bit.bit == bit.bit)
+            {
+                errors.push(ValidationError::DuplicateFlagBit {
+                    flags_name: flags_type.name.clone(),
+                    flag: bit.name.clone(),
+                    other_flag: other_bit.name.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn validate_constants(schema: &Schema, errors: &mut Vec<ValidationError>) {
+    for (index, constant) in schema.constants.iter().enumerate() {
+        match constant.check(schema) {
+            Err(Error::ConstantOutOfRange {
+                type_name, value, ..
+            }) => errors.push(ValidationError::ConstantValueOutOfRange {
+                constant_name: constant.name.clone(),
+                type_name,
+                value,
+            }),
+            Err(_) => errors.push(ValidationError::InvalidConstantType {
+                constant_name: constant.name.clone(),
+                type_name: constant.type_name().to_string(),
+            }),
+            Ok(()) => {}
+        }
+
+        if schema.constants[..index].iter().any(|other_constant| {
+            other_constant.namespace == constant.namespace && other_constant.name == constant.name
+        }) {
+            errors.push(ValidationError::DuplicateConstant {
+                constant_name: qualified_name(&constant.namespace, &constant.name),
+            });
+        }
+    }
+}
+
+fn validate_layouts(schema: &Schema, errors: &mut Vec<ValidationError>) {
+    for data_type in &schema.types {
+        if let Err(error) = data_type.try_check_attributes() {
+            errors.push(ValidationError::InvalidAttributes {
+                type_name: data_type.name.clone(),
+                error,
+            });
+            continue;
+        }
+
+        // A field only fails to size if its type is already reported as invalid
+        let Ok(mut fields) = data_type
+            .fields
+            .iter()
+            .map(|field| Ok((field, field.try_size(schema)?)))
+            .collect::<Result<Vec<_>, Error>>()
+        else {
+            continue;
+        };
+
+        for &(field, size) in &fields {
+            let Some(bitfield) = field.bitfield else {
+                continue;
+            };
+
+            let field_type = field.type_name(schema);
+            let bit_count = size as u32 * 8;
+            if field.r#type != Type::Primitive
+                || !INTEGER_PRIMITIVE_NAMES.contains(&field_type.as_ref())
+                || bitfield.bit_width == 0
+                || bitfield.bit_offset + bitfield.bit_width > bit_count
+            {
+                errors.push(ValidationError::InvalidBitfield {
+                    type_name: data_type.name.clone(),
+                    field_name: field.name.clone(),
+                    field_type: field_type.to_string(),
+                    bit_offset: bitfield.bit_offset,
+                    bit_width: bitfield.bit_width,
+                });
+            }
+        }
+
+        fields.sort_by_key(|(field, _)| {
+            (
+                field.offset,
+                field.bitfield.map(|bitfield| bitfield.bit_offset),
+            )
+        });
+
+        for window in fields.windows(2) {
+            let [(field, size), (next_field, _)] = window else {
+                unreachable!()
+            };
+
+            // Bitfields in the same storage unit only overlap if their bits do
+            let shares_storage = field.offset == next_field.offset
+                && field.r#type == next_field.r#type
+                && field.type_index == next_field.type_index;
+            if let (Some(bitfield), Some(next_bitfield), true) =
+                (field.bitfield, next_field.bitfield, shares_storage)
+            {
+                if bitfield.bit_offset + bitfield.bit_width > next_bitfield.bit_offset {
+                    errors.push(ValidationError::OverlappingFields {
+                        type_name: data_type.name.clone(),
+                        field_name: field.name.clone(),
+                        other_field_name: next_field.name.clone(),
+                    });
+                }
+                continue;
+            }
+
+            if field.offset + size > next_field.offset {
+                errors.push(ValidationError::OverlappingFields {
+                    type_name: data_type.name.clone(),
+                    field_name: field.name.clone(),
+                    other_field_name: next_field.name.clone(),
+                });
+            }
+        }
+
+        if schema.is_packed || data_type.try_is_packed().unwrap_or_default() {
+            continue;
+        }
+
+        for field in &data_type.fields {
+            let Ok(align) = field.try_align(schema) else {
+                continue;
+            };
+
+            if field.offset % align != 0 {
+                errors.push(ValidationError::MisalignedField {
+                    type_name: data_type.name.clone(),
+                    field_name: field.name.clone(),
+                    offset: field.offset,
+                    align,
+                });
+            }
+        }
+    }
+}
+
+fn validate_recursion(schema: &Schema, errors: &mut Vec<ValidationError>) {
+    match layout_order(schema) {
+        Err(Error::RecursiveWithoutIndirection { type_name, path }) => {
+            errors.push(ValidationError::RecursiveWithoutIndirection { type_name, path });
+        }
+        Err(Error::RecursiveAlias { name }) => {
+            errors.push(ValidationError::RecursiveAlias { type_name: name });
+        }
+        _ => {}
+    }
+}
+
+pub fn validate(schema: &Schema) -> Result<(), Vec<ValidationError>> {
+    let mut errors = Vec::new();
+
+    validate_references(schema, &mut errors);
+    if errors.is_empty() {
+        validate_recursion(schema, &mut errors);
+    }
+
+    // Sizes can only be computed once every reference resolves, no union is empty and no type
+    // contains itself by value
+    if errors.is_empty() {
+        validate_enums(schema, &mut errors);
+        validate_flags(schema, &mut errors);
+        validate_constants(schema, &mut errors);
+        validate_layouts(schema, &mut errors);
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
@@ -0,0 +1,1213 @@
+use crate::error::Error;
+use crate::field::Bitfield;
+use crate::schema::Schema;
+use crate::target::{Endianness, TargetLayout};
+use crate::types::inline::InlineStringType;
+use crate::types::primitives::PRIMITIVES;
+use crate::types::Type;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::mem::size_of;
+
+#[derive(Clone, Debug, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum Value {
+    Bool(bool),
+    I8(i8),
+    U8(u8),
+    I16(i16),
+    U16(u16),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    I128(i128),
+    U128(u128),
+    F16(u16),
+    Char32(char),
+    Usize(u64),
+    Isize(i64),
+    Data(BTreeMap<String, Value>),
+    Enum(String),
+    Union(String, Option<Box<Value>>),
+    Array(Vec<Value>),
+    Vector(Vec<Value>),
+    String(String),
+    Pointer(u64),
+    Function(u64),
+    Optional(Option<Box<Value>>),
+    Map(Vec<(Value, Value)>),
+    Slice(Vec<Value>),
+    Flags(Vec<String>),
+}
+
+pub trait PointerResolver {
+    fn resolve(&self, address: u64, len: usize) -> Option<&[u8]>;
+}
+
+pub struct NullResolver;
+impl PointerResolver for NullResolver {
+    fn resolve(&self, _: u64, _: usize) -> Option<&[u8]> {
+        None
+    }
+}
+
+// Addresses are treated as offsets into the slice, which is what wasm linear memory looks like
+impl PointerResolver for [u8] {
+    fn resolve(&self, address: u64, len: usize) -> Option<&[u8]> {
+        let start = usize::try_from(address).ok()?;
+
+        self.get(start..start.checked_add(len)?)
+    }
+}
+
+pub trait PointerAllocator {
+    fn allocate(&mut self, bytes: &[u8], align: usize) -> Result<u64, Error>;
+}
+
+// Returned addresses are offsets into the vector, so it can be copied into wasm linear memory as is
+impl PointerAllocator for Vec<u8> {
+    fn allocate(&mut self, bytes: &[u8], align: usize) -> Result<u64, Error> {
+        let address = self.len().next_multiple_of(align);
+
+        self.resize(address, 0);
+        self.extend_from_slice(bytes);
+
+        u64::try_from(address).map_err(|err| Error::AllocationFailed {
+            reason: err.to_string(),
+        })
+    }
+}
+
+fn read_bytes<const N: usize>(bytes: &[u8], offset: usize) -> Result<[u8; N], Error> {
+    bytes
+        .get(offset..offset + N)
+        .and_then(|slice| slice.try_into().ok())
+        .ok_or(Error::OutOfBounds {
+            offset,
+            len: N,
+            buffer_len: bytes.len(),
+        })
+}
+
+macro_rules! read_value {
+    ($target:expr, $value_type:ty, $bytes:expr, $offset:expr) => {
+        read_bytes($bytes, $offset).map(|bytes| match $target.endianness {
+            Endianness::Little => <$value_type>::from_le_bytes(bytes),
+            Endianness::Big => <$value_type>::from_be_bytes(bytes),
+        })
+    };
+}
+
+macro_rules! value_bytes {
+    ($target:expr, $value:expr) => {
+        match $target.endianness {
+            Endianness::Little => $value.to_le_bytes(),
+            Endianness::Big => $value.to_be_bytes(),
+        }
+    };
+}
+
+fn read_address(target: &TargetLayout, bytes: &[u8]) -> Result<u64, Error> {
+    match target.pointer_width {
+        4 => read_value!(target, u32, bytes, 0).map(u64::from),
+        8 => read_value!(target, u64, bytes, 0),
+        width => Err(Error::UnsupportedPointerWidth { width }),
+    }
+}
+
+fn write_address(target: &TargetLayout, bytes: &mut [u8], address: u64) -> Result<(), Error> {
+    match target.pointer_width {
+        4 => {
+            let address = u32::try_from(address).map_err(|_| Error::ValueOutOfRange {
+                value: format!("{address:#x}"),
+                type_name: "a 32-bit address".to_string(),
+            })?;
+            write_bytes(bytes, 0, &value_bytes!(target, address))
+        }
+        8 => write_bytes(bytes, 0, &value_bytes!(target, address)),
+        width => Err(Error::UnsupportedPointerWidth { width }),
+    }
+}
+
+impl Value {
+    pub fn decode<R: PointerResolver + ?Sized>(
+        schema: &Schema,
+        r#type: Type,
+        index: usize,
+        bytes: &[u8],
+        resolver: &R,
+    ) -> Result<Self, Error> {
+        let size = schema.try_type_size(r#type, index)?;
+        if bytes.len() < size {
+            return Err(Error::OutOfBounds {
+                offset: 0,
+                len: size,
+                buffer_len: bytes.len(),
+            });
+        }
+
+        match r#type {
+            Type::Primitive => Self::decode_primitive(&schema.target, index, bytes),
+            Type::Data => {
+                let data_type = &schema.types[index];
+                let mut fields = BTreeMap::new();
+
+                for field in &data_type.fields {
+                    let value = match field.bitfield {
+                        Some(bitfield) => Self::decode_bitfield(
+                            &schema.target,
+                            field.type_index,
+                            bitfield,
+                            &bytes[field.offset..],
+                        ),
+                        None => Self::decode(
+                            schema,
+                            field.r#type,
+                            field.type_index,
+                            &bytes[field.offset..],
+                            resolver,
+                        ),
+                    }
+                    .map_err(|err| err.in_member(&data_type.name, &field.name))?;
+
+                    fields.insert(field.name.clone(), value);
+                }
+
+                Ok(Self::Data(fields))
+            }
+            Type::Enum => {
+                let enum_type = &schema.enums[index];
+                let discriminant =
+                    Self::decode_primitive(&schema.target, enum_type.repr_index, bytes)?
+                        .as_integer()
+                        .ok_or(Error::InvalidEnumRepr {
+                            enum_name: enum_type.name.clone(),
+                            repr: schema
+                                .type_name(Type::Primitive, enum_type.repr_index)
+                                .to_string(),
+                        })?;
+                let variant =
+                    enum_type
+                        .variant_by_value(discriminant)
+                        .ok_or(Error::InvalidValue {
+                            type_name: enum_type.name.clone(),
+                            reason: format!("no variant with discriminant {discriminant}"),
+                        })?;
+
+                Ok(Self::Enum(variant.name.clone()))
+            }
+            Type::Alias => {
+                let alias_type = &schema.aliases[index];
+                Self::decode(
+                    schema,
+                    alias_type.inner_type,
+                    alias_type.inner_type_index,
+                    bytes,
+                    resolver,
+                )
+            }
+            Type::Flags => {
+                let flags_type = &schema.flags[index];
+                let mut bits =
+                    Self::decode_primitive(&schema.target, flags_type.repr_index, bytes)?
+                        .as_bits()
+                        .ok_or(Error::InvalidFlagsRepr {
+                            flags_name: flags_type.name.clone(),
+                            repr: schema
+                                .type_name(Type::Primitive, flags_type.repr_index)
+                                .to_string(),
+                        })?;
+
+                let mut flags = Vec::new();
+                for bit in &flags_type.bits {
+                    if bits & bit.mask() != 0 {
+                        flags.push(bit.name.clone());
+                        bits &= !bit.mask();
+                    }
+                }
+                if bits != 0 {
+                    return Err(Error::InvalidValue {
+                        type_name: flags_type.name.clone(),
+                        reason: format!("unknown bits {bits:#x} are set"),
+                    });
+                }
+
+                Ok(Self::Flags(flags))
+            }
+            Type::Union => {
+                let union_type = &schema.unions[index];
+                let tag = read_value!(schema.target, i32, bytes, 0)?;
+                let variant = usize::try_from(tag)
+                    .ok()
+                    .and_then(|tag| union_type.variants.get(tag))
+                    .ok_or(Error::InvalidValue {
+                        type_name: union_type.name.clone(),
+                        reason: format!("no variant with tag {tag}"),
+                    })?;
+
+                let data_offset = union_type.try_data_offset(schema)?;
+                let payload = variant
+                    .payload
+                    .map(|(variant_index, variant_type)| {
+                        Self::decode(
+                            schema,
+                            variant_type,
+                            variant_index,
+                            &bytes[data_offset..],
+                            resolver,
+                        )
+                    })
+                    .transpose()?;
+
+                Ok(Self::Union(variant.name.clone(), payload.map(Box::new)))
+            }
+            Type::Array => {
+                let array_type = schema.arrays[index];
+
+                Self::decode_elements(
+                    schema,
+                    array_type.inner_type,
+                    array_type.inner_type_index,
+                    array_type.len,
+                    bytes,
+                    resolver,
+                )
+                .map(Self::Array)
+            }
+            Type::Vector => {
+                let vector_type = schema.vectors[index];
+                let elements = Self::resolve_elements(
+                    schema,
+                    vector_type.inner_type,
+                    vector_type.inner_type_index,
+                    bytes,
+                    resolver,
+                )?;
+
+                Self::decode_elements(
+                    schema,
+                    vector_type.inner_type,
+                    vector_type.inner_type_index,
+                    elements.len,
+                    elements.bytes,
+                    resolver,
+                )
+                .map(Self::Vector)
+            }
+            Type::Slice => {
+                let slice_type = schema.slices[index];
+                let elements = Self::resolve_elements(
+                    schema,
+                    slice_type.inner_type,
+                    slice_type.inner_type_index,
+                    bytes,
+                    resolver,
+                )?;
+
+                Self::decode_elements(
+                    schema,
+                    slice_type.inner_type,
+                    slice_type.inner_type_index,
+                    elements.len,
+                    elements.bytes,
+                    resolver,
+                )
+                .map(Self::Slice)
+            }
+            Type::String => {
+                let u8_index = PRIMITIVES.index_of("u8").unwrap();
+                let elements =
+                    Self::resolve_elements(schema, Type::Primitive, u8_index, bytes, resolver)?;
+
+                std::str::from_utf8(elements.bytes)
+                    .map(|str| Self::String(str.to_string()))
+                    .map_err(|err| Error::InvalidValue {
+                        type_name: schema.type_name(r#type, index).to_string(),
+                        reason: err.to_string(),
+                    })
+            }
+            Type::InlineString => {
+                let inline_string_type = schema.inline_strings[index];
+                let len = read_value!(schema.target, u32, bytes, 0)? as usize;
+                if len > inline_string_type.capacity {
+                    return Err(Error::CapacityExceeded {
+                        type_name: schema.type_name(r#type, index).to_string(),
+                        capacity: inline_string_type.capacity,
+                        len,
+                    });
+                }
+
+                let data_offset = InlineStringType::data_offset();
+                std::str::from_utf8(&bytes[data_offset..data_offset + len])
+                    .map(|str| Self::String(str.to_string()))
+                    .map_err(|err| Error::InvalidValue {
+                        type_name: schema.type_name(r#type, index).to_string(),
+                        reason: err.to_string(),
+                    })
+            }
+            Type::InlineVector => {
+                let inline_vector_type = schema.inline_vectors[index];
+                let len = read_value!(schema.target, u32, bytes, 0)? as usize;
+                if len > inline_vector_type.capacity {
+                    return Err(Error::CapacityExceeded {
+                        type_name: schema.type_name(r#type, index).to_string(),
+                        capacity: inline_vector_type.capacity,
+                        len,
+                    });
+                }
+
+                Self::decode_elements(
+                    schema,
+                    inline_vector_type.inner_type,
+                    inline_vector_type.inner_type_index,
+                    len,
+                    &bytes[inline_vector_type.try_data_offset(schema)?..],
+                    resolver,
+                )
+                .map(Self::Vector)
+            }
+            Type::Pointer => read_address(&schema.target, bytes).map(Self::Pointer),
+            Type::Function => read_address(&schema.target, bytes).map(Self::Function),
+            Type::Optional => {
+                let optional_type = schema.optionals[index];
+                let is_some = match read_value!(schema.target, u8, bytes, 0)? {
+                    0 => false,
+                    1 => true,
+                    tag => {
+                        return Err(Error::InvalidValue {
+                            type_name: schema.type_name(r#type, index).to_string(),
+                            reason: format!("tag {tag} is neither 0 nor 1"),
+                        })
+                    }
+                };
+                if !is_some {
+                    return Ok(Self::Optional(None));
+                }
+
+                let data_offset = optional_type.try_data_offset(schema)?;
+                let value = Self::decode(
+                    schema,
+                    optional_type.inner_type,
+                    optional_type.inner_type_index,
+                    &bytes[data_offset..],
+                    resolver,
+                )?;
+
+                Ok(Self::Optional(Some(Box::new(value))))
+            }
+            Type::Map => {
+                let map_type = schema.maps[index];
+                let entry_size = map_type.try_entry_size(schema)?;
+                let value_offset = map_type.try_value_offset(schema)?;
+                let entries = Self::resolve_strided(schema, entry_size, bytes, resolver)?;
+
+                (0..entries.len)
+                    .map(|entry_index| {
+                        let entry_bytes = &entries.bytes[entry_index * entry_size..];
+                        let key = Self::decode(
+                            schema,
+                            map_type.key_type,
+                            map_type.key_type_index,
+                            entry_bytes,
+                            resolver,
+                        )?;
+                        let value = Self::decode(
+                            schema,
+                            map_type.value_type,
+                            map_type.value_type_index,
+                            &entry_bytes[value_offset..],
+                            resolver,
+                        )?;
+
+                        Ok((key, value))
+                    })
+                    .collect::<Result<_, Error>>()
+                    .map(Self::Map)
+            }
+        }
+    }
+
+    fn decode_primitive(target: &TargetLayout, index: usize, bytes: &[u8]) -> Result<Self, Error> {
+        let name = primitive_name(index)?;
+
+        let value = match name {
+            "bool" => match read_value!(target, u8, bytes, 0)? {
+                0 => Self::Bool(false),
+                1 => Self::Bool(true),
+                value => {
+                    return Err(Error::InvalidValue {
+                        type_name: "bool".to_string(),
+                        reason: format!("{value} is neither 0 nor 1"),
+                    })
+                }
+            },
+            "i8" => Self::I8(read_value!(target, i8, bytes, 0)?),
+            "u8" => Self::U8(read_value!(target, u8, bytes, 0)?),
+            "i16" => Self::I16(read_value!(target, i16, bytes, 0)?),
+            "u16" => Self::U16(read_value!(target, u16, bytes, 0)?),
+            "i32" => Self::I32(read_value!(target, i32, bytes, 0)?),
+            "u32" => Self::U32(read_value!(target, u32, bytes, 0)?),
+            "i64" => Self::I64(read_value!(target, i64, bytes, 0)?),
+            "u64" => Self::U64(read_value!(target, u64, bytes, 0)?),
+            "f32" => Self::F32(read_value!(target, f32, bytes, 0)?),
+            "f64" => Self::F64(read_value!(target, f64, bytes, 0)?),
+            "i128" => Self::I128(read_value!(target, i128, bytes, 0)?),
+            "u128" => Self::U128(read_value!(target, u128, bytes, 0)?),
+            "f16" => Self::F16(read_value!(target, u16, bytes, 0)?),
+            "char32" => {
+                let code_point = read_value!(target, u32, bytes, 0)?;
+                let char = char::from_u32(code_point).ok_or(Error::InvalidValue {
+                    type_name: "char32".to_string(),
+                    reason: format!("{code_point:#x} is not a valid code point"),
+                })?;
+
+                Self::Char32(char)
+            }
+            "usize" => Self::Usize(read_address(target, bytes)?),
+            "isize" => Self::Isize(match target.pointer_width {
+                4 => i64::from(read_value!(target, i32, bytes, 0)?),
+                _ => read_address(target, bytes)? as i64,
+            }),
+            name => {
+                return Err(Error::InvalidValue {
+                    type_name: name.to_string(),
+                    reason: "decoding is not supported".to_string(),
+                })
+            }
+        };
+
+        Ok(value)
+    }
+
+    pub fn as_integer(&self) -> Option<i128> {
+        match *self {
+            Self::I8(value) => Some(value.into()),
+            Self::U8(value) => Some(value.into()),
+            Self::I16(value) => Some(value.into()),
+            Self::U16(value) => Some(value.into()),
+            Self::I32(value) => Some(value.into()),
+            Self::U32(value) => Some(value.into()),
+            Self::I64(value) => Some(value.into()),
+            Self::U64(value) | Self::Usize(value) => Some(value.into()),
+            Self::I128(value) => Some(value),
+            Self::U128(value) => i128::try_from(value).ok(),
+            Self::Isize(value) => Some(value.into()),
+            _ => None,
+        }
+    }
+
+    // Raw two's complement bits, zero extended to 128 bits
+    fn as_bits(&self) -> Option<u128> {
+        match *self {
+            Self::I8(value) => Some(value as u8 as u128),
+            Self::U8(value) => Some(value.into()),
+            Self::I16(value) => Some(value as u16 as u128),
+            Self::U16(value) => Some(value.into()),
+            Self::I32(value) => Some(value as u32 as u128),
+            Self::U32(value) => Some(value.into()),
+            Self::I64(value) | Self::Isize(value) => Some(value as u64 as u128),
+            Self::U64(value) | Self::Usize(value) => Some(value.into()),
+            Self::I128(value) => Some(value as u128),
+            Self::U128(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn from_bits(name: &str, bits: u128) -> Result<Self, Error> {
+        let value = match name {
+            "i8" => Self::I8(bits as i8),
+            "u8" => Self::U8(bits as u8),
+            "i16" => Self::I16(bits as i16),
+            "u16" => Self::U16(bits as u16),
+            "i32" => Self::I32(bits as i32),
+            "u32" => Self::U32(bits as u32),
+            "i64" => Self::I64(bits as i64),
+            "u64" => Self::U64(bits as u64),
+            "i128" => Self::I128(bits as i128),
+            "u128" => Self::U128(bits),
+            "usize" => Self::Usize(bits as u64),
+            "isize" => Self::Isize(bits as i64),
+            name => {
+                return Err(Error::NotAnInteger {
+                    type_name: name.to_string(),
+                })
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn decode_bitfield(
+        target: &TargetLayout,
+        index: usize,
+        bitfield: Bitfield,
+        bytes: &[u8],
+    ) -> Result<Self, Error> {
+        let name = primitive_name(index)?;
+        let invalid_bitfield = Error::InvalidBitfield {
+            field_type: name.to_string(),
+            bit_width: bitfield.bit_width,
+        };
+        if !(1..=128).contains(&bitfield.bit_width) {
+            return Err(invalid_bitfield);
+        }
+        let storage = Self::decode_primitive(target, index, bytes)?
+            .as_bits()
+            .ok_or(invalid_bitfield)?;
+
+        let mut bits = (storage & bitfield.mask()) >> bitfield.bit_offset;
+        let sign_bit = 1u128 << (bitfield.bit_width - 1);
+        if name.starts_with('i') && bits & sign_bit != 0 {
+            bits |= !(sign_bit - 1);
+        }
+
+        Self::from_bits(name, bits)
+    }
+
+    fn encode_bitfield(
+        &self,
+        target: &TargetLayout,
+        index: usize,
+        bitfield: Bitfield,
+        bytes: &mut [u8],
+    ) -> Result<(), Error> {
+        let name = primitive_name(index)?;
+        let width = bitfield.bit_width;
+        let invalid_bitfield = Error::InvalidBitfield {
+            field_type: name.to_string(),
+            bit_width: width,
+        };
+        if !(1..=128).contains(&width) {
+            return Err(invalid_bitfield);
+        }
+        let value = self
+            .as_integer()
+            .filter(|_| Self::from_bits(name, 0).is_ok_and(|zero| zero.same_kind(self)))
+            .ok_or(Error::ValueMismatch {
+                value: format!("{self:?}"),
+                type_name: format!("a {name} bitfield"),
+            })?;
+
+        let (min, max) = if name.starts_with('i') {
+            (i128::MIN >> (128 - width), i128::MAX >> (128 - width))
+        } else {
+            (
+                0,
+                (u128::MAX >> (128 - width)).min(i128::MAX as u128) as i128,
+            )
+        };
+        if value < min || value > max {
+            return Err(Error::ValueOutOfRange {
+                value: value.to_string(),
+                type_name: format!("{width} bits"),
+            });
+        }
+
+        let storage = Self::decode_primitive(target, index, bytes)?
+            .as_bits()
+            .ok_or(invalid_bitfield)?;
+        let storage = (storage & !bitfield.mask())
+            | (((value as u128) << bitfield.bit_offset) & bitfield.mask());
+
+        Self::from_bits(name, storage)?.encode_primitive(target, index, bytes)
+    }
+
+    fn same_kind(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    pub fn from_integer(name: &str, value: i128) -> Result<Self, Error> {
+        let out_of_range = |_| Error::ValueOutOfRange {
+            value: value.to_string(),
+            type_name: name.to_string(),
+        };
+
+        let value = match name {
+            "i8" => Self::I8(value.try_into().map_err(out_of_range)?),
+            "u8" => Self::U8(value.try_into().map_err(out_of_range)?),
+            "i16" => Self::I16(value.try_into().map_err(out_of_range)?),
+            "u16" => Self::U16(value.try_into().map_err(out_of_range)?),
+            "i32" => Self::I32(value.try_into().map_err(out_of_range)?),
+            "u32" => Self::U32(value.try_into().map_err(out_of_range)?),
+            "i64" => Self::I64(value.try_into().map_err(out_of_range)?),
+            "u64" => Self::U64(value.try_into().map_err(out_of_range)?),
+            "i128" => Self::I128(value),
+            "u128" => Self::U128(value.try_into().map_err(out_of_range)?),
+            "usize" => Self::Usize(value.try_into().map_err(out_of_range)?),
+            "isize" => Self::Isize(value.try_into().map_err(out_of_range)?),
+            name => {
+                return Err(Error::NotAnInteger {
+                    type_name: name.to_string(),
+                })
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn decode_elements<R: PointerResolver + ?Sized>(
+        schema: &Schema,
+        inner_type: Type,
+        inner_type_index: usize,
+        len: usize,
+        bytes: &[u8],
+        resolver: &R,
+    ) -> Result<Vec<Self>, Error> {
+        let stride = schema.try_type_size(inner_type, inner_type_index)?;
+
+        (0..len)
+            .map(|element_index| {
+                let element_bytes =
+                    bytes
+                        .get(element_index * stride..)
+                        .ok_or(Error::OutOfBounds {
+                            offset: element_index * stride,
+                            len: stride,
+                            buffer_len: bytes.len(),
+                        })?;
+
+                Self::decode(
+                    schema,
+                    inner_type,
+                    inner_type_index,
+                    element_bytes,
+                    resolver,
+                )
+            })
+            .collect()
+    }
+
+    fn resolve_elements<'a, R: PointerResolver + ?Sized>(
+        schema: &Schema,
+        inner_type: Type,
+        inner_type_index: usize,
+        bytes: &[u8],
+        resolver: &'a R,
+    ) -> Result<ResolvedElements<'a>, Error> {
+        let stride = schema.try_type_size(inner_type, inner_type_index)?;
+
+        Self::resolve_strided(schema, stride, bytes, resolver)
+    }
+
+    fn resolve_strided<'a, R: PointerResolver + ?Sized>(
+        schema: &Schema,
+        stride: usize,
+        bytes: &[u8],
+        resolver: &'a R,
+    ) -> Result<ResolvedElements<'a>, Error> {
+        let address = read_value!(schema.target, u64, bytes, 0)?;
+        let len = read_value!(schema.target, u64, bytes, size_of::<u64>())?;
+        let len = usize::try_from(len).map_err(|_| Error::ValueOutOfRange {
+            value: len.to_string(),
+            type_name: "usize".to_string(),
+        })?;
+
+        if len == 0 {
+            return Ok(ResolvedElements { len, bytes: &[] });
+        }
+
+        let bytes_len = len.checked_mul(stride).ok_or(Error::ValueOutOfRange {
+            value: format!("{len} * {stride}"),
+            type_name: "usize".to_string(),
+        })?;
+
+        let bytes = resolver
+            .resolve(address, bytes_len)
+            .ok_or(Error::UnresolvedPointer {
+                address,
+                len: bytes_len,
+            })?;
+
+        Ok(ResolvedElements { len, bytes })
+    }
+}
+
+fn primitive_name(index: usize) -> Result<&'static str, Error> {
+    PRIMITIVES.name_of(index).ok_or(Error::IndexOutOfRange {
+        r#type: Type::Primitive,
+        index,
+    })
+}
+
+fn write_bytes(bytes: &mut [u8], offset: usize, value: &[u8]) -> Result<(), Error> {
+    let buffer_len = bytes.len();
+
+    bytes
+        .get_mut(offset..offset + value.len())
+        .ok_or(Error::OutOfBounds {
+            offset,
+            len: value.len(),
+            buffer_len,
+        })?
+        .copy_from_slice(value);
+
+    Ok(())
+}
+
+impl Value {
+    pub fn encode<A: PointerAllocator + ?Sized>(
+        &self,
+        schema: &Schema,
+        r#type: Type,
+        index: usize,
+        allocator: &mut A,
+    ) -> Result<Vec<u8>, Error> {
+        let size = schema.try_type_size(r#type, index)?;
+        let mut bytes = vec![0; size];
+
+        self.encode_into(schema, r#type, index, &mut bytes, allocator)?;
+
+        Ok(bytes)
+    }
+
+    fn encode_into<A: PointerAllocator + ?Sized>(
+        &self,
+        schema: &Schema,
+        r#type: Type,
+        index: usize,
+        bytes: &mut [u8],
+        allocator: &mut A,
+    ) -> Result<(), Error> {
+        match (r#type, self) {
+            (Type::Primitive, value) => value.encode_primitive(&schema.target, index, bytes),
+            (Type::Alias, value) => {
+                let alias_type = &schema.aliases[index];
+                value.encode_into(
+                    schema,
+                    alias_type.inner_type,
+                    alias_type.inner_type_index,
+                    bytes,
+                    allocator,
+                )
+            }
+            (Type::Data, Self::Data(fields)) => {
+                let data_type = &schema.types[index];
+
+                if let Some(unknown_field_name) = fields
+                    .keys()
+                    .find(|name| !data_type.fields.iter().any(|field| &&field.name == name))
+                {
+                    return Err(Error::UnknownMember {
+                        owner: data_type.name.clone(),
+                        member: unknown_field_name.clone(),
+                    });
+                }
+
+                for field in &data_type.fields {
+                    let value = fields.get(&field.name).ok_or(Error::MissingField {
+                        owner: data_type.name.clone(),
+                        field: field.name.clone(),
+                    })?;
+
+                    match field.bitfield {
+                        Some(bitfield) => value.encode_bitfield(
+                            &schema.target,
+                            field.type_index,
+                            bitfield,
+                            &mut bytes[field.offset..],
+                        ),
+                        None => value.encode_into(
+                            schema,
+                            field.r#type,
+                            field.type_index,
+                            &mut bytes[field.offset..],
+                            allocator,
+                        ),
+                    }
+                    .map_err(|err| err.in_member(&data_type.name, &field.name))?;
+                }
+
+                Ok(())
+            }
+            (Type::Enum, Self::Enum(variant)) => {
+                let enum_type = &schema.enums[index];
+                let variant = enum_type
+                    .variant_by_name(variant)
+                    .ok_or(Error::UnknownMember {
+                        owner: enum_type.name.clone(),
+                        member: variant.clone(),
+                    })?;
+                let repr_name = primitive_name(enum_type.repr_index)?;
+
+                Self::from_integer(repr_name, variant.value)?.encode_primitive(
+                    &schema.target,
+                    enum_type.repr_index,
+                    bytes,
+                )
+            }
+            (Type::Flags, Self::Flags(flags)) => {
+                let flags_type = &schema.flags[index];
+                let repr_name = primitive_name(flags_type.repr_index)?;
+
+                let mut bits = 0;
+                for flag in flags {
+                    bits |= flags_type
+                        .bit_by_name(flag)
+                        .ok_or(Error::UnknownMember {
+                            owner: flags_type.name.clone(),
+                            member: flag.clone(),
+                        })?
+                        .mask();
+                }
+
+                Self::from_bits(repr_name, bits)?.encode_primitive(
+                    &schema.target,
+                    flags_type.repr_index,
+                    bytes,
+                )
+            }
+            (Type::Union, Self::Union(variant_name, payload)) => {
+                let union_type = &schema.unions[index];
+                let (tag, variant) =
+                    union_type
+                        .variant_by_name(variant_name)
+                        .ok_or(Error::UnknownMember {
+                            owner: union_type.name.clone(),
+                            member: variant_name.clone(),
+                        })?;
+                let tag = i32::try_from(tag).map_err(|_| Error::ValueOutOfRange {
+                    value: tag.to_string(),
+                    type_name: "i32".to_string(),
+                })?;
+
+                write_bytes(bytes, 0, &value_bytes!(schema.target, tag))?;
+
+                let data_offset = union_type.try_data_offset(schema)?;
+                match (variant.payload, payload) {
+                    (Some((variant_index, variant_type)), Some(payload)) => payload.encode_into(
+                        schema,
+                        variant_type,
+                        variant_index,
+                        &mut bytes[data_offset..],
+                        allocator,
+                    ),
+                    (None, None) => Ok(()),
+                    (Some(_), None) => Err(Error::InvalidValue {
+                        type_name: union_type.name.clone(),
+                        reason: format!("variant {variant_name} expects a payload"),
+                    }),
+                    (None, Some(_)) => Err(Error::InvalidValue {
+                        type_name: union_type.name.clone(),
+                        reason: format!("variant {variant_name} does not take a payload"),
+                    }),
+                }
+            }
+            (Type::Array, Self::Array(elements)) => {
+                let array_type = schema.arrays[index];
+                if elements.len() != array_type.len {
+                    return Err(Error::InvalidValue {
+                        type_name: schema.type_name(r#type, index).to_string(),
+                        reason: format!(
+                            "expected {} elements, got {}",
+                            array_type.len,
+                            elements.len()
+                        ),
+                    });
+                }
+
+                Self::encode_elements(
+                    schema,
+                    array_type.inner_type,
+                    array_type.inner_type_index,
+                    elements,
+                    bytes,
+                    allocator,
+                )
+            }
+            (Type::Vector, Self::Vector(elements)) => {
+                let vector_type = schema.vectors[index];
+                let stride =
+                    schema.try_type_size(vector_type.inner_type, vector_type.inner_type_index)?;
+                let align = schema
+                    .type_align(vector_type.inner_type, vector_type.inner_type_index)
+                    .unwrap_or(1);
+
+                let mut elements_bytes = vec![0; elements.len() * stride];
+                Self::encode_elements(
+                    schema,
+                    vector_type.inner_type,
+                    vector_type.inner_type_index,
+                    elements,
+                    &mut elements_bytes,
+                    allocator,
+                )?;
+
+                Self::encode_allocation(
+                    &schema.target,
+                    &elements_bytes,
+                    elements.len(),
+                    align,
+                    bytes,
+                    allocator,
+                )
+            }
+            (Type::Slice, Self::Slice(elements)) => {
+                let slice_type = schema.slices[index];
+                let stride =
+                    schema.try_type_size(slice_type.inner_type, slice_type.inner_type_index)?;
+                let align = schema
+                    .type_align(slice_type.inner_type, slice_type.inner_type_index)
+                    .unwrap_or(1);
+
+                let mut elements_bytes = vec![0; elements.len() * stride];
+                Self::encode_elements(
+                    schema,
+                    slice_type.inner_type,
+                    slice_type.inner_type_index,
+                    elements,
+                    &mut elements_bytes,
+                    allocator,
+                )?;
+
+                Self::encode_slice_allocation(
+                    &schema.target,
+                    &elements_bytes,
+                    elements.len(),
+                    align,
+                    bytes,
+                    allocator,
+                )
+            }
+            (Type::String, Self::String(string)) => Self::encode_allocation(
+                &schema.target,
+                string.as_bytes(),
+                string.len(),
+                1,
+                bytes,
+                allocator,
+            ),
+            (Type::InlineString, Self::String(string)) => {
+                let inline_string_type = schema.inline_strings[index];
+                if string.len() > inline_string_type.capacity {
+                    return Err(Error::CapacityExceeded {
+                        type_name: schema.type_name(r#type, index).to_string(),
+                        capacity: inline_string_type.capacity,
+                        len: string.len(),
+                    });
+                }
+
+                write_bytes(bytes, 0, &value_bytes!(schema.target, string.len() as u32))?;
+                write_bytes(bytes, InlineStringType::data_offset(), string.as_bytes())
+            }
+            (Type::InlineVector, Self::Vector(elements)) => {
+                let inline_vector_type = schema.inline_vectors[index];
+                if elements.len() > inline_vector_type.capacity {
+                    return Err(Error::CapacityExceeded {
+                        type_name: schema.type_name(r#type, index).to_string(),
+                        capacity: inline_vector_type.capacity,
+                        len: elements.len(),
+                    });
+                }
+
+                write_bytes(
+                    bytes,
+                    0,
+                    &value_bytes!(schema.target, elements.len() as u32),
+                )?;
+                Self::encode_elements(
+                    schema,
+                    inline_vector_type.inner_type,
+                    inline_vector_type.inner_type_index,
+                    elements,
+                    &mut bytes[inline_vector_type.try_data_offset(schema)?..],
+                    allocator,
+                )
+            }
+            (Type::Pointer, Self::Pointer(address)) | (Type::Function, Self::Function(address)) => {
+                write_address(&schema.target, bytes, *address)
+            }
+            (Type::Optional, Self::Optional(value)) => {
+                let optional_type = schema.optionals[index];
+
+                write_bytes(bytes, 0, &[value.is_some() as u8])?;
+
+                match value {
+                    Some(value) => value.encode_into(
+                        schema,
+                        optional_type.inner_type,
+                        optional_type.inner_type_index,
+                        &mut bytes[optional_type.try_data_offset(schema)?..],
+                        allocator,
+                    ),
+                    None => Ok(()),
+                }
+            }
+            (Type::Map, Self::Map(entries)) => {
+                let map_type = schema.maps[index];
+                let entry_size = map_type.try_entry_size(schema)?;
+                let value_offset = map_type.try_value_offset(schema)?;
+
+                let mut entries = entries.iter().collect::<Vec<_>>();
+                entries.sort_by(|(key, _), (other_key, _)| {
+                    key.partial_cmp(other_key).unwrap_or(Ordering::Equal)
+                });
+                if let Some(window) = entries
+                    .windows(2)
+                    .find(|window| window[0].0.partial_cmp(&window[1].0) != Some(Ordering::Less))
+                {
+                    return Err(Error::InvalidValue {
+                        type_name: schema.type_name(r#type, index).to_string(),
+                        reason: format!(
+                            "keys {:?} and {:?} are equal or can't be ordered",
+                            window[0].0, window[1].0
+                        ),
+                    });
+                }
+
+                let mut entries_bytes = vec![0; entries.len() * entry_size];
+                for (entry_index, (key, value)) in entries.iter().enumerate() {
+                    let entry_bytes = &mut entries_bytes[entry_index * entry_size..];
+
+                    key.encode_into(
+                        schema,
+                        map_type.key_type,
+                        map_type.key_type_index,
+                        entry_bytes,
+                        allocator,
+                    )?;
+                    value.encode_into(
+                        schema,
+                        map_type.value_type,
+                        map_type.value_type_index,
+                        &mut entry_bytes[value_offset..],
+                        allocator,
+                    )?;
+                }
+
+                Self::encode_allocation(
+                    &schema.target,
+                    &entries_bytes,
+                    entries.len(),
+                    map_type.try_entry_align(schema)?,
+                    bytes,
+                    allocator,
+                )
+            }
+            (r#type, value) => Err(Error::ValueMismatch {
+                value: format!("{value:?}"),
+                type_name: schema.type_name(r#type, index).to_string(),
+            }),
+        }
+    }
+
+    fn encode_primitive(
+        &self,
+        target: &TargetLayout,
+        index: usize,
+        bytes: &mut [u8],
+    ) -> Result<(), Error> {
+        let name = primitive_name(index)?;
+
+        match (name, self) {
+            ("bool", Self::Bool(value)) => write_bytes(bytes, 0, &[*value as u8]),
+            ("i8", Self::I8(value)) => write_bytes(bytes, 0, &value_bytes!(target, value)),
+            ("u8", Self::U8(value)) => write_bytes(bytes, 0, &value_bytes!(target, value)),
+            ("i16", Self::I16(value)) => write_bytes(bytes, 0, &value_bytes!(target, value)),
+            ("u16", Self::U16(value)) => write_bytes(bytes, 0, &value_bytes!(target, value)),
+            ("i32", Self::I32(value)) => write_bytes(bytes, 0, &value_bytes!(target, value)),
+            ("u32", Self::U32(value)) => write_bytes(bytes, 0, &value_bytes!(target, value)),
+            ("i64", Self::I64(value)) => write_bytes(bytes, 0, &value_bytes!(target, value)),
+            ("u64", Self::U64(value)) => write_bytes(bytes, 0, &value_bytes!(target, value)),
+            ("f32", Self::F32(value)) => write_bytes(bytes, 0, &value_bytes!(target, value)),
+            ("f64", Self::F64(value)) => write_bytes(bytes, 0, &value_bytes!(target, value)),
+            ("i128", Self::I128(value)) => write_bytes(bytes, 0, &value_bytes!(target, value)),
+            ("u128", Self::U128(value)) => write_bytes(bytes, 0, &value_bytes!(target, value)),
+            ("f16", Self::F16(value)) => write_bytes(bytes, 0, &value_bytes!(target, value)),
+            ("char32", Self::Char32(value)) => {
+                write_bytes(bytes, 0, &value_bytes!(target, *value as u32))
+            }
+            ("usize", Self::Usize(value)) => write_address(target, bytes, *value),
+            ("isize", Self::Isize(value)) => match target.pointer_width {
+                4 => {
+                    let value = i32::try_from(*value).map_err(|_| Error::ValueOutOfRange {
+                        value: value.to_string(),
+                        type_name: "isize".to_string(),
+                    })?;
+                    write_bytes(bytes, 0, &value_bytes!(target, value))
+                }
+                _ => write_address(target, bytes, *value as u64),
+            },
+            (name, value) => Err(Error::ValueMismatch {
+                value: format!("{value:?}"),
+                type_name: name.to_string(),
+            }),
+        }
+    }
+
+    fn encode_elements<A: PointerAllocator + ?Sized>(
+        schema: &Schema,
+        inner_type: Type,
+        inner_type_index: usize,
+        elements: &[Self],
+        bytes: &mut [u8],
+        allocator: &mut A,
+    ) -> Result<(), Error> {
+        let stride = schema.try_type_size(inner_type, inner_type_index)?;
+
+        for (element_index, element) in elements.iter().enumerate() {
+            let buffer_len = bytes.len();
+            let element_bytes =
+                bytes
+                    .get_mut(element_index * stride..)
+                    .ok_or(Error::OutOfBounds {
+                        offset: element_index * stride,
+                        len: stride,
+                        buffer_len,
+                    })?;
+
+            element.encode_into(
+                schema,
+                inner_type,
+                inner_type_index,
+                element_bytes,
+                allocator,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn encode_allocation<A: PointerAllocator + ?Sized>(
+        target: &TargetLayout,
+        elements_bytes: &[u8],
+        len: usize,
+        align: usize,
+        bytes: &mut [u8],
+        allocator: &mut A,
+    ) -> Result<(), Error> {
+        Self::encode_slice_allocation(target, elements_bytes, len, align, bytes, allocator)?;
+
+        write_bytes(
+            bytes,
+            size_of::<u64>() * 2,
+            &value_bytes!(target, len as u64),
+        )
+    }
+
+    fn encode_slice_allocation<A: PointerAllocator + ?Sized>(
+        target: &TargetLayout,
+        elements_bytes: &[u8],
+        len: usize,
+        align: usize,
+        bytes: &mut [u8],
+        allocator: &mut A,
+    ) -> Result<(), Error> {
+        // Empty vectors still get a dangling, well aligned address, same as Rust's Vec::new
+        let address = if len == 0 {
+            align as u64
+        } else {
+            allocator.allocate(elements_bytes, align)?
+        };
+
+        write_bytes(bytes, 0, &value_bytes!(target, address))?;
+        write_bytes(bytes, size_of::<u64>(), &value_bytes!(target, len as u64))
+    }
+}
+
+struct ResolvedElements<'a> {
+    len: usize,
+    bytes: &'a [u8],
+}
@@ -0,0 +1,94 @@
+use binterop::compatibility::{Change, ChangeKind};
+use binterop::schema::Schema;
+use binterop::types::data::DataType;
+use binterop::types::primitives::PRIMITIVES;
+use binterop::types::r#enum::EnumType;
+
+#[test]
+pub fn compare_classifies_changes() {
+    let mut old_schema = Schema::default();
+    old_schema.types.push(DataType::from_primitives(
+        "Position",
+        &[("x", PRIMITIVES["f32"]), ("y", PRIMITIVES["f32"])],
+    ));
+    old_schema
+        .enums
+        .push(EnumType::new("Team", &["Red", "Blue"], &[]));
+
+    let mut new_schema = Schema::default();
+    new_schema.types.push(DataType::from_primitives(
+        "Position",
+        &[("y", PRIMITIVES["f32"]), ("x", PRIMITIVES["f32"])],
+    ));
+    new_schema
+        .enums
+        .push(EnumType::new("Team", &["Red", "Blue", "Green"], &[]));
+
+    let changes = old_schema.compare(&new_schema).unwrap();
+
+    assert_eq!(
+        changes,
+        vec![
+            Change {
+                type_name: "Position".to_string(),
+                kind: ChangeKind::FieldOffsetChanged {
+                    field_name: "x".to_string(),
+                    old: 0,
+                    new: 4,
+                },
+            },
+            Change {
+                type_name: "Position".to_string(),
+                kind: ChangeKind::FieldOffsetChanged {
+                    field_name: "y".to_string(),
+                    old: 4,
+                    new: 0,
+                },
+            },
+            Change {
+                type_name: "Team".to_string(),
+                kind: ChangeKind::EnumVariantAdded {
+                    variant: "Green".to_string(),
+                },
+            },
+        ]
+    );
+    assert!(changes[0].is_breaking());
+    assert!(!changes[2].is_breaking());
+}
+
+#[test]
+pub fn layout_attribute_changes_are_breaking() {
+    let position = || {
+        DataType::from_primitives(
+            "Position",
+            &[("x", PRIMITIVES["f32"]), ("y", PRIMITIVES["f32"])],
+        )
+    };
+
+    let mut old_schema = Schema::default();
+    old_schema.types.push(position());
+
+    let mut new_schema = Schema::default();
+    new_schema.types.push(position());
+    new_schema.types[0]
+        .attributes
+        .push(("align".to_string(), "16".to_string()));
+
+    let changes = old_schema.compare(&new_schema).unwrap();
+
+    assert_eq!(
+        changes,
+        vec![
+            Change {
+                type_name: "Position".to_string(),
+                kind: ChangeKind::SizeChanged { old: 8, new: 16 },
+            },
+            Change {
+                type_name: "Position".to_string(),
+                kind: ChangeKind::AlignChanged { old: 4, new: 16 },
+            },
+        ]
+    );
+    assert!(changes.iter().all(Change::is_breaking));
+}
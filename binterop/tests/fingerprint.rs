@@ -0,0 +1,91 @@
+use binterop::field::Field;
+use binterop::schema::Schema;
+use binterop::types::data::DataType;
+use binterop::types::pointer::PointerType;
+use binterop::types::primitives::PRIMITIVES;
+use binterop::types::r#enum::EnumType;
+use binterop::types::Type;
+
+fn node_schema(value_offset: usize) -> Schema {
+    let mut schema = Schema::default();
+    schema.pointers.push(PointerType::new(Type::Data, 0));
+    schema.types.push(DataType::from_fields(
+        "Node",
+        &[
+            Field::new("next", Type::Pointer, 0, 0, 0, &[]),
+            Field::new(
+                "value",
+                Type::Primitive,
+                PRIMITIVES.index_of("u32").unwrap(),
+                value_offset,
+                0,
+                &[],
+            ),
+        ],
+    ));
+
+    schema
+}
+
+#[test]
+pub fn fingerprint_is_structural() {
+    let schema = node_schema(8);
+
+    assert_eq!(
+        schema.fingerprint(Type::Data, 0),
+        node_schema(8).fingerprint(Type::Data, 0)
+    );
+    assert_ne!(
+        schema.fingerprint(Type::Data, 0),
+        node_schema(12).fingerprint(Type::Data, 0)
+    );
+}
+
+#[test]
+pub fn type_id_defaults_to_fingerprint() {
+    let mut schema = node_schema(8);
+    schema.enums.push(EnumType::new(
+        "Team",
+        &["Red", "Blue"],
+        &[("type_id".to_string(), "team".to_string())],
+    ));
+    schema.update_fingerprints().unwrap();
+
+    assert_eq!(
+        schema.type_id(Type::Data, 0),
+        format!("{:016x}", schema.types[0].fingerprint)
+    );
+    assert_eq!(schema.type_id(Type::Enum, 0), "team");
+}
+
+#[test]
+pub fn docs_dont_change_fingerprint() {
+    let mut documented = node_schema(8);
+    documented.types[0].doc = "Singly linked list node".to_string();
+    documented.types[0].fields[1].doc = "Payload of the node".to_string();
+
+    assert_eq!(
+        documented.fingerprint(Type::Data, 0),
+        node_schema(8).fingerprint(Type::Data, 0)
+    );
+}
+
+#[test]
+pub fn layout_attributes_change_fingerprint() {
+    let schema = node_schema(8);
+    let fingerprint = schema.fingerprint(Type::Data, 0);
+
+    for attribute in [("align", "32"), ("packed", "true")] {
+        let mut changed = node_schema(8);
+        changed.types[0]
+            .attributes
+            .push((attribute.0.to_string(), attribute.1.to_string()));
+
+        assert_eq!(changed.types[0].fields[1].offset, 8);
+        assert_ne!(
+            changed.fingerprint(Type::Data, 0),
+            fingerprint,
+            "{attribute:?}"
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use binterop::constant::Constant;
+use binterop::field::Field;
+use binterop::schema::Schema;
+use binterop::types::data::DataType;
+use binterop::types::primitives::PRIMITIVES;
+use binterop::types::vector::VectorType;
+use binterop::types::Type;
+use binterop::Error;
+
+fn list_schema(list_name: &str) -> Schema {
+    let mut schema = Schema::default();
+    schema.types.push(DataType::from_primitives(
+        "Item",
+        &[("id", PRIMITIVES["u32"])],
+    ));
+    schema.vectors.push(VectorType::new(Type::Data, 0));
+    schema.types.push(DataType::from_fields(
+        list_name,
+        &[Field::new("items", Type::Vector, 0, 0, 0, &[])],
+    ));
+
+    schema
+}
+
+#[test]
+pub fn merge_remaps_and_deduplicates() {
+    let mut schema = Schema::default();
+    schema.types.push(DataType::from_primitives(
+        "Header",
+        &[("len", PRIMITIVES["u64"])],
+    ));
+    schema.merge(&list_schema("Inventory")).unwrap();
+    schema.merge(&list_schema("Shop")).unwrap();
+
+    let names = schema.type_names();
+    assert_eq!(names, vec!["Header", "Item", "Inventory", "Shop"]);
+    assert_eq!(schema.vectors.len(), 1);
+    assert_eq!(schema.vectors[0].inner_type_index, 1);
+    assert_eq!(schema.types[3].fields[0].type_index, 0);
+    assert!(schema.validate().is_ok());
+}
+
+#[test]
+pub fn merge_reports_conflicting_definitions() {
+    let mut schema = list_schema("Inventory");
+    let mut other = Schema::default();
+    other.types.push(DataType::from_primitives(
+        "Item",
+        &[("id", PRIMITIVES["u64"])],
+    ));
+
+    assert_eq!(
+        schema.merge(&other),
+        Err(Error::ConflictingDefinition {
+            name: "Item".to_string()
+        })
+    );
+}
+
+#[test]
+pub fn merge_reports_conflicting_constants() {
+    let u32_index = PRIMITIVES.index_of("u32").unwrap();
+
+    let mut schema = Schema::default();
+    schema
+        .constants
+        .push(Constant::new("MAX_NAME", u32_index, 64, &[]));
+    let mut other = schema.clone();
+    other
+        .constants
+        .push(Constant::new("MAX_ITEMS", u32_index, 16, &[]));
+
+    assert_eq!(schema.merge(&other), Ok(()));
+    assert_eq!(schema.constants.len(), 2);
+
+    other.constants[0].value = 32;
+    assert_eq!(
+        schema.merge(&other),
+        Err(Error::ConflictingConstant {
+            name: "MAX_NAME".to_string()
+        })
+    );
+}
+
+#[test]
+pub fn merge_keeps_namespaces_apart() {
+    let header = |namespace: &str, len_type: &str| {
+        let mut data_type = DataType::from_primitives("Header", &[("len", PRIMITIVES[len_type])]);
+        data_type.namespace = namespace.to_string();
+
+        let mut schema = Schema::default();
+        schema.types.push(data_type);
+        schema
+    };
+
+    let mut schema = header("game.net", "u32");
+    schema.merge(&header("game.ui", "u64")).unwrap();
+    assert_eq!(
+        schema.type_names(),
+        vec!["game.net.Header", "game.ui.Header"]
+    );
+
+    assert_eq!(schema.type_data_by_name("net.Header").unwrap().index, 0);
+    assert_eq!(schema.type_data_by_name("game.ui.Header").unwrap().index, 1);
+    assert_eq!(
+        schema.type_data_by_name("Header"),
+        Err(Error::UnknownType {
+            name: "Header".to_string(),
+            suggestions: schema.type_names(),
+        })
+    );
+    assert_eq!(
+        schema.type_data_by_name("net.Haeder"),
+        Err(Error::UnknownType {
+            name: "net.Haeder".to_string(),
+            suggestions: vec!["game.net.Header".to_string()],
+        })
+    );
+
+    schema.merge(&header("", "u8")).unwrap();
+    assert_eq!(schema.type_data_by_name("Header").unwrap().index, 2);
+
+    schema.types[1].namespace = "net".to_string();
+    assert_eq!(
+        schema.type_data_by_name("net.Header"),
+        Err(Error::AmbiguousType {
+            name: "net.Header".to_string(),
+            candidates: vec!["game.net.Header".to_string(), "net.Header".to_string()],
+        })
+    );
+}
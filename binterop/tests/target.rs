@@ -0,0 +1,41 @@
+use binterop::schema::Schema;
+use binterop::target::TargetLayout;
+use binterop::types::pointer::PointerType;
+use binterop::types::primitives::PRIMITIVES;
+use binterop::types::Type;
+use binterop::value::{NullResolver, Value};
+
+#[test]
+pub fn pointer_width_follows_target() {
+    let mut schema = Schema {
+        target: TargetLayout::wasm32(),
+        ..Default::default()
+    };
+    schema.pointers.push(PointerType::new(Type::Primitive, 0));
+
+    assert_eq!(schema.type_size(Type::Pointer, 0), Some(4));
+    assert_eq!(schema.type_align(Type::Pointer, 0), Some(4));
+    assert_eq!(schema.type_size(Type::String, 0), Some(24));
+
+    let bytes = Value::Pointer(0x10)
+        .encode(&schema, Type::Pointer, 0, &mut Vec::new())
+        .unwrap();
+    assert_eq!(bytes, [0x10, 0, 0, 0]);
+}
+
+#[test]
+pub fn big_endian_round_trip() {
+    let schema = Schema {
+        target: TargetLayout::by_name("powerpc64-linux").unwrap(),
+        ..Default::default()
+    };
+    let u32_index = PRIMITIVES.index_of("u32").unwrap();
+
+    let bytes = Value::U32(0x01020304)
+        .encode(&schema, Type::Primitive, u32_index, &mut Vec::new())
+        .unwrap();
+    assert_eq!(bytes, [1, 2, 3, 4]);
+
+    let value = Value::decode(&schema, Type::Primitive, u32_index, &bytes, &NullResolver).unwrap();
+    assert_eq!(value, Value::U32(0x01020304));
+}
@@ -0,0 +1,284 @@
+use binterop::constant::Constant;
+use binterop::error::Error;
+use binterop::field::Field;
+use binterop::schema::Schema;
+use binterop::types::alias::AliasType;
+use binterop::types::data::DataType;
+use binterop::types::function::{Arg, FunctionType};
+use binterop::types::pointer::PointerType;
+use binterop::types::primitives::PRIMITIVES;
+use binterop::types::r#enum::EnumType;
+use binterop::types::union::UnionType;
+use binterop::types::vector::VectorType;
+use binterop::types::Type;
+use binterop::validation::ValidationError;
+
+#[test]
+pub fn validate_reports_dangling_references() {
+    let mut schema = Schema::default();
+    schema.types.push(DataType::from_fields(
+        "Broken",
+        &[Field::new("missing", Type::Data, 7, 0, 0, &[])],
+    ));
+    schema.unions.push(UnionType::new("Empty", &[], &[]));
+    schema.functions.push(FunctionType::new(
+        "callback".to_string(),
+        vec![Arg::new("arg".to_string(), None)],
+        None,
+    ));
+
+    let errors = schema.validate().unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec![
+            ValidationError::DanglingTypeIndex {
+                owner: "Broken".to_string(),
+                member: Some("missing".to_string()),
+                r#type: Type::Data,
+                index: 7,
+            },
+            ValidationError::EmptyUnion {
+                union_name: "Empty".to_string(),
+            },
+            ValidationError::MissingArgType {
+                function_name: "callback".to_string(),
+                arg_name: "arg".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+pub fn validate_reports_bad_layouts() {
+    let u8_index = PRIMITIVES.index_of("u8").unwrap();
+    let u32_index = PRIMITIVES.index_of("u32").unwrap();
+
+    let mut schema = Schema::default();
+    schema.types.push(DataType::from_fields(
+        "Overlapping",
+        &[
+            Field::new("a", Type::Primitive, u32_index, 0, 0, &[]),
+            Field::new("b", Type::Primitive, u8_index, 2, 0, &[]),
+            Field::new("c", Type::Primitive, u32_index, 6, 0, &[]),
+        ],
+    ));
+
+    let errors = schema.validate().unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec![
+            ValidationError::OverlappingFields {
+                type_name: "Overlapping".to_string(),
+                field_name: "a".to_string(),
+                other_field_name: "b".to_string(),
+            },
+            ValidationError::MisalignedField {
+                type_name: "Overlapping".to_string(),
+                field_name: "c".to_string(),
+                offset: 6,
+                align: 4,
+            },
+        ]
+    );
+
+    schema.types[0].fields[1].offset = 4;
+    schema.types[0].fields[2].offset = 8;
+    assert!(schema.validate().is_ok());
+}
+
+#[test]
+pub fn validate_honors_layout_attributes() {
+    let u8_index = PRIMITIVES.index_of("u8").unwrap();
+    let u32_index = PRIMITIVES.index_of("u32").unwrap();
+    let attribute = |name: &str, value: &str| vec![(name.to_string(), value.to_string())];
+
+    let mut schema = Schema::default();
+    let mut packed = DataType::from_fields(
+        "Packed",
+        &[
+            Field::new("a", Type::Primitive, u8_index, 0, 0, &[]),
+            Field::new("b", Type::Primitive, u32_index, 1, 0, &[]),
+        ],
+    );
+    packed.attributes = attribute("packed", "true");
+    schema.types.push(packed);
+
+    let mut aligned = DataType::from_fields(
+        "Aligned",
+        &[Field::new(
+            "a",
+            Type::Primitive,
+            u8_index,
+            0,
+            0,
+            &attribute("align", "8"),
+        )],
+    );
+    aligned.attributes = attribute("align", "16");
+    schema.types.push(aligned);
+
+    assert!(schema.validate().is_ok());
+    assert_eq!(
+        (
+            schema.types[0].try_size(&schema).unwrap(),
+            schema.types[0].try_align(&schema).unwrap()
+        ),
+        (5, 1)
+    );
+    assert_eq!(
+        (
+            schema.types[1].try_size(&schema).unwrap(),
+            schema.types[1].try_align(&schema).unwrap()
+        ),
+        (16, 16)
+    );
+
+    schema.types[0].fields[1].attributes = attribute("align", "4");
+    let errors = schema.validate().unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec![ValidationError::InvalidAttributes {
+            type_name: "Packed".to_string(),
+            error: Error::ConflictingAttributes {
+                first: "packed".to_string(),
+                second: "align".to_string(),
+            }
+            .in_member("Packed", "b"),
+        }]
+    );
+}
+
+#[test]
+pub fn validate_reports_recursion_without_indirection() {
+    let mut schema = Schema::default();
+    schema.types.push(DataType::from_fields(
+        "A",
+        &[Field::new("b", Type::Data, 1, 0, 0, &[])],
+    ));
+    schema.types.push(DataType::from_fields(
+        "B",
+        &[Field::new("a", Type::Data, 0, 0, 0, &[])],
+    ));
+
+    let errors = schema.validate().unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec![ValidationError::RecursiveWithoutIndirection {
+            type_name: "A".to_string(),
+            path: vec!["A.b".to_string(), "B.a".to_string()],
+        }]
+    );
+
+    schema.pointers.push(PointerType::new(Type::Data, 0));
+    schema.types[1].fields[0] = Field::new("a", Type::Pointer, 0, 0, 0, &[]);
+    assert!(schema.validate().is_ok());
+}
+
+#[test]
+pub fn validate_reports_recursive_aliases() {
+    let mut schema = Schema::default();
+    schema
+        .aliases
+        .push(AliasType::new("Path", Type::Vector, 0, false, &[]));
+    schema.vectors.push(VectorType::new(Type::Alias, 0));
+
+    let errors = schema.validate().unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec![ValidationError::RecursiveAlias {
+            type_name: "Path".to_string(),
+        }]
+    );
+
+    schema.types.push(DataType::from_fields(
+        "Node",
+        &[Field::new("children", Type::Vector, 0, 0, 0, &[])],
+    ));
+    schema.vectors[0] = VectorType::new(Type::Data, 0);
+    assert!(schema.validate().is_ok());
+}
+
+#[test]
+pub fn validate_reports_bad_enums() {
+    let u8_index = PRIMITIVES.index_of("u8").unwrap();
+    let f32_index = PRIMITIVES.index_of("f32").unwrap();
+
+    let mut schema = Schema::default();
+    schema.enums.push(EnumType::with_values(
+        "Status",
+        u8_index,
+        &[("Ok", 0), ("Alias", 0), ("NotFound", 404)],
+        &[],
+    ));
+    schema
+        .enums
+        .push(EnumType::with_values("Float", f32_index, &[("A", 0)], &[]));
+
+    let errors = schema.validate().unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec![
+            ValidationError::DuplicateEnumValue {
+                enum_name: "Status".to_string(),
+                variant: "Alias".to_string(),
+                other_variant: "Ok".to_string(),
+            },
+            ValidationError::EnumValueOutOfRange {
+                enum_name: "Status".to_string(),
+                variant: "NotFound".to_string(),
+                value: 404,
+            },
+            ValidationError::InvalidEnumRepr {
+                enum_name: "Float".to_string(),
+                repr: "f32".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+pub fn validate_reports_bad_constants() {
+    let u8_index = PRIMITIVES.index_of("u8").unwrap();
+    let i16_index = PRIMITIVES.index_of("i16").unwrap();
+    let f32_index = PRIMITIVES.index_of("f32").unwrap();
+
+    let mut schema = Schema::default();
+    schema
+        .constants
+        .push(Constant::new("MAX_NAME", u8_index, 64, &[]));
+    schema
+        .constants
+        .push(Constant::new("MIN_SCORE", i16_index, -32768, &[]));
+    schema
+        .constants
+        .push(Constant::new("MAX_NAME", u8_index, 256, &[]));
+    schema
+        .constants
+        .push(Constant::new("SCALE", f32_index, 2, &[]));
+
+    let errors = schema.validate().unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec![
+            ValidationError::ConstantValueOutOfRange {
+                constant_name: "MAX_NAME".to_string(),
+                type_name: "u8".to_string(),
+                value: 256,
+            },
+            ValidationError::DuplicateConstant {
+                constant_name: "MAX_NAME".to_string(),
+            },
+            ValidationError::InvalidConstantType {
+                constant_name: "SCALE".to_string(),
+                type_name: "f32".to_string(),
+            },
+        ]
+    );
+}
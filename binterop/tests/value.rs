@@ -0,0 +1,586 @@
+use binterop::field::{Bitfield, Field};
+use binterop::schema::Schema;
+use binterop::types::alias::AliasType;
+use binterop::types::data::DataType;
+use binterop::types::flags::FlagsType;
+use binterop::types::inline::{InlineStringType, InlineVectorType};
+use binterop::types::map::MapType;
+use binterop::types::optional::OptionalType;
+use binterop::types::primitives::PRIMITIVES;
+use binterop::types::r#enum::EnumType;
+use binterop::types::slice::SliceType;
+use binterop::types::union::{UnionType, UnionVariant};
+use binterop::types::vector::VectorType;
+use binterop::types::Type;
+use binterop::value::{NullResolver, Value};
+use binterop::{Binterop, Error};
+use std::collections::BTreeMap;
+use std::mem::{align_of, size_of};
+
+fn test_schema() -> Schema {
+    let mut schema = Schema::default();
+
+    let u8_index = PRIMITIVES.index_of("u8").unwrap();
+    let u16_index = PRIMITIVES.index_of("u16").unwrap();
+    let u32_index = PRIMITIVES.index_of("u32").unwrap();
+
+    schema
+        .enums
+        .push(EnumType::new("Team", &["Red", "Blue"], &[]));
+    schema
+        .vectors
+        .push(VectorType::new(Type::Primitive, u16_index));
+    schema.types.push(DataType::from_fields(
+        "Player",
+        &[
+            Field::new("team", Type::Enum, 0, 0, 0, &[]),
+            Field::new("level", Type::Primitive, u8_index, 4, 0, &[]),
+            Field::new("score", Type::Primitive, u32_index, 8, 0, &[]),
+            Field::new("history", Type::Vector, 0, 16, 0, &[]),
+            Field::new("name", Type::String, 0, 40, 0, &[]),
+        ],
+    ));
+
+    schema
+}
+
+#[test]
+pub fn decode_data_type() {
+    let schema = test_schema();
+
+    let mut memory = vec![0u8; 128];
+    for (index, element) in [1u16, 2, 3].into_iter().enumerate() {
+        memory[64 + index * 2..][..2].copy_from_slice(&element.to_ne_bytes());
+    }
+    memory[80..83].copy_from_slice(b"Bob");
+
+    let mut bytes = vec![0u8; 64];
+    bytes[0..4].copy_from_slice(&1i32.to_ne_bytes());
+    bytes[4] = 7;
+    bytes[8..12].copy_from_slice(&1337u32.to_ne_bytes());
+    bytes[16..24].copy_from_slice(&64u64.to_ne_bytes());
+    bytes[24..32].copy_from_slice(&3u64.to_ne_bytes());
+    bytes[40..48].copy_from_slice(&80u64.to_ne_bytes());
+    bytes[48..56].copy_from_slice(&3u64.to_ne_bytes());
+
+    let value = Value::decode(&schema, Type::Data, 0, &bytes, memory.as_slice()).unwrap();
+
+    let expected = Value::Data(BTreeMap::from([
+        ("team".to_string(), Value::Enum("Blue".to_string())),
+        ("level".to_string(), Value::U8(7)),
+        ("score".to_string(), Value::U32(1337)),
+        (
+            "history".to_string(),
+            Value::Vector(vec![Value::U16(1), Value::U16(2), Value::U16(3)]),
+        ),
+        ("name".to_string(), Value::String("Bob".to_string())),
+    ]));
+    assert_eq!(value, expected);
+}
+
+#[test]
+pub fn decode_rejects_short_buffer() {
+    let schema = test_schema();
+
+    assert!(matches!(
+        Value::decode(&schema, Type::Data, 0, &[0; 8], &NullResolver),
+        Err(Error::OutOfBounds { buffer_len: 8, .. })
+    ));
+}
+
+#[test]
+pub fn encode_round_trip() {
+    let schema = test_schema();
+
+    let value = Value::Data(BTreeMap::from([
+        ("team".to_string(), Value::Enum("Red".to_string())),
+        ("level".to_string(), Value::U8(42)),
+        ("score".to_string(), Value::U32(9001)),
+        (
+            "history".to_string(),
+            Value::Vector(vec![Value::U16(4), Value::U16(5)]),
+        ),
+        ("name".to_string(), Value::String("Alice".to_string())),
+    ]));
+
+    let mut memory = Vec::new();
+    let bytes = value.encode(&schema, Type::Data, 0, &mut memory).unwrap();
+
+    assert_eq!(bytes.len(), schema.type_size(Type::Data, 0).unwrap());
+    assert_eq!(
+        Value::decode(&schema, Type::Data, 0, &bytes, memory.as_slice()).unwrap(),
+        value
+    );
+}
+
+#[test]
+pub fn encode_matches_repr_c_layout() {
+    #[repr(C)]
+    struct Padded {
+        a: u8,
+        b: u64,
+        c: u16,
+    }
+
+    let u8_index = PRIMITIVES.index_of("u8").unwrap();
+    let u16_index = PRIMITIVES.index_of("u16").unwrap();
+    let u64_index = PRIMITIVES.index_of("u64").unwrap();
+
+    let mut schema = Schema::default();
+    schema.types.push(DataType::from_fields(
+        "Padded",
+        &[
+            Field::new("a", Type::Primitive, u8_index, 0, 0, &[]),
+            Field::new("b", Type::Primitive, u64_index, 8, 0, &[]),
+            Field::new("c", Type::Primitive, u16_index, 16, 0, &[]),
+        ],
+    ));
+
+    let value = Value::Data(BTreeMap::from([
+        ("a".to_string(), Value::U8(1)),
+        ("b".to_string(), Value::U64(2)),
+        ("c".to_string(), Value::U16(3)),
+    ]));
+    let bytes = value
+        .encode(&schema, Type::Data, 0, &mut Vec::new())
+        .unwrap();
+
+    assert_eq!(bytes.len(), size_of::<Padded>());
+
+    let padded = unsafe { bytes.as_ptr().cast::<Padded>().read_unaligned() };
+    assert_eq!((padded.a, padded.b, padded.c), (1, 2, 3));
+}
+
+#[test]
+pub fn round_trip_extended_primitives() {
+    let schema = Schema::default();
+    let values = [
+        ("i128", Value::I128(-(1 << 100))),
+        ("u128", Value::U128(u128::MAX)),
+        ("f16", Value::F16(0x3c00)),
+        ("char32", Value::Char32('ß')),
+        ("usize", Value::Usize(42)),
+        ("isize", Value::Isize(-42)),
+    ];
+
+    for (name, value) in values {
+        let index = PRIMITIVES.index_of(name).unwrap();
+        let bytes = value
+            .encode(&schema, Type::Primitive, index, &mut Vec::new())
+            .unwrap();
+
+        assert_eq!(Some(bytes.len()), schema.type_size(Type::Primitive, index));
+        assert_eq!(
+            Value::decode(&schema, Type::Primitive, index, &bytes, &NullResolver),
+            Ok(value)
+        );
+    }
+}
+
+#[test]
+pub fn enum_uses_explicit_values_and_repr() {
+    let u16_index = PRIMITIVES.index_of("u16").unwrap();
+
+    let mut schema = Schema::default();
+    schema.enums.push(EnumType::with_values(
+        "HttpStatus",
+        u16_index,
+        &[("Ok", 200), ("NotFound", 404)],
+        &[],
+    ));
+    assert_eq!(schema.type_size(Type::Enum, 0), Some(2));
+
+    let bytes = Value::Enum("NotFound".to_string())
+        .encode(&schema, Type::Enum, 0, &mut Vec::new())
+        .unwrap();
+    assert_eq!(bytes, 404u16.to_le_bytes());
+
+    let value = Value::decode(&schema, Type::Enum, 0, &bytes, &NullResolver).unwrap();
+    assert_eq!(value, Value::Enum("NotFound".to_string()));
+
+    assert!(Value::decode(&schema, Type::Enum, 0, &[0, 0], &NullResolver).is_err());
+}
+
+#[test]
+pub fn union_variants_share_payload_types() {
+    let f32_index = PRIMITIVES.index_of("f32").unwrap();
+
+    let mut schema = Schema::default();
+    schema.unions.push(UnionType::new(
+        "Command",
+        &[
+            UnionVariant::new("move", Some((f32_index, Type::Primitive))),
+            UnionVariant::new("look", Some((f32_index, Type::Primitive))),
+            UnionVariant::new("quit", None),
+        ],
+        &[],
+    ));
+    assert_eq!(schema.type_size(Type::Union, 0), Some(8));
+
+    let look = Value::Union("look".to_string(), Some(Box::new(Value::F32(0.5))));
+    let bytes = look
+        .encode(&schema, Type::Union, 0, &mut Vec::new())
+        .unwrap();
+    assert_eq!(bytes[..4], 1i32.to_le_bytes());
+    assert_eq!(
+        Value::decode(&schema, Type::Union, 0, &bytes, &NullResolver).unwrap(),
+        look
+    );
+
+    let quit = Value::Union("quit".to_string(), None);
+    let bytes = quit
+        .encode(&schema, Type::Union, 0, &mut Vec::new())
+        .unwrap();
+    assert_eq!(
+        Value::decode(&schema, Type::Union, 0, &bytes, &NullResolver).unwrap(),
+        quit
+    );
+
+    assert!(
+        Value::Union("quit".to_string(), Some(Box::new(Value::F32(0.0))))
+            .encode(&schema, Type::Union, 0, &mut Vec::new())
+            .is_err()
+    );
+}
+
+#[test]
+pub fn optional_matches_std_option_layout() {
+    let u8_index = PRIMITIVES.index_of("u8").unwrap();
+    let f64_index = PRIMITIVES.index_of("f64").unwrap();
+
+    let mut schema = Schema::default();
+    schema
+        .optionals
+        .push(OptionalType::new(Type::Primitive, u8_index));
+    schema
+        .optionals
+        .push(OptionalType::new(Type::Primitive, f64_index));
+
+    assert_eq!(
+        schema.type_size(Type::Optional, 0),
+        Some(size_of::<binterop::std::Option<u8>>())
+    );
+    assert_eq!(
+        schema.type_size(Type::Optional, 1),
+        Some(size_of::<binterop::std::Option<f64>>())
+    );
+    assert_eq!(
+        schema.type_align(Type::Optional, 1),
+        Some(align_of::<binterop::std::Option<f64>>())
+    );
+
+    let some = Value::Optional(Some(Box::new(Value::F64(1.5))));
+    let bytes = some
+        .encode(&schema, Type::Optional, 1, &mut Vec::new())
+        .unwrap();
+    assert_eq!(bytes[0], 1);
+    assert_eq!(bytes[8..], 1.5f64.to_le_bytes());
+    assert_eq!(
+        Value::decode(&schema, Type::Optional, 1, &bytes, &NullResolver).unwrap(),
+        some
+    );
+
+    let none = Value::Optional(None);
+    let bytes = none
+        .encode(&schema, Type::Optional, 1, &mut Vec::new())
+        .unwrap();
+    assert_eq!(
+        Value::decode(&schema, Type::Optional, 1, &bytes, &NullResolver).unwrap(),
+        none
+    );
+}
+
+#[test]
+pub fn map_entries_are_sorted_by_key() {
+    let u8_index = PRIMITIVES.index_of("u8").unwrap();
+    let u32_index = PRIMITIVES.index_of("u32").unwrap();
+
+    let mut schema = Schema::default();
+    binterop::std::Map::<u8, u32>::binterop_type(&mut schema);
+    assert_eq!(
+        schema.maps,
+        [MapType::new(
+            Type::Primitive,
+            u8_index,
+            Type::Primitive,
+            u32_index
+        )]
+    );
+    assert_eq!(
+        schema.type_size(Type::Map, 0),
+        Some(size_of::<binterop::std::Map<u8, u32>>())
+    );
+    assert_eq!(schema.type_data_by_name("{u8: u32}").unwrap().index, 0);
+    assert_eq!(schema.type_name(Type::Map, 0), "{u8: u32}");
+    assert_eq!(schema.maps[0].try_entry_size(&schema).unwrap(), 8);
+    assert_eq!(schema.maps[0].try_value_offset(&schema).unwrap(), 4);
+
+    let value = Value::Map(vec![
+        (Value::U8(7), Value::U32(700)),
+        (Value::U8(2), Value::U32(200)),
+    ]);
+    let mut memory = Vec::new();
+    let bytes = value.encode(&schema, Type::Map, 0, &mut memory).unwrap();
+
+    assert_eq!(
+        Value::decode(&schema, Type::Map, 0, &bytes, memory.as_slice()).unwrap(),
+        Value::Map(vec![
+            (Value::U8(2), Value::U32(200)),
+            (Value::U8(7), Value::U32(700)),
+        ])
+    );
+
+    let duplicate = Value::Map(vec![
+        (Value::U8(1), Value::U32(1)),
+        (Value::U8(1), Value::U32(2)),
+    ]);
+    assert!(duplicate
+        .encode(&schema, Type::Map, 0, &mut Vec::new())
+        .is_err());
+}
+
+#[test]
+pub fn std_map_lookup() {
+    let map = binterop::std::Map::from(vec![
+        (binterop::std::String::from("b"), 2u32),
+        (binterop::std::String::from("a"), 1u32),
+    ]);
+
+    assert_eq!(map.get(&"a".into()), Some(&1));
+    assert_eq!(map.get(&"b".into()), Some(&2));
+    assert_eq!(map.get(&"c".into()), None);
+    assert_eq!(
+        map.iter()
+            .map(|entry| entry.key.as_str().unwrap())
+            .collect::<Vec<_>>(),
+        ["a", "b"]
+    );
+}
+
+#[test]
+pub fn slice_has_no_capacity() {
+    let u16_index = PRIMITIVES.index_of("u16").unwrap();
+
+    let mut schema = Schema::default();
+    binterop::std::Slice::<u16>::binterop_type(&mut schema);
+    assert_eq!(schema.slices, [SliceType::new(Type::Primitive, u16_index)]);
+    assert_eq!(
+        schema.type_size(Type::Slice, 0),
+        Some(size_of::<binterop::std::Slice<u16>>())
+    );
+    assert_eq!(schema.type_name(Type::Slice, 0), "&<u16>");
+
+    let value = Value::Slice(vec![Value::U16(1), Value::U16(2), Value::U16(3)]);
+    let mut memory = Vec::new();
+    let bytes = value.encode(&schema, Type::Slice, 0, &mut memory).unwrap();
+    assert_eq!(bytes.len(), 16);
+    assert_eq!(bytes[8..], 3u64.to_le_bytes());
+    assert_eq!(
+        Value::decode(&schema, Type::Slice, 0, &bytes, memory.as_slice()).unwrap(),
+        value
+    );
+
+    let elements = [4u16, 5, 6];
+    let slice = binterop::std::Slice::from(elements.as_slice());
+    assert_eq!(slice.as_slice(), elements);
+
+    let raw_slice =
+        unsafe { binterop::std::Slice::<u16>::from_raw_parts(slice.ptr(), slice.len() as u64) };
+    assert_eq!(raw_slice.as_slice(), elements);
+}
+
+#[test]
+pub fn inline_types_are_copy_and_heap_free() {
+    let u16_index = PRIMITIVES.index_of("u16").unwrap();
+
+    let mut schema = Schema::default();
+    let string_type_data = schema.type_data_by_name("String<5>").unwrap();
+    let vector_type_data = schema.type_data_by_name("<u16:3>").unwrap();
+    assert_eq!(schema.inline_strings, [InlineStringType::new(5)]);
+    assert_eq!(
+        schema.inline_vectors,
+        [InlineVectorType::new(Type::Primitive, u16_index, 3)]
+    );
+    assert!(string_type_data.is_copy && vector_type_data.is_copy);
+    assert_eq!(
+        string_type_data.size,
+        size_of::<binterop::std::InlineString<5>>()
+    );
+    assert_eq!(
+        vector_type_data.size,
+        size_of::<binterop::std::InlineVector<u16, 3>>()
+    );
+    assert_eq!(
+        schema.type_align(Type::InlineVector, 0),
+        Some(align_of::<binterop::std::InlineVector<u16, 3>>())
+    );
+    assert_eq!(schema.type_name(Type::InlineVector, 0), "<u16:3>");
+    assert!(!schema.type_data_by_name("<u16>").unwrap().is_copy);
+
+    let value = Value::String("héllo".to_string());
+    assert!(value
+        .encode(&schema, Type::InlineString, 0, &mut Vec::new())
+        .is_err());
+
+    let value = Value::String("hey".to_string());
+    let bytes = value
+        .encode(&schema, Type::InlineString, 0, &mut Vec::new())
+        .unwrap();
+    let string = unsafe {
+        std::ptr::read_unaligned(bytes.as_ptr() as *const binterop::std::InlineString<5>)
+    };
+    assert_eq!(string.as_str(), Ok("hey"));
+    assert_eq!(
+        Value::decode(&schema, Type::InlineString, 0, &bytes, &NullResolver).unwrap(),
+        value
+    );
+
+    let mut vector = binterop::std::InlineVector::<u16, 3>::new();
+    assert!(vector.push(1).is_ok() && vector.push(2).is_ok() && vector.push(3).is_ok());
+    assert_eq!(vector.push(4), Err(4));
+    assert_eq!(vector.pop(), Some(3));
+    assert_eq!(vector.as_slice(), [1, 2]);
+
+    let value = Value::Vector(vec![Value::U16(1), Value::U16(2)]);
+    let bytes = value
+        .encode(&schema, Type::InlineVector, 0, &mut Vec::new())
+        .unwrap();
+    assert_eq!(
+        Value::decode(&schema, Type::InlineVector, 0, &bytes, &NullResolver).unwrap(),
+        value
+    );
+
+    let mut invalid = binterop::std::InlineString::<5>::try_from("ab").unwrap();
+    invalid.bytes[0] = 0xff;
+    assert!(invalid.as_str().is_err());
+}
+
+#[test]
+pub fn flags_and_bitfields_round_trip() {
+    let u8_index = PRIMITIVES.index_of("u8").unwrap();
+    let i8_index = PRIMITIVES.index_of("i8").unwrap();
+    let u32_index = PRIMITIVES.index_of("u32").unwrap();
+
+    let mut schema = Schema::default();
+    schema.flags.push(FlagsType::new(
+        "Permissions",
+        u32_index,
+        &[("Read", 0), ("Write", 1), ("Exec", 7)],
+        &[],
+    ));
+    schema.types.push(DataType::from_fields(
+        "FileHeader",
+        &[
+            Field::new("mode", Type::Primitive, u8_index, 0, 0, &[])
+                .with_bitfield(Bitfield::new(0, 3)),
+            Field::new("kind", Type::Primitive, u8_index, 0, 0, &[])
+                .with_bitfield(Bitfield::new(3, 5)),
+            Field::new("level", Type::Primitive, i8_index, 1, 2, &[])
+                .with_bitfield(Bitfield::new(0, 4)),
+            Field::new("perms", Type::Flags, 0, 4, 0, &[]),
+        ],
+    ));
+    assert!(schema.validate().is_ok());
+
+    let value = Value::Data(BTreeMap::from([
+        ("mode".to_string(), Value::U8(5)),
+        ("kind".to_string(), Value::U8(30)),
+        ("level".to_string(), Value::I8(-3)),
+        (
+            "perms".to_string(),
+            Value::Flags(vec!["Read".to_string(), "Exec".to_string()]),
+        ),
+    ]));
+    let bytes = value
+        .encode(&schema, Type::Data, 0, &mut Vec::new())
+        .unwrap();
+    assert_eq!(bytes[0], 5 | (30 << 3));
+    assert_eq!(bytes[1] & 0xf, 0xd);
+    assert_eq!(bytes[4..8], 0x81u32.to_ne_bytes());
+    assert_eq!(
+        Value::decode(&schema, Type::Data, 0, &bytes, &NullResolver).unwrap(),
+        value
+    );
+
+    let overflowing = Value::Data(BTreeMap::from([
+        ("mode".to_string(), Value::U8(8)),
+        ("kind".to_string(), Value::U8(0)),
+        ("level".to_string(), Value::I8(0)),
+        ("perms".to_string(), Value::Flags(Vec::new())),
+    ]));
+    assert!(overflowing
+        .encode(&schema, Type::Data, 0, &mut Vec::new())
+        .is_err());
+}
+
+#[test]
+pub fn aliases_are_transparent() {
+    let f32_index = PRIMITIVES.index_of("f32").unwrap();
+    let u64_index = PRIMITIVES.index_of("u64").unwrap();
+
+    let mut schema = Schema::default();
+    schema.aliases.push(AliasType::new(
+        "Meters",
+        Type::Primitive,
+        f32_index,
+        false,
+        &[],
+    ));
+    schema.aliases.push(AliasType::new(
+        "EntityId",
+        Type::Primitive,
+        u64_index,
+        true,
+        &[],
+    ));
+    schema.types.push(DataType::from_fields(
+        "Waypoint",
+        &[
+            Field::new("owner", Type::Alias, 1, 0, 0, &[]),
+            Field::new("distance", Type::Alias, 0, 8, 4, &[]),
+        ],
+    ));
+    assert!(schema.validate().is_ok());
+
+    let type_data = schema.type_data_by_name("EntityId").unwrap();
+    assert_eq!((type_data.r#type, type_data.size), (Type::Alias, 8));
+    assert!(type_data.is_copy);
+    assert_eq!(schema.type_name(Type::Alias, 0), "Meters");
+    assert_eq!(schema.type_size(Type::Data, 0), Some(16));
+
+    let value = Value::Data(BTreeMap::from([
+        ("owner".to_string(), Value::U64(42)),
+        ("distance".to_string(), Value::F32(1.5)),
+    ]));
+    let bytes = value
+        .encode(&schema, Type::Data, 0, &mut Vec::new())
+        .unwrap();
+    assert_eq!(bytes[8..12], 1.5f32.to_ne_bytes());
+    assert_eq!(
+        Value::decode(&schema, Type::Data, 0, &bytes, &NullResolver).unwrap(),
+        value
+    );
+}
+
+#[test]
+pub fn integers_are_range_checked() {
+    assert_eq!(Value::from_integer("u8", 255), Ok(Value::U8(255)));
+    assert_eq!(Value::from_integer("i16", -4), Ok(Value::I16(-4)));
+    assert_eq!(
+        Value::from_integer("u8", 300),
+        Err(Error::ValueOutOfRange {
+            value: "300".to_string(),
+            type_name: "u8".to_string(),
+        })
+    );
+    assert!(Value::from_integer("u32", -1).is_err());
+    assert_eq!(
+        Value::from_integer("f32", 1),
+        Err(Error::NotAnInteger {
+            type_name: "f32".to_string(),
+        })
+    );
+
+    assert_eq!(Value::U64(u64::MAX).as_integer(), Some(u64::MAX.into()));
+    assert_eq!(Value::Bool(true).as_integer(), None);
+}
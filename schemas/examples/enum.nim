@@ -1,7 +1,15 @@
-import helpers
+import binterop
 
-type Color = enum
-  Red
-  Green
-  Blue
+when sizeof(pointer) != 8 or cpuEndian != littleEndian:
+  {.error: "Generated for x86_64-linux layout".}
+
+type Color* {.size: sizeof(int32).} = enum
+  Red = 0
+  Green = 1
+  Blue = 2
+
+type HttpStatus* {.size: sizeof(uint16).} = enum
+  Ok = 200
+  Created = 201
+  NotFound = 404
 
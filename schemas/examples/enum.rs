@@ -1,10 +1,21 @@
-mod helpers;
+const _: () = assert!(
+	cfg!(target_pointer_width = "64") && cfg!(target_endian = "little"),
+	"Generated for x86_64-linux layout"
+);
 
-#[repr(C)]
+#[repr(i32)]
 #[derive(Copy, Clone, Debug)]
 pub enum Color {
-	Red,
-	Green,
-	Blue,
+	Red = 0,
+	Green = 1,
+	Blue = 2,
+}
+
+#[repr(u16)]
+#[derive(Copy, Clone, Debug)]
+pub enum HttpStatus {
+	Ok = 200,
+	Created = 201,
+	NotFound = 404,
 }
 
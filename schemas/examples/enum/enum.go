@@ -2,13 +2,24 @@ package enum
 
 import (
 	"binterop/helpers"
+	"unsafe"
 )
 var _ = binterop.NewVector[byte]()
 
+// Fails to compile unless pointers are 8 bytes wide
+var _ [0]struct{} = [unsafe.Sizeof(uintptr(0)) - 8]struct{}{}
+
 type Color int32
 const (
-	Red Color = iota
-	Green
-	Blue
+	Red Color = 0
+	Green Color = 1
+	Blue Color = 2
+)
+
+type HttpStatus uint16
+const (
+	Ok HttpStatus = 200
+	Created HttpStatus = 201
+	NotFound HttpStatus = 404
 )
 
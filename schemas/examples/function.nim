@@ -1,13 +1,16 @@
-import helpers
+import binterop
 
-type SomeOtherType = object
+when sizeof(pointer) != 8 or cpuEndian != littleEndian:
+  {.error: "Generated for x86_64-linux layout".}
+
+type SomeOtherType* = object
   a*: float64
 
-type Test = object
+type Test* = object
   b*: uint32
   a*: array[69, uint8]
 
-type SomeStruct = object
+type SomeStruct* = object
   some_bool*: bool
   some_uint*: uint16
   some_float*: float32
@@ -19,6 +22,11 @@ type SomeStruct = object
   some_string*: String
   some_float_array*: array[10, float32]
 
-type add = proc(a: int32, b: int32): float64
-type passByPointer = proc(pointer: ptr SomeOtherType)
-type vecSum = proc(vec: Vector[int32]): int64
+type Packet* = object
+  header*: array[4, uint8]
+  payload*: SliceView[uint8]
+  others*: SliceView[SomeOtherType]
+
+type add* = proc(a: int32, b: int32): float64
+type passByPointer* = proc(pointer: ptr SomeOtherType)
+type vecSum* = proc(vec: Vector[int32]): int64
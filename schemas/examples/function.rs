@@ -1,4 +1,7 @@
-mod helpers;
+const _: () = assert!(
+	cfg!(target_pointer_width = "64") && cfg!(target_endian = "little"),
+	"Generated for x86_64-linux layout"
+);
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
@@ -23,11 +26,19 @@ pub struct SomeStruct {
 	pub some_pointer: *mut SomeOtherType,
 	pub some_other_type: SomeOtherType,
 	pub some_other_type_array: [SomeOtherType; 3],
-	pub some_other_type_vector: helpers::Vector<SomeOtherType>,
-	pub some_string: String,
+	pub some_other_type_vector: binterop::std::Vector<SomeOtherType>,
+	pub some_string: binterop::std::String,
 	pub some_float_array: [f32; 10],
 }
 
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct Packet<'a> {
+	pub header: [u8; 4],
+	pub payload: binterop::std::Slice<'a, u8>,
+	pub others: binterop::std::Slice<'a, SomeOtherType>,
+}
+
 type add = extern "C" fn(a: i32, b: i32) -> f64;
 type pass_by_pointer = extern "C" fn(pointer: *mut SomeOtherType);
-type vec_sum = extern "C" fn(vec: helpers::Vector<i32>) -> i64;
+type vec_sum = extern "C" fn(vec: binterop::std::Vector<i32>) -> i64;
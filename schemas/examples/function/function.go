@@ -2,9 +2,13 @@ package function
 
 import (
 	"binterop/helpers"
+	"unsafe"
 )
 var _ = binterop.NewVector[byte]()
 
+// Fails to compile unless pointers are 8 bytes wide
+var _ [0]struct{} = [unsafe.Sizeof(uintptr(0)) - 8]struct{}{}
+
 type SomeOtherType struct {
 	A float64
 }
@@ -27,6 +31,12 @@ type SomeStruct struct {
 	SomeFloatArray [10]float32
 }
 
+type Packet struct {
+	Header [4]uint8
+	Payload binterop.Slice[uint8]
+	Others binterop.Slice[SomeOtherType]
+}
+
 type Add func(a int32, b int32) float64
 type PassByPointer func(pointer *SomeOtherType)
 type VecSum func(vec binterop.Vector[int32]) int64
@@ -1,6 +1,23 @@
-import helpers
+import binterop
 
-type Recursive = object
-  recursive*: bool
+when sizeof(pointer) != 8 or cpuEndian != littleEndian:
+  {.error: "Generated for x86_64-linux layout".}
+
+type Recursive* = object
   depth*: uint32
+  some_float*: float32
+  recursive*: ptr Recursive
+  recursive2*: Vector[Recursive]
+
+type TreeVariant* {.size: sizeof(int32).} = enum
+  LeafVariant = 0
+  ChildrenVariant = 1
+
+type Tree* = object
+  case variant: TreeVariant
+  of LeafVariant:
+    Leaf*: float32
+  of ChildrenVariant:
+    Children*: Vector[Tree]
+
 
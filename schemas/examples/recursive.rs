@@ -1,9 +1,34 @@
-mod helpers;
+const _: () = assert!(
+	cfg!(target_pointer_width = "64") && cfg!(target_endian = "little"),
+	"Generated for x86_64-linux layout"
+);
 
 #[repr(C)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct Recursive {
-	pub recursive: bool,
 	pub depth: u32,
+	pub some_float: f32,
+	pub recursive: *mut Recursive,
+	pub recursive2: binterop::std::Vector<Recursive>,
+}
+
+#[repr(i32)]
+#[derive(Copy, Clone, Debug)]
+pub enum TreeVariant {
+	Leaf = 0,
+	Children = 1,
+}
+
+#[repr(C)]
+pub union TreeUnion {
+	pub leaf: std::mem::ManuallyDrop<f32>,
+	pub children: std::mem::ManuallyDrop<binterop::std::Vector<Tree>>,
+}
+
+#[repr(C)]
+#[derive(Clone, Debug)]
+pub struct Tree {
+	pub variant: TreeVariant,
+	pub data: TreeUnion
 }
 
@@ -2,11 +2,29 @@ package recursive
 
 import (
 	"binterop/helpers"
+	"unsafe"
 )
 var _ = binterop.NewVector[byte]()
 
+// Fails to compile unless pointers are 8 bytes wide
+var _ [0]struct{} = [unsafe.Sizeof(uintptr(0)) - 8]struct{}{}
+
 type Recursive struct {
-	Recursive bool
 	Depth uint32
+	SomeFloat float32
+	Recursive *Recursive
+	Recursive2 binterop.Vector[Recursive]
+}
+
+type TreeVariant int32
+const (
+	LeafVariant TreeVariant = 0
+	ChildrenVariant TreeVariant = 1
+)
+
+type Tree struct {
+  Variant TreeVariant
+  _ [4]byte
+  Data [24]byte
 }
 
@@ -1,13 +1,16 @@
-import helpers
+import binterop
 
-type SomeOtherType = object
+when sizeof(pointer) != 8 or cpuEndian != littleEndian:
+  {.error: "Generated for x86_64-linux layout".}
+
+type SomeOtherType* = object
   a*: float64
 
-type Test = object
+type Test* = object
   b*: uint32
   a*: array[69, uint8]
 
-type SomeStruct = object
+type SomeStruct* = object
   some_bool*: bool
   some_uint*: uint16
   some_float*: float32
@@ -19,20 +22,45 @@ type SomeStruct = object
   some_string*: String
   some_float_array*: array[10, float32]
 
-type Color = enum
-  Red
-  Green
-  Blue
+type Packet* = object
+  header*: array[4, uint8]
+  payload*: SliceView[uint8]
+  others*: SliceView[SomeOtherType]
+
+type Color* {.size: sizeof(int32).} = enum
+  Red = 0
+  Green = 1
+  Blue = 2
+
+type HttpStatus* {.size: sizeof(uint16).} = enum
+  Ok = 200
+  Created = 201
+  NotFound = 404
 
-type SomeUnionVariant = enum
-  ColorVariant
-  SomeStructVariant
+type SomeUnionVariant* {.size: sizeof(int32).} = enum
+  ColorVariant = 0
+  SomeStructVariant = 1
 
-type SomeUnion = object
+type SomeUnion* = object
   case variant: SomeUnionVariant
   of ColorVariant:
-    Color*: Color
+    color*: Color
   of SomeStructVariant:
-    SomeStruct*: SomeStruct
+    someStruct*: SomeStruct
+
+
+type CommandVariant* {.size: sizeof(int32).} = enum
+  MoveVariant = 0
+  LookVariant = 1
+  QuitVariant = 2
+
+type Command* = object
+  case variant: CommandVariant
+  of MoveVariant:
+    move*: SomeOtherType
+  of LookVariant:
+    look*: SomeOtherType
+  of QuitVariant:
+    discard
 
 
@@ -1,4 +1,7 @@
-mod helpers;
+const _: () = assert!(
+	cfg!(target_pointer_width = "64") && cfg!(target_endian = "little"),
+	"Generated for x86_64-linux layout"
+);
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
@@ -23,24 +26,40 @@ pub struct SomeStruct {
 	pub some_pointer: *mut SomeOtherType,
 	pub some_other_type: SomeOtherType,
 	pub some_other_type_array: [SomeOtherType; 3],
-	pub some_other_type_vector: helpers::Vector<SomeOtherType>,
-	pub some_string: String,
+	pub some_other_type_vector: binterop::std::Vector<SomeOtherType>,
+	pub some_string: binterop::std::String,
 	pub some_float_array: [f32; 10],
 }
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
+pub struct Packet<'a> {
+	pub header: [u8; 4],
+	pub payload: binterop::std::Slice<'a, u8>,
+	pub others: binterop::std::Slice<'a, SomeOtherType>,
+}
+
+#[repr(i32)]
+#[derive(Copy, Clone, Debug)]
 pub enum Color {
-	Red,
-	Green,
-	Blue,
+	Red = 0,
+	Green = 1,
+	Blue = 2,
 }
 
-#[repr(C)]
+#[repr(u16)]
+#[derive(Copy, Clone, Debug)]
+pub enum HttpStatus {
+	Ok = 200,
+	Created = 201,
+	NotFound = 404,
+}
+
+#[repr(i32)]
 #[derive(Copy, Clone, Debug)]
 pub enum SomeUnionVariant {
-	Color,
-	SomeStruct,
+	Color = 0,
+	SomeStruct = 1,
 }
 
 #[repr(C)]
@@ -56,3 +75,24 @@ pub struct SomeUnion {
 	pub data: SomeUnionUnion
 }
 
+#[repr(i32)]
+#[derive(Copy, Clone, Debug)]
+pub enum CommandVariant {
+	Move = 0,
+	Look = 1,
+	Quit = 2,
+}
+
+#[repr(C)]
+pub union CommandUnion {
+	pub r#move: std::mem::ManuallyDrop<SomeOtherType>,
+	pub look: std::mem::ManuallyDrop<SomeOtherType>,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct Command {
+	pub variant: CommandVariant,
+	pub data: CommandUnion
+}
+
@@ -2,9 +2,13 @@ package union
 
 import (
 	"binterop/helpers"
+	"unsafe"
 )
 var _ = binterop.NewVector[byte]()
 
+// Fails to compile unless pointers are 8 bytes wide
+var _ [0]struct{} = [unsafe.Sizeof(uintptr(0)) - 8]struct{}{}
+
 type SomeOtherType struct {
 	A float64
 }
@@ -27,21 +31,48 @@ type SomeStruct struct {
 	SomeFloatArray [10]float32
 }
 
+type Packet struct {
+	Header [4]uint8
+	Payload binterop.Slice[uint8]
+	Others binterop.Slice[SomeOtherType]
+}
+
 type Color int32
 const (
-	Red Color = iota
-	Green
-	Blue
+	Red Color = 0
+	Green Color = 1
+	Blue Color = 2
+)
+
+type HttpStatus uint16
+const (
+	Ok HttpStatus = 200
+	Created HttpStatus = 201
+	NotFound HttpStatus = 404
 )
 
 type SomeUnionVariant int32
 const (
-	ColorVariant SomeUnionVariant = iota
-	SomeStructVariant
+	ColorVariant SomeUnionVariant = 0
+	SomeStructVariant SomeUnionVariant = 1
 )
 
 type SomeUnion struct {
   Variant SomeUnionVariant
-  Data [143]byte
+  _ [4]byte
+  Data [144]byte
+}
+
+type CommandVariant int32
+const (
+	MoveVariant CommandVariant = 0
+	LookVariant CommandVariant = 1
+	QuitVariant CommandVariant = 2
+)
+
+type Command struct {
+  Variant CommandVariant
+  _ [4]byte
+  Data [8]byte
 }
 